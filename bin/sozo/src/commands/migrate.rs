@@ -13,6 +13,7 @@ use starknet::core::utils::parse_cairo_short_string;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, Provider, ProviderError};
 use tracing::trace;
+use url::Url;
 
 use super::options::account::{AccountOptions, SozoAccount};
 use super::options::starknet::StarknetOptions;
@@ -43,6 +44,20 @@ pub enum MigrateCommand {
     Apply {
         #[command(flatten)]
         transaction: TransactionOptions,
+
+        #[arg(long)]
+        #[arg(help = "Print a JSON summary of the migration once applied.")]
+        #[arg(long_help = "Print a JSON summary of the migration once applied, with the world \
+                           address, the deployed contracts, the registered models and the \
+                           transactions sent. Use with `--quiet` to only output the summary.")]
+        json: bool,
+
+        #[arg(long)]
+        #[arg(help = "Deploy a fresh world, ignoring the existing remote world state.")]
+        #[arg(long_help = "Deploy a fresh world at the address derived from the seed, ignoring \
+                           the existing remote world state. Fails if this address is already \
+                           occupied instead of upgrading the world in place.")]
+        fresh: bool,
     },
 }
 
@@ -54,7 +69,11 @@ impl MigrateArgs {
         account: AccountOptions,
     ) -> Self {
         Self {
-            command: MigrateCommand::Apply { transaction: TransactionOptions::init_wait() },
+            command: MigrateCommand::Apply {
+                transaction: TransactionOptions::init_wait(),
+                json: false,
+                fresh: false,
+            },
             world,
             starknet,
             account,
@@ -102,17 +121,19 @@ impl MigrateArgs {
                         true,
                         TxnConfig::default(),
                         dojo_metadata.migration.map(|m| m.skip_contracts.clone()),
+                        false,
                     )
                     .await
                 })
                 .map(|_| ()),
-            MigrateCommand::Apply { transaction } => config
-                .tokio_handle()
-                .block_on(async {
+            MigrateCommand::Apply { transaction, json, fresh } => {
+                config.tokio_handle().block_on(async {
                     trace!(name, "Applying migration.");
                     let txn_config: TxnConfig = transaction.into();
+                    let wait = txn_config.wait;
+                    let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(&rpc_url)?));
 
-                    migration::migrate(
+                    let output = migration::migrate(
                         &ws,
                         world_address,
                         rpc_url,
@@ -121,10 +142,20 @@ impl MigrateArgs {
                         false,
                         txn_config,
                         dojo_metadata.migration.map(|m| m.skip_contracts.clone()),
+                        fresh,
                     )
-                    .await
+                    .await?;
+
+                    if let (true, Some(output)) = (json, output) {
+                        let summary =
+                            migration::MigrationSummary::from_output(&provider, &output, wait)
+                                .await?;
+                        println!("{}", summary.to_json()?);
+                    }
+
+                    Ok::<_, anyhow::Error>(())
                 })
-                .map(|_| ()),
+            }
         }
     }
 }
@@ -16,9 +16,17 @@ pub struct TransactionOptions {
     #[arg(global = true)]
     pub fee_estimate_multiplier: Option<f64>,
 
+    #[arg(long, value_name = "WEI")]
+    #[arg(help = "A flat amount to add to the fee estimate, in Wei.")]
+    #[arg(long_help = "A flat amount to add to the fee estimate, in Wei. It is added once the \
+                       multiplier has been applied. (max_fee = estimated_fee * multiplier + pad)")]
+    #[arg(conflicts_with = "max_fee_raw")]
+    #[arg(global = true)]
+    pub fee_estimate_pad: Option<u128>,
+
     #[arg(long)]
     #[arg(help = "Maximum raw value to be used for fees, in Wei.")]
-    #[arg(conflicts_with = "fee_estimate_multiplier")]
+    #[arg(conflicts_with_all = ["fee_estimate_multiplier", "fee_estimate_pad"])]
     #[arg(global = true)]
     pub max_fee_raw: Option<Felt>,
 
@@ -65,15 +73,21 @@ impl From<TransactionOptions> for TxnConfig {
     fn from(value: TransactionOptions) -> Self {
         trace!(
             fee_estimate_multiplier = value.fee_estimate_multiplier,
+            fee_estimate_pad = value.fee_estimate_pad,
             wait = value.wait,
             receipt = value.receipt,
             "Converting TransactionOptions to TxnConfig."
         );
         Self {
             fee_estimate_multiplier: value.fee_estimate_multiplier,
+            fee_estimate_pad: value.fee_estimate_pad,
             wait: value.wait,
             receipt: value.receipt,
             max_fee_raw: value.max_fee_raw,
+            report_dir: None,
+            max_concurrent_declarations: None,
+            max_retries: None,
+            retry_backoff_ms: None,
         }
     }
 }
@@ -4,4 +4,4 @@ pub mod parse;
 mod tx;
 
 pub use tx::waiter::*;
-pub use tx::{TransactionExt, TxnAction, TxnConfig};
+pub use tx::{RetriesExhausted, TransactionExt, TxnAction, TxnConfig};
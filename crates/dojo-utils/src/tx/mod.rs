@@ -1,5 +1,10 @@
 pub mod waiter;
 
+use std::any::Any;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use anyhow::Result;
 use starknet::accounts::{
     AccountDeploymentV1, AccountError, AccountFactory, AccountFactoryError, ConnectedAccount,
@@ -8,16 +13,57 @@ use starknet::accounts::{
 use starknet::core::types::{
     DeclareTransactionResult, DeployAccountTransactionResult, Felt, InvokeTransactionResult,
 };
+use starknet::providers::{ProviderError, ProviderImplError};
+
+/// The fee estimate multiplier used by `starknet-rs` when none is provided.
+const DEFAULT_FEE_ESTIMATE_MULTIPLIER: f64 = 1.1;
+
+/// The delay before the first retry of a failed request when none is provided, in milliseconds.
+const DEFAULT_RETRY_BACKOFF_MS: u64 = 500;
 
 /// The transaction configuration to use when sending a transaction.
-#[derive(Debug, Copy, Clone, Default)]
+///
+/// It isn't `Copy` since it owns the path of the report directory, so it's cloned when reused.
+#[derive(Debug, Clone, Default)]
 pub struct TxnConfig {
     /// The multiplier for how much the actual transaction max fee should be relative to the
     /// estimated fee. If `None` is provided, the multiplier is set to `1.1`.
     pub fee_estimate_multiplier: Option<f64>,
+    /// A flat amount (in Wei) added to the estimated fee once the multiplier has been applied,
+    /// ie `max_fee = estimated_fee * multiplier + pad`.
+    pub fee_estimate_pad: Option<u128>,
     pub wait: bool,
     pub receipt: bool,
     pub max_fee_raw: Option<Felt>,
+    /// The directory where the migration report, transaction logs and state are written. If
+    /// `None` is provided, no report is written and the state goes to the target directory.
+    pub report_dir: Option<PathBuf>,
+    /// The maximum number of declarations a migration sends before waiting for them. If `None`
+    /// is provided, the classes are declared one at a time.
+    pub max_concurrent_declarations: Option<usize>,
+    /// The number of times a request failing with a transient error is retried. A fee
+    /// estimation is retried on any error not returned by the sequencer, but a transaction is
+    /// only re-sent if its request was rate limited, as it may be in the mempool otherwise. If
+    /// `None` is provided, nothing is retried.
+    pub max_retries: Option<u32>,
+    /// The delay before the first retry, in milliseconds, doubled after each retry. If `None` is
+    /// provided, the delay is 500ms.
+    pub retry_backoff_ms: Option<u64>,
+}
+
+/// The error of a request which still failed once all the retries configured by
+/// [`TxnConfig::max_retries`] were exhausted.
+#[derive(Debug, thiserror::Error)]
+#[error("{source} (gave up after {attempts} attempts)")]
+pub struct RetriesExhausted {
+    pub attempts: u32,
+    pub source: ProviderError,
+}
+
+impl ProviderImplError for RetriesExhausted {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -38,6 +84,129 @@ impl TxnConfig {
     pub fn init_wait() -> Self {
         Self { wait: true, ..Default::default() }
     }
+
+    /// Returns `true` if the fee is estimated before sending a transaction, to be padded or to
+    /// be retried on its own as `send` can't be retried once the transaction is sent.
+    ///
+    /// Otherwise the max fee is left to `starknet-rs`, which only applies the multiplier.
+    fn estimates_fee_beforehand(&self) -> bool {
+        self.max_fee_raw.is_none()
+            && (self.fee_estimate_pad.is_some() || self.max_retries.is_some())
+    }
+
+    /// Returns the max fee to use for a transaction with the given estimated fee, applying the
+    /// multiplier and then the pad, if any.
+    fn max_fee_from_estimate(&self, estimated_fee: Felt) -> Felt {
+        let multiplier = self.fee_estimate_multiplier.unwrap_or(DEFAULT_FEE_ESTIMATE_MULTIPLIER);
+        let estimated_fee = u128::try_from(estimated_fee).unwrap_or(u128::MAX);
+        let max_fee = ((estimated_fee as f64) * multiplier) as u128;
+
+        max_fee.saturating_add(self.fee_estimate_pad.unwrap_or_default()).into()
+    }
+
+    /// Sets the fee estimate multiplier and the raw max fee of `tx`, if any. If the fee is
+    /// estimated beforehand, the max fee is set to the padded estimate.
+    async fn configure_fee<Tx: FeeConfigurable>(&self, mut tx: Tx) -> Result<Tx, Tx::Error> {
+        if let Some(fee_estimate_multiplier) = self.fee_estimate_multiplier {
+            tx = tx.with_fee_estimate_multiplier(fee_estimate_multiplier);
+        }
+
+        if let Some(max_fee_raw) = self.max_fee_raw {
+            tx = tx.with_max_fee(max_fee_raw);
+        }
+
+        if self.estimates_fee_beforehand() {
+            let estimated_fee = self.retry(is_transient, || tx.overall_fee()).await?;
+            tx = tx.with_max_fee(self.max_fee_from_estimate(estimated_fee));
+        }
+
+        Ok(tx)
+    }
+
+    /// Runs `request` until it succeeds, or fails with a provider error `is_retryable` rejects,
+    /// retrying it up to `max_retries` times with an exponential backoff.
+    async fn retry<T, E, F, Fut>(
+        &self,
+        is_retryable: fn(&ProviderError) -> bool,
+        mut request: F,
+    ) -> Result<T, E>
+    where
+        E: RequestError,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let max_retries = self.max_retries.unwrap_or_default();
+        let mut backoff =
+            Duration::from_millis(self.retry_backoff_ms.unwrap_or(DEFAULT_RETRY_BACKOFF_MS));
+        let mut attempts = 1;
+
+        loop {
+            let error = match request().await.map_err(E::into_provider_error) {
+                Ok(output) => return Ok(output),
+                Err(Ok(e)) if is_retryable(&e) => e,
+                Err(Ok(e)) => return Err(E::from_provider_error(e)),
+                Err(Err(e)) => return Err(e),
+            };
+
+            if attempts > max_retries {
+                return Err(E::from_provider_error(if attempts > 1 {
+                    ProviderError::Other(Box::new(RetriesExhausted { attempts, source: error }))
+                } else {
+                    error
+                }));
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+            attempts += 1;
+        }
+    }
+}
+
+/// The error of a request sent by an account, which may be a provider error.
+trait RequestError: Sized {
+    /// Returns the provider error, or the error itself if it isn't one.
+    fn into_provider_error(self) -> Result<ProviderError, Self>;
+
+    fn from_provider_error(error: ProviderError) -> Self;
+}
+
+impl<S> RequestError for AccountError<S> {
+    fn into_provider_error(self) -> Result<ProviderError, Self> {
+        match self {
+            AccountError::Provider(e) => Ok(e),
+            e => Err(e),
+        }
+    }
+
+    fn from_provider_error(error: ProviderError) -> Self {
+        AccountError::Provider(error)
+    }
+}
+
+impl<S> RequestError for AccountFactoryError<S> {
+    fn into_provider_error(self) -> Result<ProviderError, Self> {
+        match self {
+            AccountFactoryError::Provider(e) => Ok(e),
+            e => Err(e),
+        }
+    }
+
+    fn from_provider_error(error: ProviderError) -> Self {
+        AccountFactoryError::Provider(error)
+    }
+}
+
+/// Returns `true` if a read only request, like a fee estimation, failing with `error` can be
+/// retried: the error isn't returned by the sequencer but by the transport.
+fn is_transient(error: &ProviderError) -> bool {
+    matches!(error, ProviderError::RateLimited | ProviderError::Other(_))
+}
+
+/// Returns `true` if a transaction failing with `error` can be sent again, which is only the case
+/// if the request was rate limited: any other error may happen once the transaction is accepted.
+fn is_rate_limited(error: &ProviderError) -> bool {
+    matches!(error, ProviderError::RateLimited)
 }
 
 /// Helper trait to abstract away setting `TxnConfig` configurations before sending a transaction
@@ -51,6 +220,10 @@ pub trait TransactionExt<T> {
     /// calling `send` method on the respective type.
     /// NOTE: If both are specified `max_fee_raw` will take precedence and `fee_estimate_multiplier`
     /// will be ignored by `starknet-rs`
+    ///
+    /// If `fee_estimate_pad` or `max_retries` is set (and `max_fee_raw` isn't), the fee is
+    /// estimated beforehand and the padded max fee is set explicitly. The fee estimation and the
+    /// sending are retried according to `max_retries`.
     async fn send_with_cfg(self, txn_config: &TxnConfig) -> Result<Self::R, Self::U>;
 }
 
@@ -62,18 +235,11 @@ where
     type U = AccountError<T::SignError>;
 
     async fn send_with_cfg(
-        mut self,
+        self,
         txn_config: &TxnConfig,
     ) -> Result<Self::R, AccountError<T::SignError>> {
-        if let TxnConfig { fee_estimate_multiplier: Some(fee_est_mul), .. } = txn_config {
-            self = self.fee_estimate_multiplier(*fee_est_mul);
-        }
-
-        if let TxnConfig { max_fee_raw: Some(max_fee_r), .. } = txn_config {
-            self = self.max_fee(*max_fee_r);
-        }
-
-        self.send().await
+        let tx = txn_config.configure_fee(self).await?;
+        txn_config.retry(is_rate_limited, || tx.send()).await
     }
 }
 
@@ -85,18 +251,11 @@ where
     type U = AccountError<T::SignError>;
 
     async fn send_with_cfg(
-        mut self,
+        self,
         txn_config: &TxnConfig,
     ) -> Result<Self::R, AccountError<T::SignError>> {
-        if let TxnConfig { fee_estimate_multiplier: Some(fee_est_mul), .. } = txn_config {
-            self = self.fee_estimate_multiplier(*fee_est_mul);
-        }
-
-        if let TxnConfig { max_fee_raw: Some(max_raw_f), .. } = txn_config {
-            self = self.max_fee(*max_raw_f);
-        }
-
-        self.send().await
+        let tx = txn_config.configure_fee(self).await?;
+        txn_config.retry(is_rate_limited, || tx.send()).await
     }
 }
 
@@ -108,17 +267,180 @@ where
     type U = AccountFactoryError<T::SignError>;
 
     async fn send_with_cfg(
-        mut self,
+        self,
         txn_config: &TxnConfig,
     ) -> Result<Self::R, AccountFactoryError<<T>::SignError>> {
-        if let TxnConfig { fee_estimate_multiplier: Some(fee_est_mul), .. } = txn_config {
-            self = self.fee_estimate_multiplier(*fee_est_mul);
-        }
+        let tx = txn_config.configure_fee(self).await?;
+        txn_config.retry(is_rate_limited, || tx.send()).await
+    }
+}
+
+/// The fee settings shared by the transaction types of `starknet-accounts`.
+trait FeeConfigurable: Sized {
+    type Error: RequestError;
 
-        if let TxnConfig { max_fee_raw: Some(max_raw_f), .. } = txn_config {
-            self = self.max_fee(*max_raw_f);
+    fn with_fee_estimate_multiplier(self, fee_estimate_multiplier: f64) -> Self;
+
+    fn with_max_fee(self, max_fee: Felt) -> Self;
+
+    async fn overall_fee(&self) -> Result<Felt, Self::Error>;
+}
+
+macro_rules! impl_fee_configurable {
+    ($tx:ident, $account:ident, $error:ident) => {
+        impl<T> FeeConfigurable for $tx<'_, T>
+        where
+            T: $account + Sync,
+        {
+            type Error = $error<T::SignError>;
+
+            fn with_fee_estimate_multiplier(self, fee_estimate_multiplier: f64) -> Self {
+                self.fee_estimate_multiplier(fee_estimate_multiplier)
+            }
+
+            fn with_max_fee(self, max_fee: Felt) -> Self {
+                self.max_fee(max_fee)
+            }
+
+            async fn overall_fee(&self) -> Result<Felt, Self::Error> {
+                Ok(self.estimate_fee().await?.overall_fee)
+            }
         }
+    };
+}
+
+impl_fee_configurable!(ExecutionV1, ConnectedAccount, AccountError);
+impl_fee_configurable!(DeclarationV2, ConnectedAccount, AccountError);
+impl_fee_configurable!(AccountDeploymentV1, AccountFactory, AccountFactoryError);
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::convert::Infallible;
+
+    use starknet::accounts::{AccountError, AccountFactoryError};
+    use starknet::core::types::{Felt, StarknetError};
+    use starknet::providers::ProviderError;
+
+    use super::{is_rate_limited, is_transient, TxnConfig};
+
+    #[test]
+    fn padded_max_fee_is_applied_after_multiplier() {
+        let config = TxnConfig {
+            fee_estimate_multiplier: Some(2.0),
+            fee_estimate_pad: Some(10_000),
+            ..Default::default()
+        };
+
+        assert!(config.estimates_fee_beforehand());
+        let max_fee = config.max_fee_from_estimate(Felt::from(1_000_000u128));
+        assert_eq!(max_fee, Felt::from(2_010_000u128));
+
+        // The default multiplier is used when none is provided.
+        let config = TxnConfig { fee_estimate_pad: Some(10_000), ..Default::default() };
+        let max_fee = config.max_fee_from_estimate(Felt::from(1_000_000u128));
+        assert_eq!(max_fee, Felt::from(1_110_000u128));
+    }
+
+    #[test]
+    fn padded_max_fee_is_ignored_with_raw_max_fee() {
+        let config = TxnConfig {
+            fee_estimate_pad: Some(10_000),
+            max_fee_raw: Some(Felt::from(42u128)),
+            ..Default::default()
+        };
+        assert!(!config.estimates_fee_beforehand());
+
+        // Without a pad nor retries, the max fee is left to `starknet-rs`.
+        let config = TxnConfig { fee_estimate_multiplier: Some(2.0), ..Default::default() };
+        assert!(!config.estimates_fee_beforehand());
+
+        // The fee is estimated on its own to be retried.
+        let config = TxnConfig { max_retries: Some(2), ..Default::default() };
+        assert!(config.estimates_fee_beforehand());
+    }
+
+    #[tokio::test]
+    async fn account_deployment_errors_are_retried() {
+        let config =
+            TxnConfig { max_retries: Some(1), retry_backoff_ms: Some(1), ..Default::default() };
+        let attempts = Cell::new(0);
+
+        let result: Result<(), AccountFactoryError<Infallible>> = config
+            .retry(is_transient, || async {
+                attempts.set(attempts.get() + 1);
+                Err(AccountFactoryError::Provider(ProviderError::RateLimited))
+            })
+            .await;
+
+        assert_eq!(attempts.get(), 2);
+        assert!(result.unwrap_err().to_string().contains("gave up after 2 attempts"));
+    }
+
+    #[tokio::test]
+    async fn transient_errors_are_retried_until_exhausted() {
+        let config =
+            TxnConfig { max_retries: Some(2), retry_backoff_ms: Some(1), ..Default::default() };
+        let attempts = Cell::new(0);
+
+        let result: Result<(), AccountError<Infallible>> = config
+            .retry(is_rate_limited, || async {
+                attempts.set(attempts.get() + 1);
+                Err(AccountError::Provider(ProviderError::RateLimited))
+            })
+            .await;
+
+        assert_eq!(attempts.get(), 3);
+        assert!(result.unwrap_err().to_string().contains("gave up after 3 attempts"));
+
+        // A request succeeding after a transient error is returned as is.
+        let attempts = Cell::new(0);
+        let result: Result<u32, AccountError<Infallible>> = config
+            .retry(is_transient, || async {
+                attempts.set(attempts.get() + 1);
+                if attempts.get() == 1 {
+                    Err(AccountError::Provider(ProviderError::RateLimited))
+                } else {
+                    Ok(42)
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn sequencer_errors_are_not_retried() {
+        let config =
+            TxnConfig { max_retries: Some(2), retry_backoff_ms: Some(1), ..Default::default() };
+        let attempts = Cell::new(0);
+
+        let result: Result<(), AccountError<Infallible>> = config
+            .retry(is_transient, || async {
+                attempts.set(attempts.get() + 1);
+                Err(AccountError::Provider(ProviderError::StarknetError(
+                    StarknetError::InsufficientAccountBalance,
+                )))
+            })
+            .await;
+
+        assert_eq!(attempts.get(), 1);
+        assert!(matches!(
+            result,
+            Err(AccountError::Provider(ProviderError::StarknetError(
+                StarknetError::InsufficientAccountBalance
+            )))
+        ));
+
+        // Without retries, the error of the first attempt is returned unchanged.
+        let config = TxnConfig::default();
+        let result: Result<(), AccountError<Infallible>> = config
+            .retry(is_rate_limited, || async {
+                Err(AccountError::Provider(ProviderError::RateLimited))
+            })
+            .await;
 
-        self.send().await
+        assert!(matches!(result, Err(AccountError::Provider(ProviderError::RateLimited))));
     }
 }
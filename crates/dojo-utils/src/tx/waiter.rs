@@ -4,7 +4,7 @@ use std::task::{Context, Poll};
 use std::time::Duration;
 
 use anyhow::Result;
-use futures::FutureExt;
+use futures::{future, FutureExt};
 use starknet::core::types::{
     ExecutionResult, Felt, ReceiptBlock, StarknetError, TransactionFinalityStatus,
     TransactionReceipt, TransactionReceiptWithBlockInfo, TransactionStatus,
@@ -20,20 +20,28 @@ type GetTxReceiptFuture<'a> = Pin<Box<dyn Future<Output = GetTxReceiptResult> +
 
 #[derive(Debug, thiserror::Error)]
 pub enum TransactionWaitingError {
-    #[error("request timed out")]
-    Timeout,
+    /// The transaction didn't reach the waited status before the timeout.
+    #[error("transaction {tx_hash:#x} timed out, last status: {last_status:?}")]
+    Timeout {
+        /// The hash of the waited transaction.
+        tx_hash: Felt,
+        /// The last status of the transaction, `None` if it was never found.
+        last_status: Option<TransactionStatus>,
+    },
     #[error("transaction reverted with reason: {0}")]
     TransactionReverted(String),
     #[error("transaction rejected")]
     TransactionRejected,
+    #[error("{} transactions reverted", .0.len())]
+    TransactionsReverted(Vec<(Felt, String)>),
     #[error(transparent)]
     Provider(ProviderError),
 }
 
 /// Utility for waiting on a transaction.
 ///
-/// The waiter will poll for the transaction receipt every `interval` miliseconds until it achieves
-/// the desired status or until `timeout` is reached.
+/// The waiter will poll for the transaction receipt every `interval` until it achieves the desired
+/// status or until `timeout` is reached, which is reported with the last status of the transaction.
 ///
 /// The waiter can be configured to wait for a specific finality status (e.g, `ACCEPTED_ON_L2`), by
 /// default, it only waits until the transaction is included in the _pending_ block. It can also be
@@ -68,8 +76,7 @@ pub struct TransactionWaiter<'a, P: Provider> {
     /// be considered when waiting for the transaction, meaning `REVERTED` transaction will not
     /// return an error.
     must_succeed: bool,
-    /// Poll the transaction every `interval` milliseconds. Milliseconds are used so that
-    /// we can be more precise with the polling interval. Defaults to 2.5 seconds.
+    /// Poll the transaction every `interval`. Defaults to 2.5 seconds.
     interval: Interval,
     /// The maximum amount of time to wait for the transaction to achieve the desired status. An
     /// error will be returned if it is unable to finish within the `timeout` duration. Defaults to
@@ -83,6 +90,8 @@ pub struct TransactionWaiter<'a, P: Provider> {
     tx_receipt_request_fut: Option<GetTxReceiptFuture<'a>>,
     /// The time when the transaction waiter was first polled.
     started_at: Option<Instant>,
+    /// The last status of the transaction, reported if the waiter times out.
+    last_status: Option<TransactionStatus>,
 }
 
 impl<'a, P> TransactionWaiter<'a, P>
@@ -98,6 +107,7 @@ where
             provider,
             tx_hash: tx,
             started_at: None,
+            last_status: None,
             must_succeed: true,
             tx_finality_status: None,
             tx_status_request_fut: None,
@@ -110,8 +120,7 @@ where
         }
     }
 
-    pub fn with_interval(self, milisecond: u64) -> Self {
-        let interval = Duration::from_millis(milisecond);
+    pub fn with_interval(self, interval: Duration) -> Self {
         Self { interval: tokio::time::interval_at(Instant::now() + interval, interval), ..self }
     }
 
@@ -123,6 +132,48 @@ where
         Self { timeout, ..self }
     }
 
+    /// Waits concurrently for all the transactions of `tx_hashes` to reach the finality `status`,
+    /// returning their receipts in the same order.
+    ///
+    /// Every transaction is waited on until it reaches the status or fails. If some of them
+    /// reverted, [`TransactionWaitingError::TransactionsReverted`] is returned with the hash and
+    /// the revert reason of each of them, otherwise the first other error is returned.
+    pub async fn wait_all(
+        tx_hashes: &[Felt],
+        provider: &'a P,
+        status: TransactionFinalityStatus,
+    ) -> Result<Vec<TransactionReceiptWithBlockInfo>, TransactionWaitingError> {
+        let results = future::join_all(
+            tx_hashes.iter().map(|hash| Self::new(*hash, provider).with_tx_status(status)),
+        )
+        .await;
+
+        let mut receipts = Vec::with_capacity(results.len());
+        let mut reverted = vec![];
+        let mut error = None;
+
+        for (hash, result) in tx_hashes.iter().zip(results) {
+            match result {
+                Ok(receipt) => receipts.push(receipt),
+                Err(TransactionWaitingError::TransactionReverted(reason)) => {
+                    reverted.push((*hash, reason))
+                }
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+
+        if !reverted.is_empty() {
+            return Err(TransactionWaitingError::TransactionsReverted(reverted));
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(receipts),
+        }
+    }
+
     // Helper function to evaluate if the transaction receipt should be accepted yet or not, based
     // on the waiter's parameters. Used in the `Future` impl.
     fn evaluate_receipt_from_params(
@@ -195,29 +246,36 @@ where
         loop {
             if let Some(started_at) = this.started_at {
                 if started_at.elapsed() > this.timeout {
-                    return Poll::Ready(Err(TransactionWaitingError::Timeout));
+                    return Poll::Ready(Err(TransactionWaitingError::Timeout {
+                        tx_hash: this.tx_hash,
+                        last_status: this.last_status.take(),
+                    }));
                 }
             }
 
             if let Some(mut fut) = this.tx_status_request_fut.take() {
                 match fut.poll_unpin(cx) {
                     Poll::Ready(res) => match res {
-                        Ok(status) => match status {
-                            TransactionStatus::AcceptedOnL2(_)
-                            | TransactionStatus::AcceptedOnL1(_) => {
-                                this.tx_receipt_request_fut = Some(Box::pin(
-                                    this.provider.get_transaction_receipt(this.tx_hash),
-                                ));
+                        Ok(status) => {
+                            match status {
+                                TransactionStatus::AcceptedOnL2(_)
+                                | TransactionStatus::AcceptedOnL1(_) => {
+                                    this.tx_receipt_request_fut = Some(Box::pin(
+                                        this.provider.get_transaction_receipt(this.tx_hash),
+                                    ));
+                                }
+
+                                TransactionStatus::Rejected => {
+                                    return Poll::Ready(Err(
+                                        TransactionWaitingError::TransactionRejected,
+                                    ));
+                                }
+
+                                TransactionStatus::Received => {}
                             }
 
-                            TransactionStatus::Rejected => {
-                                return Poll::Ready(Err(
-                                    TransactionWaitingError::TransactionRejected,
-                                ));
-                            }
-
-                            TransactionStatus::Received => {}
-                        },
+                            this.last_status = Some(status);
+                        }
 
                         Err(ProviderError::StarknetError(
                             StarknetError::TransactionHashNotFound,
@@ -305,14 +363,15 @@ mod tests {
     use dojo_test_utils::sequencer::{
         get_default_test_starknet_config, SequencerConfig, TestSequencer,
     };
+    use starknet::accounts::{Account, Call};
     use starknet::core::types::ExecutionResult::{Reverted, Succeeded};
     use starknet::core::types::TransactionFinalityStatus::{self, AcceptedOnL1, AcceptedOnL2};
     use starknet::core::types::{
         ComputationResources, DataAvailabilityResources, DataResources, ExecutionResources,
-        ExecutionResult, FeePayment, InvokeTransactionReceipt, PriceUnit, ReceiptBlock,
-        TransactionReceipt, TransactionReceiptWithBlockInfo,
+        ExecutionResult, FeePayment, Felt, InvokeTransactionReceipt, PriceUnit, ReceiptBlock,
+        TransactionReceipt, TransactionReceiptWithBlockInfo, TransactionStatus,
     };
-    use starknet::macros::felt;
+    use starknet::macros::{felt, selector};
     use starknet::providers::jsonrpc::HttpTransport;
     use starknet::providers::JsonRpcClient;
 
@@ -383,6 +442,29 @@ mod tests {
         TransactionReceiptWithBlockInfo { receipt, block: ReceiptBlock::Pending }
     }
 
+    #[tokio::test]
+    async fn wait_all_returns_the_receipts_in_order() {
+        let (sequencer, provider) = create_test_sequencer().await;
+        let account = sequencer.account();
+        let fee_token = felt!("0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7");
+
+        let mut hashes = vec![];
+        for amount in [felt!("0x1"), felt!("0x2")] {
+            let call = Call {
+                to: fee_token,
+                selector: selector!("transfer"),
+                calldata: vec![felt!("0x1337"), amount, Felt::ZERO],
+            };
+            hashes.push(account.execute_v1(vec![call]).send().await.unwrap().transaction_hash);
+        }
+
+        let receipts = TransactionWaiter::wait_all(&hashes, &provider, AcceptedOnL2).await.unwrap();
+
+        let receipt_hashes =
+            receipts.iter().map(|r| *r.receipt.transaction_hash()).collect::<Vec<_>>();
+        assert_eq!(receipt_hashes, hashes);
+    }
+
     #[tokio::test]
     async fn should_timeout_on_nonexistant_transaction() {
         let (_sequencer, provider) = create_test_sequencer().await;
@@ -393,7 +475,40 @@ mod tests {
             .await
             .unwrap_err();
 
-        assert_matches!(result, TransactionWaitingError::Timeout);
+        assert_matches!(
+            result,
+            TransactionWaitingError::Timeout { tx_hash, last_status: None } if tx_hash == hash
+        );
+    }
+
+    #[tokio::test]
+    async fn should_timeout_with_the_last_status_of_the_transaction() {
+        let (sequencer, provider) = create_test_sequencer().await;
+        let account = sequencer.account();
+        let fee_token = felt!("0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7");
+
+        let call = Call {
+            to: fee_token,
+            selector: selector!("transfer"),
+            calldata: vec![felt!("0x1337"), felt!("0x1"), Felt::ZERO],
+        };
+        let hash = account.execute_v1(vec![call]).send().await.unwrap().transaction_hash;
+
+        // The transaction is never accepted on L1 by the sequencer.
+        let result = TransactionWaiter::new(hash, &provider)
+            .with_tx_status(AcceptedOnL1)
+            .with_interval(Duration::from_millis(100))
+            .with_timeout(Duration::from_secs(1))
+            .await
+            .unwrap_err();
+
+        assert_matches!(
+            result,
+            TransactionWaitingError::Timeout {
+                tx_hash,
+                last_status: Some(TransactionStatus::AcceptedOnL2(_)),
+            } if tx_hash == hash
+        );
     }
 
     macro_rules! eval_receipt {
@@ -1,6 +1,28 @@
 use serde::Deserialize;
 
+use crate::metadata::{StorageConfig, UploadConfig};
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct MigrationConfig {
     pub skip_contracts: Vec<String>,
+    /// Whether the metadata artifacts (ABIs and sources) are gzipped before being uploaded.
+    #[serde(default)]
+    pub compress_metadata: bool,
+    /// The options used when uploading the metadata to IPFS.
+    #[serde(default)]
+    pub upload: UploadConfig,
+    /// The storage the metadata are uploaded to, the default IPFS node if not set.
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// Whether upgrading a contract whose deployed storage layout version doesn't match the one
+    /// expected by its new class fails the migration, instead of only warning.
+    #[serde(default)]
+    pub strict_layout: bool,
+    /// The maximum size (in bytes) of the classes to declare. If `None` is provided, the maximum
+    /// size accepted by Starknet is used.
+    pub max_class_size: Option<usize>,
+    /// Whether a class exceeding the maximum class size fails the migration, instead of only
+    /// warning.
+    #[serde(default)]
+    pub strict_class_size: bool,
 }
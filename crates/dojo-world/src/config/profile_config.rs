@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 
 use anyhow::Result;
@@ -21,6 +22,8 @@ pub struct ProfileConfig {
     pub namespace: NamespaceConfig,
     pub env: Option<Environment>,
     pub migration: Option<MigrationConfig>,
+    /// Custom fields uploaded with the metadata of the resources, keyed by resource tag.
+    pub metadata: Option<HashMap<String, BTreeMap<String, serde_json::Value>>>,
 }
 
 impl ProfileConfig {
@@ -60,6 +63,7 @@ mod tests {
     use url::Url;
 
     use super::*;
+    use crate::metadata::StorageConfig;
     use crate::uri::Uri;
 
     #[test]
@@ -121,6 +125,16 @@ mod tests {
 
         [migration]
         skip_contracts = [ "module::my-contract" ]
+        max_class_size = 1000
+        strict_class_size = true
+
+        [migration.storage]
+        kind = "arweave"
+        url = "https://upload.example.com"
+
+        [metadata.test-Position]
+        category = "movement"
+        docs = "https://example.com/docs"
 
         "#;
 
@@ -128,6 +142,12 @@ mod tests {
 
         let migration = config.migration.unwrap();
         assert_eq!(migration.skip_contracts, vec!["module::my-contract".to_string()]);
+        assert_eq!(migration.max_class_size, Some(1000));
+        assert!(migration.strict_class_size);
+        assert_eq!(
+            migration.storage,
+            StorageConfig::Arweave { url: "https://upload.example.com".to_string(), api_key: None }
+        );
 
         let env = config.env.unwrap();
         assert_eq!(env.rpc_url, Some("https://example.com/rpc".to_string()));
@@ -160,6 +180,15 @@ mod tests {
             config.namespace.mappings,
             Some(HashMap::from([("test".to_string(), "test2".to_string())]))
         );
+
+        let metadata = config.metadata.unwrap();
+        assert_eq!(
+            metadata["test-Position"],
+            BTreeMap::from([
+                ("category".to_string(), serde_json::json!("movement")),
+                ("docs".to_string(), serde_json::json!("https://example.com/docs")),
+            ])
+        );
     }
 
     #[test]
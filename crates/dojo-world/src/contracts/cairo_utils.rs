@@ -15,3 +15,19 @@ pub fn encode_uri(uri: &str) -> Result<cainome::cairo_serde::ByteArray> {
 
     Ok(cainome::cairo_serde::ByteArray::from_string(parsed.to_string().as_str()).unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_uri_accepts_ipfs_and_arweave_uris() {
+        for uri in [
+            "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            "ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U",
+        ] {
+            let encoded = encode_uri(uri).unwrap().to_string().unwrap();
+            assert_eq!(encoded.trim_end_matches('/'), uri);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use starknet::core::types::Felt;
+use starknet::macros::selector;
+
+/// The events emitted by a Dojo world.
+///
+/// The first key of an emitted event is the selector of its name, which is what indexers
+/// filter on to subscribe to a given event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorldEventKind {
+    WorldSpawned,
+    ContractDeployed,
+    ContractUpgraded,
+    ContractInitialized,
+    WorldUpgraded,
+    MetadataUpdate,
+    NamespaceRegistered,
+    ModelRegistered,
+    ModelUpgraded,
+    StoreSetRecord,
+    StoreUpdateRecord,
+    StoreUpdateMember,
+    StoreDelRecord,
+    WriterUpdated,
+    OwnerUpdated,
+    /// An update of the world config. The event isn't flattened in the world, so the second key
+    /// is the selector of the update (eg, `DifferProgramHashUpdate`).
+    ConfigEvent,
+    StateUpdated,
+}
+
+impl WorldEventKind {
+    /// All the events a world emits.
+    pub const ALL: [Self; 17] = [
+        Self::WorldSpawned,
+        Self::ContractDeployed,
+        Self::ContractUpgraded,
+        Self::ContractInitialized,
+        Self::WorldUpgraded,
+        Self::MetadataUpdate,
+        Self::NamespaceRegistered,
+        Self::ModelRegistered,
+        Self::ModelUpgraded,
+        Self::StoreSetRecord,
+        Self::StoreUpdateRecord,
+        Self::StoreUpdateMember,
+        Self::StoreDelRecord,
+        Self::WriterUpdated,
+        Self::OwnerUpdated,
+        Self::ConfigEvent,
+        Self::StateUpdated,
+    ];
+
+    /// Returns the name of the event, as declared in the world contract.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::WorldSpawned => "WorldSpawned",
+            Self::ContractDeployed => "ContractDeployed",
+            Self::ContractUpgraded => "ContractUpgraded",
+            Self::ContractInitialized => "ContractInitialized",
+            Self::WorldUpgraded => "WorldUpgraded",
+            Self::MetadataUpdate => "MetadataUpdate",
+            Self::NamespaceRegistered => "NamespaceRegistered",
+            Self::ModelRegistered => "ModelRegistered",
+            Self::ModelUpgraded => "ModelUpgraded",
+            Self::StoreSetRecord => "StoreSetRecord",
+            Self::StoreUpdateRecord => "StoreUpdateRecord",
+            Self::StoreUpdateMember => "StoreUpdateMember",
+            Self::StoreDelRecord => "StoreDelRecord",
+            Self::WriterUpdated => "WriterUpdated",
+            Self::OwnerUpdated => "OwnerUpdated",
+            Self::ConfigEvent => "ConfigEvent",
+            Self::StateUpdated => "StateUpdated",
+        }
+    }
+
+    /// Returns the selector of the event, which is the first key of the emitted event.
+    pub const fn selector(&self) -> Felt {
+        match self {
+            Self::WorldSpawned => selector!("WorldSpawned"),
+            Self::ContractDeployed => selector!("ContractDeployed"),
+            Self::ContractUpgraded => selector!("ContractUpgraded"),
+            Self::ContractInitialized => selector!("ContractInitialized"),
+            Self::WorldUpgraded => selector!("WorldUpgraded"),
+            Self::MetadataUpdate => selector!("MetadataUpdate"),
+            Self::NamespaceRegistered => selector!("NamespaceRegistered"),
+            Self::ModelRegistered => selector!("ModelRegistered"),
+            Self::ModelUpgraded => selector!("ModelUpgraded"),
+            Self::StoreSetRecord => selector!("StoreSetRecord"),
+            Self::StoreUpdateRecord => selector!("StoreUpdateRecord"),
+            Self::StoreUpdateMember => selector!("StoreUpdateMember"),
+            Self::StoreDelRecord => selector!("StoreDelRecord"),
+            Self::WriterUpdated => selector!("WriterUpdated"),
+            Self::OwnerUpdated => selector!("OwnerUpdated"),
+            Self::ConfigEvent => selector!("ConfigEvent"),
+            Self::StateUpdated => selector!("StateUpdated"),
+        }
+    }
+
+    /// Returns the event with the given selector, if it's emitted by a world.
+    pub fn from_selector(selector: Felt) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.selector() == selector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::utils::get_selector_from_name;
+
+    use super::WorldEventKind;
+
+    #[test]
+    fn selectors_match_event_names() {
+        for event in WorldEventKind::ALL {
+            assert_eq!(event.selector(), get_selector_from_name(event.name()).unwrap());
+            assert_eq!(WorldEventKind::from_selector(event.selector()), Some(event));
+        }
+
+        assert_eq!(WorldEventKind::from_selector(get_selector_from_name("Unknown").unwrap()), None);
+    }
+}
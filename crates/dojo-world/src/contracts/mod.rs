@@ -1,7 +1,9 @@
 pub mod abi;
 pub mod cairo_utils;
+pub mod events;
 pub mod model;
 pub mod naming;
 pub mod world;
 
+pub use events::WorldEventKind;
 pub use world::{WorldContract, WorldContractReader};
@@ -54,6 +54,63 @@ pub enum ModelError {
     TagError(String),
 }
 
+/// The schema of a model, as a tree of named and typed fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelSchema {
+    /// The selector of the model.
+    pub selector: Felt,
+    /// The name of the model.
+    pub name: String,
+    /// The fields of the model, keys included.
+    pub fields: Vec<SchemaField>,
+}
+
+/// A field of a [`ModelSchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaField {
+    /// The member name, the variant name for enums or the index for tuples.
+    pub name: String,
+    /// The Cairo type of the field.
+    pub type_name: String,
+    /// Whether the field is a key of the model.
+    pub key: bool,
+    /// The nested fields of structs, enums, tuples and array items.
+    pub fields: Vec<SchemaField>,
+}
+
+impl ModelSchema {
+    /// Builds the schema from the [`Ty`] returned by the `schema` entrypoint of a model.
+    pub fn from_ty(selector: Felt, ty: &Ty) -> Result<Self, ModelError> {
+        let Ty::Struct(model) = ty else {
+            return Err(ModelError::Parse(ParseError::invalid_schema()));
+        };
+
+        Ok(Self { selector, name: model.name.clone(), fields: SchemaField::from_ty(ty) })
+    }
+}
+
+impl SchemaField {
+    fn new(name: String, ty: &Ty, key: bool) -> Self {
+        Self { name, type_name: ty.name(), key, fields: Self::from_ty(ty) }
+    }
+
+    fn from_ty(ty: &Ty) -> Vec<Self> {
+        match ty {
+            Ty::Struct(s) => {
+                s.children.iter().map(|m| Self::new(m.name.clone(), &m.ty, m.key)).collect()
+            }
+            Ty::Enum(e) => {
+                e.options.iter().map(|o| Self::new(o.name.clone(), &o.ty, false)).collect()
+            }
+            Ty::Tuple(tys) => {
+                tys.iter().enumerate().map(|(i, ty)| Self::new(i.to_string(), ty, false)).collect()
+            }
+            Ty::Array(tys) => tys.first().map(Self::from_ty).unwrap_or_default(),
+            Ty::Primitive(_) | Ty::ByteArray(_) => vec![],
+        }
+    }
+}
+
 // TODO: to update to match with new model interface
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -197,7 +254,7 @@ where
     }
 }
 
-fn parse_schema(ty: &abigen::model::Ty) -> Result<Ty, ParseError> {
+pub(crate) fn parse_schema(ty: &abigen::model::Ty) -> Result<Ty, ParseError> {
     match ty {
         abigen::model::Ty::Primitive(primitive) => {
             let ty = parse_cairo_short_string(primitive)?;
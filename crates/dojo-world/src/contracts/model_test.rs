@@ -8,7 +8,8 @@ use scarb::compiler::Profile;
 use starknet::accounts::ConnectedAccount;
 use starknet::macros::felt;
 
-use crate::contracts::model::ModelReader;
+use crate::contracts::model::{ModelReader, SchemaField};
+use crate::contracts::naming::compute_selector_from_names;
 use crate::contracts::world::WorldContractReader;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -117,3 +118,75 @@ async fn test_model() {
         })
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_model_schema() {
+    let seq_config = KatanaRunnerConfig::default().with_db_dir(copy_spawn_and_move_db().as_str());
+    let sequencer = KatanaRunner::new_with_config(seq_config).expect("Failed to start runner.");
+
+    let account = sequencer.account(0);
+    let provider = account.provider();
+
+    let setup = CompilerTestSetup::from_examples("../dojo-core", "../../examples/");
+    let config = setup.build_test_config("spawn-and-move", Profile::DEV);
+
+    let manifest_dir = config.manifest_path().parent().unwrap();
+    let target_dir = manifest_dir.join("target").join("dev");
+
+    let (strat, _) = prepare_migration_with_world_and_seed(
+        Utf8PathBuf::from(&manifest_dir),
+        Utf8PathBuf::from(&target_dir),
+        None,
+        "dojo_examples",
+        "dojo_examples",
+    )
+    .unwrap();
+
+    let world = WorldContractReader::new(strat.world_address, provider);
+
+    let selector = compute_selector_from_names("dojo_examples", "Moves");
+    let schema = world.model_schema(selector).await.unwrap();
+
+    let unit_variant = |name: &str| SchemaField {
+        name: name.to_string(),
+        type_name: "()".to_string(),
+        key: false,
+        fields: vec![],
+    };
+
+    assert_eq!(schema.selector, selector);
+    assert_eq!(schema.name, "Moves");
+    assert_eq!(
+        schema.fields,
+        vec![
+            SchemaField {
+                name: "player".to_string(),
+                type_name: "ContractAddress".to_string(),
+                key: true,
+                fields: vec![]
+            },
+            SchemaField {
+                name: "remaining".to_string(),
+                type_name: "u8".to_string(),
+                key: false,
+                fields: vec![]
+            },
+            SchemaField {
+                name: "last_direction".to_string(),
+                type_name: "Direction".to_string(),
+                key: false,
+                fields: vec![
+                    unit_variant("None"),
+                    unit_variant("Left"),
+                    unit_variant("Right"),
+                    unit_variant("Up"),
+                    unit_variant("Down"),
+                ]
+            }
+        ]
+    );
+
+    // reading the schema of an unknown model should return an error
+    let unknown = compute_selector_from_names("dojo_examples", "UnknownModel");
+    assert!(world.model_schema(unknown).await.is_err());
+}
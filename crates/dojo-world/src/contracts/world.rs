@@ -1,24 +1,64 @@
+use std::collections::HashSet;
 use std::result::Result;
 
 pub use abigen::world::{
     ContractDeployed, ContractUpgraded, Event as WorldEvent, ModelRegistered, WorldContract,
     WorldContractReader,
 };
-use starknet::providers::Provider;
+use cainome::cairo_serde::{
+    ByteArray, CairoSerde as _, ClassHash, ContractAddress, Error as CainomeError,
+};
+use futures::future;
+use starknet::core::types::{BlockId, EmittedEvent, EventFilter, Felt, FunctionCall, StarknetError};
+use starknet::macros::selector;
+use starknet::providers::{Provider, ProviderError};
+use starknet_crypto::poseidon_hash_many;
 
-use super::model::{ModelError, ModelRPCReader};
+use super::events::WorldEventKind;
+use super::model::{parse_schema, ModelContractReader, ModelError, ModelRPCReader, ModelSchema};
 use super::naming;
+use crate::uri::Uri;
 
 #[cfg(test)]
 #[path = "world_test.rs"]
 pub(crate) mod test;
 
+/// The storage layout version of contracts not exposing a `layout_version` entrypoint.
+pub const DEFAULT_LAYOUT_VERSION: u32 = 0;
+
 pub mod abigen {
     pub mod world {
         pub use crate::contracts::abi::world::*;
     }
 }
 
+/// A contract registered in a world.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractInfo {
+    /// The tag of the contract (`namespace-name`).
+    pub tag: String,
+    /// The selector of the contract, computed from its tag.
+    pub selector: Felt,
+    /// The address of the contract.
+    pub address: Felt,
+    /// The class hash of the contract in the resource registry, which is the class the contract
+    /// was deployed with: the registry isn't updated when the contract is upgraded.
+    pub class_hash: Felt,
+}
+
+/// A model registered in a world.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelInfo {
+    /// The tag of the model (`namespace-name`).
+    pub tag: String,
+    /// The selector of the model, computed from its tag.
+    pub selector: Felt,
+    /// The address of the model contract.
+    pub address: Felt,
+    /// The class hash of the model contract, which reflects the latest upgrade.
+    pub class_hash: Felt,
+}
+
 impl<P> WorldContractReader<P>
 where
     P: Provider + Sync + Send,
@@ -39,4 +79,351 @@ where
     ) -> Result<ModelRPCReader<'_, P>, ModelError> {
         ModelRPCReader::new(namespace, name, self).await
     }
+
+    /// Reads the schema of the model registered with the given selector.
+    pub async fn model_schema(&self, model_selector: Felt) -> Result<ModelSchema, ModelError> {
+        let contract_address =
+            match self.resource(&model_selector).block_id(self.block_id).call().await? {
+                abigen::world::Resource::Model((_, address)) => address,
+                _ => return Err(ModelError::ModelNotFound),
+            };
+
+        if contract_address == ContractAddress(Felt::ZERO) {
+            return Err(ModelError::ModelNotFound);
+        }
+
+        let model_reader = ModelContractReader::new(contract_address.into(), self.provider());
+        let ty = parse_schema(&model_reader.schema().block_id(self.block_id).call().await?)?;
+
+        ModelSchema::from_ty(model_selector, &ty)
+    }
+
+    /// Reads the metadata URI of a resource as it was at the end of the given block.
+    ///
+    /// The URI is empty if no metadata was set for the resource at that block.
+    pub async fn metadata_at(&self, resource: Felt, block: u64) -> Result<ByteArray, CainomeError> {
+        let metadata = self.metadata(&resource).block_id(BlockId::Number(block)).call().await?;
+        Ok(metadata.metadata_uri)
+    }
+
+    /// Reads the CID of the world metadata, from its `ipfs://` URI.
+    ///
+    /// Returns `None` if no metadata was set for the world, or if it isn't stored on IPFS (eg,
+    /// an `ar://` URI).
+    pub async fn world_metadata_cid(&self) -> Result<Option<String>, CainomeError> {
+        let metadata = self.metadata(&Felt::ZERO).block_id(self.block_id).call().await?;
+        let uri = metadata.metadata_uri.to_string()?;
+
+        let cid = match Uri::from_string(&uri) {
+            Ok(uri) => uri.cid().map(|cid| cid.trim_end_matches('/').to_string()),
+            Err(_) => None,
+        };
+
+        Ok(cid.filter(|cid| !cid.is_empty()))
+    }
+
+    /// Reads the class hash of the base contract registered in the world, from which the models
+    /// and contracts of the world are deployed.
+    pub async fn base_class_hash(&self) -> Result<ClassHash, CainomeError> {
+        self.base().block_id(self.block_id).call().await
+    }
+
+    /// Reads the storage layout version of a contract through its `layout_version` entrypoint.
+    ///
+    /// Contracts that don't expose this entrypoint are considered to be at
+    /// [`DEFAULT_LAYOUT_VERSION`], any other failure of the call is returned.
+    pub async fn contract_layout_version(
+        &self,
+        contract_address: Felt,
+    ) -> Result<u32, CainomeError> {
+        let call = FunctionCall {
+            contract_address,
+            entry_point_selector: selector!("layout_version"),
+            calldata: vec![],
+        };
+
+        match self.provider().call(call, self.block_id).await {
+            Ok(res) => Ok(u32::cairo_deserialize(&res, 0)?),
+            Err(e) if is_entrypoint_not_found(&e) => Ok(DEFAULT_LAYOUT_VERSION),
+            Err(e) => Err(CainomeError::Provider(e)),
+        }
+    }
+
+    /// Returns the contracts currently registered in the world.
+    ///
+    /// The contract tags are collected from the `ContractDeployed` events of the world, in
+    /// deployment order, and the address and class hash of each contract are then read
+    /// concurrently from the resource registry at the block of the reader.
+    pub async fn registered_contracts(&self) -> Result<Vec<ContractInfo>, CainomeError> {
+        self.registered_resources(WorldEventKind::ContractDeployed, |tag, selector, resource| {
+            match resource {
+                abigen::world::Resource::Contract((class_hash, address)) => Some(ContractInfo {
+                    tag,
+                    selector,
+                    address: address.into(),
+                    class_hash: class_hash.into(),
+                }),
+                _ => None,
+            }
+        })
+        .await
+    }
+
+    /// Returns the models currently registered in the world.
+    ///
+    /// Like [`Self::registered_contracts`], the model tags are collected from the
+    /// `ModelRegistered` events of the world, in registration order, and the address and class
+    /// hash of each model are then read concurrently from the resource registry.
+    pub async fn registered_models(&self) -> Result<Vec<ModelInfo>, CainomeError> {
+        self.registered_resources(WorldEventKind::ModelRegistered, |tag, selector, resource| {
+            match resource {
+                abigen::world::Resource::Model((class_hash, address)) => Some(ModelInfo {
+                    tag,
+                    selector,
+                    address: address.into(),
+                    class_hash: class_hash.into(),
+                }),
+                _ => None,
+            }
+        })
+        .await
+    }
+
+    /// Reads the class hash of the contract registered with the given tag.
+    ///
+    /// The class hash is read at the address of the contract, since the resource registry keeps
+    /// the class the contract was deployed with.
+    ///
+    /// Returns `None` if no contract is registered with this tag.
+    pub async fn contract_class_hash(&self, tag: &str) -> Result<Option<Felt>, CainomeError> {
+        let selector = naming::compute_selector_from_tag(tag);
+
+        let address = match self.resource(&selector).block_id(self.block_id).call().await? {
+            abigen::world::Resource::Contract((_, address)) => address,
+            _ => return Ok(None),
+        };
+
+        let class_hash = self
+            .provider()
+            .get_class_hash_at(self.block_id, Felt::from(address))
+            .await
+            .map_err(CainomeError::Provider)?;
+
+        Ok(Some(class_hash))
+    }
+
+    /// Returns the contracts currently allowed to write to the resource with the given selector.
+    ///
+    /// Candidates are collected from the `WriterUpdated` events of the world, in the order they
+    /// were first granted, and each of them is confirmed with `is_writer` at the block of the
+    /// reader to exclude revoked permissions.
+    pub async fn contracts_writing(
+        &self,
+        resource: Felt,
+    ) -> Result<Vec<ContractAddress>, CainomeError> {
+        let writers = self.confirmed_writers(|r| r == resource).await?;
+        Ok(writers.into_iter().map(|(_, contract)| contract).collect())
+    }
+
+    /// Returns all the writer permissions currently granted in the world, as
+    /// `(resource selector, writer)` pairs.
+    ///
+    /// Like [`Self::contracts_writing`], the pairs are collected from the `WriterUpdated` events
+    /// of the world and each of them is confirmed with `is_writer` at the block of the reader.
+    pub async fn writers(&self) -> Result<Vec<(Felt, ContractAddress)>, CainomeError> {
+        self.confirmed_writers(|_| true).await
+    }
+
+    /// Returns the number of models registered in the world.
+    ///
+    /// The models are counted from the `ModelRegistered` events of the world, which is cheaper
+    /// than loading the whole remote manifest.
+    pub async fn model_count(&self) -> Result<usize, CainomeError> {
+        let mut tags = HashSet::new();
+
+        for event in self.world_events(WorldEventKind::ModelRegistered).await? {
+            if let Ok(WorldEvent::ModelRegistered(e)) = WorldEvent::try_from(event) {
+                tags.insert(naming::get_tag(&e.namespace.to_string()?, &e.name.to_string()?));
+            }
+        }
+
+        Ok(tags.len())
+    }
+
+    /// Computes a fingerprint of the world, which changes whenever the world is upgraded or a
+    /// model or a contract is registered or upgraded, for monitoring tools to poll.
+    ///
+    /// The fingerprint is the Poseidon hash of the world class hash, the selector and class hash
+    /// of each registered model, and the class hash of each registered contract. The models and
+    /// contracts are sorted first, so that it doesn't depend on the order of the events.
+    ///
+    /// The class hashes of the contracts are read at their address, since the resource registry
+    /// keeps the class the contract was deployed with.
+    pub async fn fingerprint(&self) -> Result<Felt, CainomeError> {
+        let world_class_hash = self
+            .provider()
+            .get_class_hash_at(self.block_id, self.address)
+            .await
+            .map_err(CainomeError::Provider)?;
+
+        let mut selectors = HashSet::new();
+        for event in self.world_events(WorldEventKind::ModelRegistered).await? {
+            if let Ok(WorldEvent::ModelRegistered(e)) = WorldEvent::try_from(event) {
+                let tag = naming::get_tag(&e.namespace.to_string()?, &e.name.to_string()?);
+                selectors.insert(naming::compute_selector_from_tag(&tag));
+            }
+        }
+
+        let resources = future::try_join_all(selectors.into_iter().map(|selector| async move {
+            let resource = self.resource(&selector).block_id(self.block_id).call().await?;
+            Ok::<_, CainomeError>((selector, resource))
+        }))
+        .await?;
+
+        let mut models = resources
+            .into_iter()
+            .filter_map(|(selector, resource)| match resource {
+                abigen::world::Resource::Model((class_hash, _)) => {
+                    Some((selector, class_hash.into()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<(Felt, Felt)>>();
+        models.sort();
+
+        let contracts = self.registered_contracts().await?;
+        let mut contracts = future::try_join_all(
+            contracts.iter().map(|c| self.provider().get_class_hash_at(self.block_id, c.address)),
+        )
+        .await
+        .map_err(CainomeError::Provider)?;
+        contracts.sort();
+
+        let mut data = vec![world_class_hash, models.len().into()];
+        data.extend(models.into_iter().flat_map(|(selector, class_hash)| [selector, class_hash]));
+        data.push(contracts.len().into());
+        data.extend(contracts);
+
+        Ok(poseidon_hash_many(&data))
+    }
+
+    /// Collects the `(resource, contract)` pairs of the `WriterUpdated` events of the world whose
+    /// resource matches `filter`, in the order they were first granted, and keeps the ones still
+    /// confirmed by `is_writer` at the block of the reader.
+    async fn confirmed_writers(
+        &self,
+        filter: impl Fn(Felt) -> bool,
+    ) -> Result<Vec<(Felt, ContractAddress)>, CainomeError> {
+        let mut candidates = vec![];
+
+        // `WriterUpdated` data is `[resource, contract, value]`.
+        for event in self.world_events(WorldEventKind::WriterUpdated).await? {
+            if let [resource, contract, ..] = event.data[..] {
+                if filter(resource) && !candidates.contains(&(resource, contract)) {
+                    candidates.push((resource, contract));
+                }
+            }
+        }
+
+        let mut writers = vec![];
+
+        for (resource, contract) in candidates {
+            let contract = ContractAddress(contract);
+
+            if self.is_writer(&resource, &contract).block_id(self.block_id).call().await? {
+                writers.push((resource, contract));
+            }
+        }
+
+        Ok(writers)
+    }
+
+    /// Collects the tags of the resources registered by the events of the given kind, and reads
+    /// their registry entry concurrently at the block of the reader.
+    ///
+    /// Each resource is built by `extract` from its tag, selector and registry entry, the ones
+    /// for which it returns `None` are skipped.
+    async fn registered_resources<T, F>(
+        &self,
+        kind: WorldEventKind,
+        extract: F,
+    ) -> Result<Vec<T>, CainomeError>
+    where
+        F: Fn(String, Felt, abigen::world::Resource) -> Option<T>,
+    {
+        let mut tags = vec![];
+
+        for event in self.world_events(kind).await? {
+            let (namespace, name) = match WorldEvent::try_from(event) {
+                Ok(WorldEvent::ContractDeployed(e)) => (e.namespace, e.name),
+                Ok(WorldEvent::ModelRegistered(e)) => (e.namespace, e.name),
+                _ => continue,
+            };
+
+            let tag = naming::get_tag(&namespace.to_string()?, &name.to_string()?);
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        let resources = future::try_join_all(tags.iter().map(|tag| async move {
+            let selector = naming::compute_selector_from_tag(tag);
+            let resource = self.resource(&selector).block_id(self.block_id).call().await?;
+            Ok::<_, CainomeError>((selector, resource))
+        }))
+        .await?;
+
+        Ok(tags
+            .into_iter()
+            .zip(resources)
+            .filter_map(|(tag, (selector, resource))| extract(tag, selector, resource))
+            .collect())
+    }
+
+    /// Fetches all the events of the given kind emitted by the world, page by page.
+    async fn world_events(&self, kind: WorldEventKind) -> Result<Vec<EmittedEvent>, CainomeError> {
+        const CHUNK_SIZE: u64 = 100;
+
+        let filter = EventFilter {
+            from_block: None,
+            to_block: None,
+            address: Some(self.address),
+            keys: Some(vec![vec![kind.selector()]]),
+        };
+
+        let mut events = vec![];
+        let mut continuation_token = None;
+
+        loop {
+            let page = self
+                .provider()
+                .get_events(filter.clone(), continuation_token, CHUNK_SIZE)
+                .await
+                .map_err(CainomeError::Provider)?;
+
+            events.extend(page.events);
+
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+/// Whether `error` reports a call to an entrypoint the contract doesn't expose.
+///
+/// Katana returns this failure as a contract error, whose message is then checked.
+fn is_entrypoint_not_found(error: &ProviderError) -> bool {
+    match error {
+        ProviderError::StarknetError(StarknetError::EntrypointNotFound) => true,
+        ProviderError::StarknetError(StarknetError::ContractError(data)) => {
+            let reason = data.revert_error.to_lowercase();
+            reason.contains("entry point") && reason.contains("not found")
+                || reason.contains("entrypoint_not_found")
+        }
+        _ => false,
+    }
 }
@@ -3,9 +3,11 @@ use dojo_test_utils::migration::{copy_spawn_and_move_db, prepare_migration_with_
 use katana_runner::{KatanaRunner, KatanaRunnerConfig};
 use scarb::compiler::Profile;
 use starknet::accounts::ConnectedAccount;
-use starknet::core::types::{BlockId, BlockTag};
+use starknet::core::types::{BlockId, BlockTag, ContractErrorData, Felt, StarknetError};
+use starknet::providers::ProviderError;
 
-use super::WorldContractReader;
+use super::{is_entrypoint_not_found, WorldContractReader};
+use crate::manifest::{BaseManifest, BASE_DIR, MANIFESTS_DIR};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_world_contract_reader() {
@@ -34,3 +36,51 @@ async fn test_world_contract_reader() {
 
     let _world = WorldContractReader::new(strat.world_address, provider);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn base_class_hash_matches_local_manifest() {
+    let setup = CompilerTestSetup::from_examples("../dojo-core", "../../examples/");
+    let config = setup.build_test_config("spawn-and-move", Profile::DEV);
+
+    let manifest_dir = config.manifest_path().parent().unwrap();
+    let target_dir = manifest_dir.join("target").join("dev");
+
+    let seq_config = KatanaRunnerConfig::default().with_db_dir(copy_spawn_and_move_db().as_str());
+    let sequencer = KatanaRunner::new_with_config(seq_config).expect("Failed to start runner.");
+
+    let (strat, _) = prepare_migration_with_world_and_seed(
+        manifest_dir.to_path_buf(),
+        target_dir.to_path_buf(),
+        None,
+        "dojo_examples",
+        "dojo_examples",
+    )
+    .unwrap();
+
+    let manifest =
+        BaseManifest::load_from_path(&manifest_dir.join(MANIFESTS_DIR).join("dev").join(BASE_DIR))
+            .unwrap();
+
+    let world = WorldContractReader::new(strat.world_address, sequencer.provider());
+    let base_class_hash = world.base_class_hash().await.unwrap();
+
+    assert_eq!(Felt::from(base_class_hash), manifest.base.inner.class_hash);
+}
+
+#[test]
+fn only_missing_entrypoints_fall_back_to_the_default_layout_version() {
+    let contract_error = |revert_error: &str| {
+        ProviderError::StarknetError(StarknetError::ContractError(ContractErrorData {
+            revert_error: revert_error.to_string(),
+        }))
+    };
+
+    assert!(is_entrypoint_not_found(&ProviderError::StarknetError(
+        StarknetError::EntrypointNotFound
+    )));
+    assert!(is_entrypoint_not_found(&contract_error("Entry point 0x1 not found in contract")));
+    assert!(!is_entrypoint_not_found(&contract_error("Failed to deserialize param #1")));
+    assert!(!is_entrypoint_not_found(&ProviderError::StarknetError(
+        StarknetError::ContractNotFound
+    )));
+}
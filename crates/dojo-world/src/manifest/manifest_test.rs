@@ -15,7 +15,8 @@ use starknet::macros::{felt, selector};
 use starknet::providers::jsonrpc::{JsonRpcClient, JsonRpcMethod};
 
 use super::{
-    parse_contracts_events, AbiFormat, BaseManifest, DojoContract, DojoModel, OverlayDojoContract,
+    parse_contracts_events, AbiFormat, BaseManifest, ContractGrants, DojoContract, DojoModel,
+    ManifestMethods, MergeConflict, OrphanEntry, OrphanEntryKind, OverlayDojoContract,
     OverlayManifest,
 };
 use crate::contracts::naming::{get_filename_from_tag, get_tag};
@@ -612,6 +613,200 @@ fn base_manifest_remove_items_work_as_expected() {
     );
 }
 
+#[test]
+fn base_manifest_validation_reports_duplicates_and_unknown_writes() {
+    let world = Manifest { manifest_name: "world".into(), inner: Default::default() };
+    let base = Manifest { manifest_name: "dojo-base".to_string(), inner: Default::default() };
+
+    let contract = |tag: &str, writes: &[&str]| Manifest {
+        manifest_name: tag.to_string(),
+        inner: DojoContract {
+            tag: tag.to_string(),
+            writes: writes.iter().map(|w| w.to_string()).collect(),
+            ..Default::default()
+        },
+    };
+    let models = ["ns-Position", "ns-Moves"]
+        .iter()
+        .map(|m| Manifest {
+            manifest_name: m.to_string(),
+            inner: DojoModel { tag: m.to_string(), ..Default::default() },
+        })
+        .collect();
+
+    let mut base = BaseManifest {
+        contracts: vec![contract("ns-actions", &["ns:ns", "m:ns-Moves", "Position"])],
+        models,
+        world,
+        base,
+    };
+
+    assert!(base.validate().is_ok());
+
+    base.contracts.push(contract("ns-Position", &[]));
+    base.contracts.push(contract("ns-others", &["m:ns-Health", "Moves", "c:ns-unknown"]));
+
+    match base.validate() {
+        Err(AbstractManifestError::InvalidManifest(problems)) => assert_eq!(
+            problems,
+            vec![
+                "`ns-Position` is declared twice".to_string(),
+                "`ns-others` writes to the unknown model `m:ns-Health`".to_string(),
+            ]
+        ),
+        res => panic!("expected an invalid manifest, got {res:?}"),
+    }
+}
+
+#[test]
+fn overlay_entries_referencing_removed_model_are_orphans() {
+    let world = Manifest { manifest_name: "world".into(), inner: Default::default() };
+    let base = Manifest { manifest_name: "dojo-base".to_string(), inner: Default::default() };
+
+    let contracts = vec![Manifest {
+        manifest_name: "ns-actions".to_string(),
+        inner: DojoContract { tag: "ns-actions".to_string(), ..Default::default() },
+    }];
+    let models = ["ns-Position", "ns-Moves"]
+        .iter()
+        .map(|m| Manifest {
+            manifest_name: m.to_string(),
+            inner: DojoModel { tag: m.to_string(), ..Default::default() },
+        })
+        .collect();
+
+    let mut base = BaseManifest { contracts, models, world, base };
+
+    let overlay = OverlayManifest {
+        contracts: vec![OverlayDojoContract {
+            tag: "ns-actions".into(),
+            writes: Some(vec!["ns:ns".into(), "m:ns-Moves".into(), "Position".into()]),
+            owners: Some(vec!["c:ns-actions".into()]),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    assert_eq!(overlay.validate_against(&base), Ok(()));
+
+    base.remove_tags(vec!["ns-Moves".to_string()]);
+
+    assert_eq!(
+        overlay.validate_against(&base),
+        Err(vec![OrphanEntry {
+            overlay_tag: "ns-actions".into(),
+            kind: OrphanEntryKind::Write,
+            resource: "m:ns-Moves".into(),
+        }])
+    );
+}
+
+#[test]
+fn overlay_grants_are_merged_with_writes_and_owners() {
+    let overlay: OverlayDojoContract = toml::from_str(
+        r#"
+        tag = "ns-actions"
+        writes = ["ns:ns"]
+
+        [grants]
+        writer = ["ns:ns", "m:ns-Moves"]
+        owner = ["c:ns-actions"]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        overlay.grants,
+        Some(ContractGrants {
+            writer: vec!["ns:ns".into(), "m:ns-Moves".into()],
+            owner: vec!["c:ns-actions".into()],
+        })
+    );
+
+    let mut contract = DojoContract { tag: "ns-actions".into(), ..Default::default() };
+    contract.merge(overlay);
+
+    assert_eq!(contract.writes, vec!["ns:ns".to_string(), "m:ns-Moves".to_string()]);
+    assert_eq!(contract.owners, vec!["c:ns-actions".to_string()]);
+
+    // The world has no reader role, such a grant is rejected rather than ignored.
+    let res = toml::from_str::<OverlayDojoContract>(
+        r#"
+        tag = "ns-actions"
+
+        [grants]
+        reader = ["m:ns-Moves"]
+        "#,
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn try_merge_reports_conflicting_overlays() {
+    let contracts = ["ns-actions", "ns-others"]
+        .iter()
+        .map(|tag| Manifest {
+            manifest_name: tag.to_string(),
+            inner: DojoContract {
+                tag: tag.to_string(),
+                writes: vec!["ns:ns".into()],
+                ..Default::default()
+            },
+        })
+        .collect();
+
+    let world = Manifest { manifest_name: "world".into(), inner: Default::default() };
+    let base = Manifest { manifest_name: "dojo-base".to_string(), inner: Default::default() };
+    let base = BaseManifest { contracts, models: vec![], world, base };
+
+    let overlay = OverlayManifest {
+        contracts: vec![
+            OverlayDojoContract {
+                tag: "ns-actions".into(),
+                writes: Some(vec!["m:ns-Moves".into()]),
+                init_calldata: Some(vec!["0x1".into()]),
+                ..Default::default()
+            },
+            OverlayDojoContract {
+                tag: "ns-actions".into(),
+                init_calldata: Some(vec!["0x2".into()]),
+                ..Default::default()
+            },
+            // Same value as the base manifest, no conflict.
+            OverlayDojoContract {
+                tag: "ns-others".into(),
+                writes: Some(vec!["ns:ns".into()]),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let mut merged = base.clone();
+    assert_eq!(
+        merged.try_merge(overlay),
+        Err(vec![MergeConflict {
+            tag: "ns-actions".into(),
+            fields: vec!["init_calldata".into(), "writes".into()],
+        }])
+    );
+    // Nothing is merged if there is a conflict.
+    assert_eq!(merged, base);
+
+    let overlay = OverlayManifest {
+        contracts: vec![OverlayDojoContract {
+            tag: "ns-actions".into(),
+            init_calldata: Some(vec!["0x1".into()]),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let mut merged = base.clone();
+    merged.try_merge(overlay).unwrap();
+    assert_eq!(merged.contracts[0].inner.init_calldata, vec!["0x1".to_string()]);
+}
+
 fn serialize_bytearray(s: &str) -> Vec<Felt> {
     let ba = ByteArray::from_string(s).unwrap();
     ByteArray::cairo_serialize(&ba)
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::{fs, io};
 
@@ -8,9 +8,7 @@ use camino::Utf8PathBuf;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use starknet::core::types::{BlockId, BlockTag, EmittedEvent, EventFilter, Felt, StarknetError};
-use starknet::core::utils::{
-    starknet_keccak, CairoShortStringToFeltError, ParseCairoShortStringError,
-};
+use starknet::core::utils::{CairoShortStringToFeltError, ParseCairoShortStringError};
 use starknet::providers::{Provider, ProviderError};
 use thiserror::Error;
 use toml;
@@ -20,7 +18,7 @@ use walkdir::WalkDir;
 
 use crate::contracts::model::ModelError;
 use crate::contracts::world::WorldEvent;
-use crate::contracts::{naming, WorldContractReader};
+use crate::contracts::{naming, WorldContractReader, WorldEventKind};
 
 #[cfg(test)]
 #[path = "manifest_test.rs"]
@@ -29,9 +27,10 @@ mod test;
 mod types;
 
 pub use types::{
-    AbiFormat, BaseManifest, Class, DeploymentManifest, DojoContract, DojoModel, Manifest,
-    ManifestMethods, Member, OverlayClass, OverlayContract, OverlayDojoContract, OverlayDojoModel,
-    OverlayManifest, WorldContract, WorldMetadata,
+    AbiFormat, BaseManifest, Class, ContractGrants, DeploymentManifest, DojoContract, DojoModel,
+    Manifest, ManifestMethods, Member, MergeConflict, OrphanEntry, OrphanEntryKind, OverlayClass,
+    OverlayContract, OverlayDojoContract, OverlayDojoModel, OverlayManifest, WorldContract,
+    WorldMetadata,
 };
 
 pub const WORLD_CONTRACT_TAG: &str = "dojo-world";
@@ -82,6 +81,8 @@ pub enum AbstractManifestError {
     TagError(String),
     #[error("{0}")]
     UnknownTarget(String),
+    #[error("Invalid manifest: {}", .0.join(", "))]
+    InvalidManifest(Vec<String>),
 }
 
 impl From<Manifest<Class>> for Manifest<WorldContract> {
@@ -123,7 +124,50 @@ impl BaseManifest {
         let contracts = elements_from_path::<DojoContract>(&path.join(CONTRACTS_DIR))?;
         let models = elements_from_path::<DojoModel>(&path.join(MODELS_DIR))?;
 
-        Ok(Self { world, base, contracts, models })
+        let manifest = Self { world, base, contracts, models };
+        manifest.validate()?;
+
+        Ok(manifest)
+    }
+
+    /// Checks that the tags of the models and contracts are unique, and that the contracts only
+    /// write to models of the manifest.
+    ///
+    /// Every problem found is listed in the returned [`AbstractManifestError::InvalidManifest`].
+    /// The writes are resolved like the ones of an overlay, see
+    /// [`OverlayManifest::validate_against`], and only those targeting a model are checked.
+    pub fn validate(&self) -> Result<(), AbstractManifestError> {
+        let models = self.models.iter().map(|m| m.inner.tag.as_str()).collect::<Vec<_>>();
+        let contracts = self.contracts.iter().map(|c| c.inner.tag.as_str()).collect::<Vec<_>>();
+
+        let mut problems = vec![];
+
+        let mut seen = HashSet::new();
+        let mut duplicated = vec![];
+        for tag in models.iter().chain(contracts.iter()) {
+            if !seen.insert(tag) && !duplicated.contains(&tag) {
+                duplicated.push(tag);
+            }
+        }
+        problems.extend(duplicated.into_iter().map(|tag| format!("`{tag}` is declared twice")));
+
+        for contract in &self.contracts {
+            for write in &contract.inner.writes {
+                let is_model = matches!(write.split_once(':'), None | Some(("model" | "m", _)));
+                if is_model && !resource_exists(write, &models, &contracts) {
+                    problems.push(format!(
+                        "`{}` writes to the unknown model `{write}`",
+                        contract.inner.tag
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(AbstractManifestError::InvalidManifest(problems))
+        }
     }
 
     /// Given a list of contract or model tags, remove those from the manifest.
@@ -150,6 +194,20 @@ impl BaseManifest {
         kind_from_tags
     }
 
+    /// Returns the selector of every model and contract of the manifest, by tag.
+    ///
+    /// The selectors are those the world registers the resources with, computed from their
+    /// tags with [`naming::compute_selector_from_tag`].
+    pub fn resource_selectors(&self) -> HashMap<String, Felt> {
+        let models = self.models.iter().map(|m| &m.inner.tag);
+        let contracts = self.contracts.iter().map(|c| &c.inner.tag);
+
+        models
+            .chain(contracts)
+            .map(|tag| (tag.clone(), naming::compute_selector_from_tag(tag)))
+            .collect()
+    }
+
     pub fn merge(&mut self, overlay: OverlayManifest) {
         let mut base_map = HashMap::new();
 
@@ -176,6 +234,85 @@ impl BaseManifest {
             self.base.inner.merge(overlay_base);
         }
     }
+
+    /// Merges `overlay` like [`Self::merge`], unless a contract field is set to different values
+    /// by the manifest and the overlay, or by several overlays of the same contract.
+    ///
+    /// The conflicts are reported, by contract, instead of letting the last value win, and
+    /// nothing is merged in that case. A field left empty in the manifest never conflicts.
+    pub fn try_merge(&mut self, overlay: OverlayManifest) -> Result<(), Vec<MergeConflict>> {
+        let mut conflicts: Vec<MergeConflict> = vec![];
+
+        for (i, contract) in overlay.contracts.iter().enumerate() {
+            let mut fields = vec![];
+
+            if let Some(base) = self.contracts.iter().find(|c| c.inner.tag == contract.tag) {
+                fields.extend(conflicting_fields(&base_contract_fields(&base.inner), contract));
+            }
+
+            for other in overlay.contracts[..i].iter().filter(|c| c.tag == contract.tag) {
+                fields.extend(conflicting_fields(&overlay_contract_fields(other), contract));
+            }
+
+            if fields.is_empty() {
+                continue;
+            }
+
+            match conflicts.iter_mut().find(|c| c.tag == contract.tag) {
+                Some(conflict) => conflict.fields.extend(fields),
+                None => conflicts.push(MergeConflict { tag: contract.tag.clone(), fields }),
+            }
+        }
+
+        for conflict in &mut conflicts {
+            conflict.fields.sort();
+            conflict.fields.dedup();
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        self.merge(overlay);
+        Ok(())
+    }
+}
+
+/// The fields of a contract an overlay can set, with their value if set.
+type ContractFields<'a> = [(&'static str, Option<&'a [String]>); 4];
+
+fn base_contract_fields(contract: &DojoContract) -> ContractFields<'_> {
+    fn set(values: &[String]) -> Option<&[String]> {
+        (!values.is_empty()).then_some(values)
+    }
+
+    [
+        ("reads", set(&contract.reads)),
+        ("writes", set(&contract.writes)),
+        ("owners", set(&contract.owners)),
+        ("init_calldata", set(&contract.init_calldata)),
+    ]
+}
+
+fn overlay_contract_fields(contract: &OverlayDojoContract) -> ContractFields<'_> {
+    [
+        ("reads", contract.reads.as_deref()),
+        ("writes", contract.writes.as_deref()),
+        ("owners", contract.owners.as_deref()),
+        ("init_calldata", contract.init_calldata.as_deref()),
+    ]
+}
+
+/// Returns the names of the fields `overlay` sets to another value than in `current`.
+fn conflicting_fields(current: &ContractFields<'_>, overlay: &OverlayDojoContract) -> Vec<String> {
+    current
+        .iter()
+        .zip(overlay_contract_fields(overlay))
+        .filter_map(|((name, current), (_, new))| match (current, new) {
+            (Some(current), Some(new)) if *current != new => Some(name.to_string()),
+            _ => None,
+        })
+        .collect()
 }
 
 #[derive(Clone, Debug, Copy)]
@@ -302,6 +439,67 @@ impl OverlayManifest {
         Ok(())
     }
 
+    /// Checks that every resource referenced by the overlays exists in `base`.
+    ///
+    /// Reports the overlays targeting a model or a contract absent from `base`, and the reads,
+    /// writes and owners referencing one, like the stale entries left in an overlay once a model
+    /// is removed. A resource is written `type:name` like in `sozo auth`, `name` being a tag or,
+    /// without namespace, the name of the resource in any namespace. A namespace is known if a
+    /// model or a contract of `base` is in it, and selectors can't be checked.
+    pub fn validate_against(&self, base: &BaseManifest) -> Result<(), Vec<OrphanEntry>> {
+        let models = base.models.iter().map(|m| m.inner.tag.as_str()).collect::<Vec<_>>();
+        let contracts = base.contracts.iter().map(|c| c.inner.tag.as_str()).collect::<Vec<_>>();
+
+        let mut orphans = vec![];
+
+        for model in &self.models {
+            if !models.contains(&model.tag.as_str()) {
+                orphans.push(OrphanEntry {
+                    overlay_tag: model.tag.clone(),
+                    kind: OrphanEntryKind::Target,
+                    resource: model.tag.clone(),
+                });
+            }
+        }
+
+        for contract in &self.contracts {
+            if !contracts.contains(&contract.tag.as_str()) {
+                orphans.push(OrphanEntry {
+                    overlay_tag: contract.tag.clone(),
+                    kind: OrphanEntryKind::Target,
+                    resource: contract.tag.clone(),
+                });
+            }
+
+            let grants = contract.grants.clone().unwrap_or_default();
+            let entries = [
+                (OrphanEntryKind::Read, contract.reads.clone().unwrap_or_default()),
+                (
+                    OrphanEntryKind::Write,
+                    [contract.writes.clone().unwrap_or_default(), grants.writer].concat(),
+                ),
+                (
+                    OrphanEntryKind::Owner,
+                    [contract.owners.clone().unwrap_or_default(), grants.owner].concat(),
+                ),
+            ];
+
+            for (kind, resources) in entries {
+                for resource in resources {
+                    if !resource_exists(&resource, &models, &contracts) {
+                        orphans.push(OrphanEntry {
+                            overlay_tag: contract.tag.clone(),
+                            kind,
+                            resource,
+                        });
+                    }
+                }
+            }
+        }
+
+        if orphans.is_empty() { Ok(()) } else { Err(orphans) }
+    }
+
     /// Add missing overlay items from `others` to `self`.
     /// Note that this method don't override if certain item already exists in `self`.
     pub fn merge(&mut self, other: OverlayManifest) {
@@ -329,6 +527,29 @@ impl OverlayManifest {
     }
 }
 
+/// Returns `true` if the `type:name` resource of an overlay is among `models` and `contracts`,
+/// a resource without type being a model.
+fn resource_exists(resource: &str, models: &[&str], contracts: &[&str]) -> bool {
+    let matches = |tags: &[&str], name: &str| {
+        if name.contains(naming::TAG_SEPARATOR) {
+            tags.contains(&name)
+        } else {
+            tags.iter().any(|tag| naming::get_name_from_tag(tag) == name)
+        }
+    };
+
+    match resource.split_once(':') {
+        Some(("model", name)) | Some(("m", name)) => matches(models, name),
+        Some(("contract", name)) | Some(("c", name)) => matches(contracts, name),
+        Some(("namespace", namespace)) | Some(("ns", namespace)) => models
+            .iter()
+            .chain(contracts.iter())
+            .any(|tag| naming::get_namespace_from_tag(tag) == namespace),
+        Some(_) => true,
+        None => matches(models, resource),
+    }
+}
+
 impl DeploymentManifest {
     pub fn load_from_path(path: &Utf8PathBuf) -> Result<Self, AbstractManifestError> {
         let manifest: Self = toml::from_str(&fs::read_to_string(path)?).unwrap();
@@ -481,10 +702,10 @@ async fn get_remote_models_and_contracts<P>(
 where
     P: Provider + Send + Sync,
 {
-    let registered_models_event_name = starknet_keccak("ModelRegistered".as_bytes());
-    let contract_deployed_event_name = starknet_keccak("ContractDeployed".as_bytes());
-    let contract_upgraded_event_name = starknet_keccak("ContractUpgraded".as_bytes());
-    let writer_updated_event_name = starknet_keccak("WriterUpdated".as_bytes());
+    let registered_models_event_name = WorldEventKind::ModelRegistered.selector();
+    let contract_deployed_event_name = WorldEventKind::ContractDeployed.selector();
+    let contract_upgraded_event_name = WorldEventKind::ContractUpgraded.selector();
+    let writer_updated_event_name = WorldEventKind::WriterUpdated.selector();
 
     let events = get_events(
         &provider,
@@ -806,9 +1027,27 @@ impl ManifestMethods for DojoContract {
         if let Some(writes) = old.writes {
             self.writes = writes;
         }
+        if let Some(owners) = old.owners {
+            self.owners = owners;
+        }
+        if let Some(grants) = old.grants {
+            for writer in grants.writer {
+                if !self.writes.contains(&writer) {
+                    self.writes.push(writer);
+                }
+            }
+            for owner in grants.owner {
+                if !self.owners.contains(&owner) {
+                    self.owners.push(owner);
+                }
+            }
+        }
         if let Some(init_calldata) = old.init_calldata {
             self.init_calldata = init_calldata;
         }
+        if let Some(layout_version) = old.layout_version {
+            self.layout_version = Some(layout_version);
+        }
     }
 }
 
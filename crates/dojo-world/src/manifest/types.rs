@@ -1,3 +1,4 @@
+use std::fmt::Display;
 use std::fs;
 
 use camino::Utf8PathBuf;
@@ -103,10 +104,17 @@ pub struct DojoContract {
     pub reads: Vec<String>,
     #[serde(default)]
     pub writes: Vec<String>,
+    /// Resources the contract is granted ownership of.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
     #[serde(default)]
     pub init_calldata: Vec<String>,
     pub tag: String,
     pub systems: Vec<String>,
+    /// The storage layout version the class of the contract expects, checked against the
+    /// deployed contract before upgrading it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout_version: Option<u32>,
 }
 
 /// Represents a declaration of a model.
@@ -165,7 +173,26 @@ pub struct OverlayDojoContract {
     pub original_class_hash: Option<Felt>,
     pub reads: Option<Vec<String>>,
     pub writes: Option<Vec<String>>,
+    pub owners: Option<Vec<String>>,
+    pub grants: Option<ContractGrants>,
     pub init_calldata: Option<Vec<String>>,
+    pub layout_version: Option<u32>,
+}
+
+/// Permissions granted to a contract, declared in the `[grants]` section of its overlay.
+///
+/// They are added to the `writes` and `owners` of the contract, which remain supported on their
+/// own.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+#[serde(deny_unknown_fields)]
+pub struct ContractGrants {
+    /// Resources the contract is granted write access to.
+    #[serde(default)]
+    pub writer: Vec<String>,
+    /// Resources the contract is granted ownership of.
+    #[serde(default)]
+    pub owner: Vec<String>,
 }
 
 #[serde_as]
@@ -192,6 +219,65 @@ pub struct OverlayClass {
     pub original_class_hash: Option<Felt>,
 }
 
+/// An entry of an overlay referencing a resource which isn't in the base manifest, as reported
+/// by [`OverlayManifest::validate_against`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrphanEntry {
+    /// The tag of the overlay containing the entry.
+    pub overlay_tag: String,
+    pub kind: OrphanEntryKind,
+    /// The referenced resource, as written in the overlay.
+    pub resource: String,
+}
+
+/// Where an [`OrphanEntry`] was found in its overlay.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrphanEntryKind {
+    /// The overlay itself targets a model or a contract which isn't in the base manifest.
+    Target,
+    Read,
+    Write,
+    Owner,
+}
+
+impl Display for OrphanEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entry = match self.kind {
+            OrphanEntryKind::Target => "target",
+            OrphanEntryKind::Read => "read",
+            OrphanEntryKind::Write => "write",
+            OrphanEntryKind::Owner => "owner",
+        };
+
+        write!(
+            f,
+            "The {entry} '{}' of the overlay '{}' is absent from the base manifest.",
+            self.resource, self.overlay_tag
+        )
+    }
+}
+
+/// A contract whose fields are set to different values by the manifests being merged, as
+/// reported by [`BaseManifest::try_merge`](super::BaseManifest::try_merge).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// The tag of the contract.
+    pub tag: String,
+    /// The names of the conflicting fields, like `writes` or `init_calldata`.
+    pub fields: Vec<String>,
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Conflicting values of {} for the contract '{}'.",
+            self.fields.join(", "),
+            self.tag
+        )
+    }
+}
+
 // Types used by manifest
 
 /// Represents a model member.
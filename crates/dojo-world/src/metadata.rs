@@ -1,9 +1,12 @@
-use std::collections::HashMap;
-use std::io::Cursor;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
 
 use anyhow::Result;
 use camino::Utf8PathBuf;
-use ipfs_api_backend_hyper::{IpfsApi, IpfsClient, TryFromUri};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use ipfs_api_backend_hyper::request;
 use scarb::core::{Package, TargetKind, Workspace};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -16,10 +19,17 @@ use crate::uri::Uri;
 
 const LOG_TARGET: &str = "dojo_world::metadata";
 
+mod storage;
+
 #[cfg(test)]
 #[path = "metadata_test.rs"]
 mod test;
 
+pub use storage::{
+    ArweaveStorage, IpfsStorage, MetadataStorage, PinataAuth, PinataStorage, ARWEAVE_GATEWAY_URL,
+    PINATA_API_URL,
+};
+
 pub const IPFS_CLIENT_URL: &str = "https://ipfs.infura.io:5001";
 pub const IPFS_USERNAME: &str = "2EBrzr7ZASQZKH32sl2xWauXPSA";
 pub const IPFS_PASSWORD: &str = "12290b883db9138a8ae3363b6739d220";
@@ -98,6 +108,26 @@ pub fn dojo_metadata_from_package(package: &Package, ws: &Workspace<'_>) -> Resu
 
     metadata_artifacts_load(&mut dojo_metadata, ws)?;
 
+    for (tag, extra) in profile_config.metadata.unwrap_or_default() {
+        if tag == WORLD_CONTRACT_TAG {
+            dojo_metadata.world.artifacts.extra = extra;
+        } else if let Some(resource) = dojo_metadata.resources_artifacts.get_mut(&tag) {
+            resource.artifacts.extra = extra;
+        } else {
+            tracing::warn!(target: LOG_TARGET, %tag, "Metadata configured for an unknown resource.");
+        }
+    }
+
+    if dojo_metadata.migration.as_ref().is_some_and(|m| m.compress_metadata) {
+        let encoding = Some(ContentEncoding::Gzip);
+
+        dojo_metadata.world.artifacts.content_encoding = encoding;
+        dojo_metadata
+            .resources_artifacts
+            .values_mut()
+            .for_each(|r| r.artifacts.content_encoding = encoding);
+    }
+
     tracing::trace!(target: LOG_TARGET, ?dojo_metadata);
 
     Ok(dojo_metadata)
@@ -206,6 +236,8 @@ fn build_artifact_from_filename(
         } else {
             None
         },
+        content_encoding: None,
+        extra: BTreeMap::new(),
     }
 }
 
@@ -231,6 +263,116 @@ pub struct DojoMetadata {
 pub struct ArtifactMetadata {
     pub abi: Option<Uri>,
     pub source: Option<Uri>,
+    /// The encoding of the `abi` and `source` content once uploaded, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_encoding: Option<ContentEncoding>,
+    /// Custom fields uploaded along with the artifacts (e.g. a display name, a category or a
+    /// documentation URL), left to the tooling to interpret.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Encoding applied to the content of the artifacts before uploading them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentEncoding {
+    Gzip,
+}
+
+/// Options applied when adding content to IPFS.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct UploadConfig {
+    /// Whether the added content is pinned, or only added to the node.
+    pub pin: bool,
+    /// The version of the CIDs produced for the added content, which is reflected in the
+    /// `ipfs://` URIs registered on-chain.
+    pub cid_version: CidVersion,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self { pin: true, cid_version: CidVersion::V0 }
+    }
+}
+
+impl UploadConfig {
+    fn add_options(&self) -> request::Add<'static> {
+        request::Add {
+            pin: Some(self.pin),
+            cid_version: Some(self.cid_version as u32),
+            ..Default::default()
+        }
+    }
+}
+
+/// The storage the metadata are uploaded to by the migration.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum StorageConfig {
+    /// The default IPFS node, the metadata being referenced by `ipfs://` URIs.
+    #[default]
+    Ipfs,
+    /// An Arweave upload gateway, the metadata being referenced by `ar://` URIs.
+    Arweave {
+        /// The URL of the upload gateway.
+        url: String,
+        /// The API key authenticating the uploads, if the gateway requires one.
+        #[serde(default)]
+        api_key: Option<String>,
+    },
+}
+
+impl StorageConfig {
+    /// Builds the storage the metadata are uploaded to.
+    pub fn storage(&self) -> Result<Box<dyn MetadataStorage>> {
+        Ok(match self {
+            Self::Ipfs => Box::new(IpfsStorage::default_node()?),
+            Self::Arweave { url, api_key } => {
+                let storage = ArweaveStorage::new(url.as_str());
+                match api_key {
+                    Some(api_key) => Box::new(storage.with_api_key(api_key.as_str())),
+                    None => Box::new(storage),
+                }
+            }
+        })
+    }
+}
+
+/// Version of the CIDs produced by IPFS.
+///
+/// CIDv0 hashes are base58 encoded (`Qm...`) whereas CIDv1 hashes are base32 encoded
+/// (`bafy...`).
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CidVersion {
+    #[default]
+    V0 = 0,
+    V1 = 1,
+}
+
+/// Encodes the content of an artifact before uploading it.
+pub fn encode_artifact(data: Vec<u8>, encoding: Option<ContentEncoding>) -> Result<Vec<u8>> {
+    match encoding {
+        None => Ok(data),
+        Some(ContentEncoding::Gzip) => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&data)?;
+            Ok(encoder.finish()?)
+        }
+    }
+}
+
+/// Decodes the content of an uploaded artifact back to its original bytes.
+pub fn decode_artifact(data: &[u8], encoding: Option<ContentEncoding>) -> Result<Vec<u8>> {
+    match encoding {
+        None => Ok(data.to_vec()),
+        Some(ContentEncoding::Gzip) => {
+            let mut decoded = Vec::new();
+            GzDecoder::new(data).read_to_end(&mut decoded)?;
+            Ok(decoded)
+        }
+    }
 }
 
 /// World metadata collected from the project configuration and the Dojo workspace
@@ -262,100 +404,97 @@ impl From<WorldConfig> for WorldMetadata {
 }
 
 impl WorldMetadata {
-    pub async fn upload(&self) -> Result<String> {
+    /// Uploads the metadata to the default IPFS node, see [`WorldMetadata::upload_to`].
+    pub async fn upload(&self, config: &UploadConfig) -> Result<String> {
+        self.upload_to(&IpfsStorage::default_node()?, config).await
+    }
+
+    /// Uploads the local files referenced by the metadata and then the metadata itself to
+    /// `storage`, returning the CID of the metadata.
+    ///
+    /// The uploaded files are referenced by the URIs of `storage`, `ipfs://` or `ar://`.
+    pub async fn upload_to(
+        &self,
+        storage: &dyn MetadataStorage,
+        config: &UploadConfig,
+    ) -> Result<String> {
         let mut meta = self.clone();
-        let client =
-            IpfsClient::from_str(IPFS_CLIENT_URL)?.with_credentials(IPFS_USERNAME, IPFS_PASSWORD);
 
         if let Some(Uri::File(icon)) = &self.icon_uri {
-            let icon_data = std::fs::read(icon)?;
-            let reader = Cursor::new(icon_data);
-            let response = client.add(reader).await?;
-            meta.icon_uri = Some(Uri::Ipfs(format!("ipfs://{}", response.hash)))
+            let cid = storage.add(std::fs::read(icon)?, config).await?;
+            meta.icon_uri = Some(storage.uri(&cid))
         };
 
         if let Some(Uri::File(cover)) = &self.cover_uri {
-            let cover_data = std::fs::read(cover)?;
-            let reader = Cursor::new(cover_data);
-            let response = client.add(reader).await?;
-            meta.cover_uri = Some(Uri::Ipfs(format!("ipfs://{}", response.hash)))
-        };
-
-        if let Some(Uri::File(abi)) = &self.artifacts.abi {
-            let abi_data = std::fs::read(abi)?;
-            let reader = Cursor::new(abi_data);
-            let response = client.add(reader).await?;
-            meta.artifacts.abi = Some(Uri::Ipfs(format!("ipfs://{}", response.hash)))
-        };
-
-        if let Some(Uri::File(source)) = &self.artifacts.source {
-            let source_data = std::fs::read(source)?;
-            let reader = Cursor::new(source_data);
-            let response = client.add(reader).await?;
-            meta.artifacts.source = Some(Uri::Ipfs(format!("ipfs://{}", response.hash)))
+            let cid = storage.add(std::fs::read(cover)?, config).await?;
+            meta.cover_uri = Some(storage.uri(&cid))
         };
 
-        let serialized = json!(meta).to_string();
-        let reader = Cursor::new(serialized);
-        let response = client.add(reader).await?;
+        meta.artifacts = self.artifacts.upload_files(storage, config).await?;
 
-        Ok(response.hash)
+        storage.add(json!(meta).to_string().into_bytes(), config).await
     }
 }
 
 impl ArtifactMetadata {
-    pub async fn upload(&self) -> Result<String> {
+    /// Uploads the metadata to the default IPFS node, see [`ArtifactMetadata::upload_to`].
+    pub async fn upload(&self, config: &UploadConfig) -> Result<String> {
+        self.upload_to(&IpfsStorage::default_node()?, config).await
+    }
+
+    /// Uploads the local artifacts and then the metadata itself to `storage`, returning the CID
+    /// of the metadata.
+    pub async fn upload_to(
+        &self,
+        storage: &dyn MetadataStorage,
+        config: &UploadConfig,
+    ) -> Result<String> {
+        let meta = self.upload_files(storage, config).await?;
+        storage.add(json!(meta).to_string().into_bytes(), config).await
+    }
+
+    /// Uploads the local artifacts to `storage`, returning the metadata referencing their
+    /// `ipfs://` URIs instead.
+    async fn upload_files(
+        &self,
+        storage: &dyn MetadataStorage,
+        config: &UploadConfig,
+    ) -> Result<ArtifactMetadata> {
         let mut meta = self.clone();
-        let client =
-            IpfsClient::from_str(IPFS_CLIENT_URL)?.with_credentials(IPFS_USERNAME, IPFS_PASSWORD);
 
         if let Some(Uri::File(abi)) = &self.abi {
-            let abi_data = std::fs::read(abi)?;
-            let reader = Cursor::new(abi_data);
-            let response = client.add(reader).await?;
-            meta.abi = Some(Uri::Ipfs(format!("ipfs://{}", response.hash)))
+            let abi_data = encode_artifact(std::fs::read(abi)?, self.content_encoding)?;
+            let cid = storage.add(abi_data, config).await?;
+            meta.abi = Some(storage.uri(&cid))
         };
 
         if let Some(Uri::File(source)) = &self.source {
-            let source_data = std::fs::read(source)?;
-            let reader = Cursor::new(source_data);
-            let response = client.add(reader).await?;
-            meta.source = Some(Uri::Ipfs(format!("ipfs://{}", response.hash)))
+            let source_data = encode_artifact(std::fs::read(source)?, self.content_encoding)?;
+            let cid = storage.add(source_data, config).await?;
+            meta.source = Some(storage.uri(&cid))
         };
 
-        let serialized = json!(meta).to_string();
-        let reader = Cursor::new(serialized);
-        let response = client.add(reader).await?;
-
-        Ok(response.hash)
+        Ok(meta)
     }
 }
 
 impl ResourceMetadata {
-    pub async fn upload(&self) -> Result<String> {
-        let mut meta = self.clone();
-        let client =
-            IpfsClient::from_str(IPFS_CLIENT_URL)?.with_credentials(IPFS_USERNAME, IPFS_PASSWORD);
-
-        if let Some(Uri::File(abi)) = &self.artifacts.abi {
-            let abi_data = std::fs::read(abi)?;
-            let reader = Cursor::new(abi_data);
-            let response = client.add(reader).await?;
-            meta.artifacts.abi = Some(Uri::Ipfs(format!("ipfs://{}", response.hash)))
-        };
-
-        if let Some(Uri::File(source)) = &self.artifacts.source {
-            let source_data = std::fs::read(source)?;
-            let reader = Cursor::new(source_data);
-            let response = client.add(reader).await?;
-            meta.artifacts.source = Some(Uri::Ipfs(format!("ipfs://{}", response.hash)))
-        };
+    /// Uploads the metadata to the default IPFS node, see [`ResourceMetadata::upload_to`].
+    pub async fn upload(&self, config: &UploadConfig) -> Result<String> {
+        self.upload_to(&IpfsStorage::default_node()?, config).await
+    }
 
-        let serialized = json!(meta).to_string();
-        let reader = Cursor::new(serialized);
-        let response = client.add(reader).await?;
+    /// Uploads the local artifacts and then the metadata itself to `storage`, returning the CID
+    /// of the metadata.
+    pub async fn upload_to(
+        &self,
+        storage: &dyn MetadataStorage,
+        config: &UploadConfig,
+    ) -> Result<String> {
+        let mut meta = self.clone();
+        meta.artifacts = self.artifacts.upload_files(storage, config).await?;
 
-        Ok(response.hash)
+        storage.add(json!(meta).to_string().into_bytes(), config).await
     }
 }
 
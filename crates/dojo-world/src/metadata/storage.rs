@@ -0,0 +1,209 @@
+use std::io::Cursor;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use ipfs_api_backend_hyper::{IpfsApi, IpfsClient, TryFromUri};
+use reqwest::multipart::{Form, Part};
+use reqwest::RequestBuilder;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{UploadConfig, IPFS_CLIENT_URL, IPFS_PASSWORD, IPFS_USERNAME};
+use crate::uri::Uri;
+
+/// Base URL of the Pinata API.
+pub const PINATA_API_URL: &str = "https://api.pinata.cloud";
+/// Gateway serving the content of `ar://` URIs.
+pub const ARWEAVE_GATEWAY_URL: &str = "https://arweave.net";
+
+/// Storage the metadata and their artifacts are uploaded to.
+#[async_trait(?Send)]
+pub trait MetadataStorage {
+    /// Adds `data` to the storage and returns its CID.
+    ///
+    /// The content is pinned while being added if `config.pin` is set.
+    async fn add(&self, data: Vec<u8>, config: &UploadConfig) -> Result<String>;
+
+    /// Pins the content identified by `cid`, which may have been added by another node.
+    async fn pin(&self, cid: &str) -> Result<()>;
+
+    /// The URI referencing the content identified by `cid` in the uploaded metadata.
+    fn uri(&self, cid: &str) -> Uri {
+        Uri::Ipfs(format!("ipfs://{cid}"))
+    }
+}
+
+/// Storage backed by the HTTP API of an IPFS node.
+pub struct IpfsStorage {
+    client: IpfsClient,
+}
+
+impl IpfsStorage {
+    pub fn new(url: &str) -> Result<Self> {
+        Ok(Self { client: IpfsClient::from_str(url)? })
+    }
+
+    /// The IPFS node metadata are uploaded to by default.
+    pub fn default_node() -> Result<Self> {
+        Ok(Self::new(IPFS_CLIENT_URL)?.with_credentials(IPFS_USERNAME, IPFS_PASSWORD))
+    }
+
+    pub fn with_credentials(self, username: &str, password: &str) -> Self {
+        Self { client: self.client.with_credentials(username, password) }
+    }
+}
+
+#[async_trait(?Send)]
+impl MetadataStorage for IpfsStorage {
+    async fn add(&self, data: Vec<u8>, config: &UploadConfig) -> Result<String> {
+        let response =
+            self.client.add_with_options(Cursor::new(data), config.add_options()).await?;
+        Ok(response.hash)
+    }
+
+    async fn pin(&self, cid: &str) -> Result<()> {
+        self.client.pin_add(cid, true).await?;
+        Ok(())
+    }
+}
+
+/// Credentials of a Pinata account.
+#[derive(Debug, Clone)]
+pub enum PinataAuth {
+    /// A JWT sent as a bearer token.
+    Jwt(String),
+    /// An API key and its secret.
+    ApiKey { key: String, secret: String },
+}
+
+/// Storage backed by the Pinata pinning service.
+///
+/// Pinata pins all the content it stores, the content added with `config.pin` unset is pinned
+/// as well.
+#[derive(Debug, Clone)]
+pub struct PinataStorage {
+    client: reqwest::Client,
+    url: String,
+    auth: PinataAuth,
+}
+
+#[derive(Deserialize)]
+struct PinataPinResponse {
+    #[serde(rename = "IpfsHash")]
+    ipfs_hash: String,
+}
+
+impl PinataStorage {
+    pub fn new(auth: PinataAuth) -> Self {
+        Self { client: reqwest::Client::new(), url: PINATA_API_URL.to_string(), auth }
+    }
+
+    /// Sends the requests to `url` instead of [`PINATA_API_URL`].
+    pub fn with_url(self, url: impl Into<String>) -> Self {
+        Self { url: url.into(), ..self }
+    }
+
+    fn post(&self, endpoint: &str) -> RequestBuilder {
+        let request = self.client.post(format!("{}/pinning/{endpoint}", self.url));
+
+        match &self.auth {
+            PinataAuth::Jwt(jwt) => request.bearer_auth(jwt),
+            PinataAuth::ApiKey { key, secret } => {
+                request.header("pinata_api_key", key).header("pinata_secret_api_key", secret)
+            }
+        }
+    }
+
+    async fn send(request: RequestBuilder) -> Result<reqwest::Response> {
+        let response = request.send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            bail!("Pinata request failed with status {status}: {}", response.text().await?);
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait(?Send)]
+impl MetadataStorage for PinataStorage {
+    async fn add(&self, data: Vec<u8>, config: &UploadConfig) -> Result<String> {
+        let options = json!({ "cidVersion": config.cid_version as u32 });
+        let form = Form::new()
+            .part("file", Part::bytes(data).file_name("metadata"))
+            .text("pinataOptions", options.to_string());
+
+        let response = Self::send(self.post("pinFileToIPFS").multipart(form)).await?;
+        let response: PinataPinResponse = response.json().await?;
+
+        Ok(response.ipfs_hash)
+    }
+
+    async fn pin(&self, cid: &str) -> Result<()> {
+        Self::send(self.post("pinByHash").json(&json!({ "hashToPin": cid }))).await?;
+        Ok(())
+    }
+}
+
+/// Storage backed by an Arweave upload gateway, which signs and bundles the posted content into
+/// Arweave transactions.
+///
+/// The content stored on Arweave is permanent, so pinning it is a no-op and the content is
+/// referenced by `ar://` URIs.
+#[derive(Debug, Clone)]
+pub struct ArweaveStorage {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ArweaveUploadResponse {
+    id: String,
+}
+
+impl ArweaveStorage {
+    /// Creates a storage posting the content to the gateway at `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { client: reqwest::Client::new(), url: url.into(), api_key: None }
+    }
+
+    /// Authenticates the uploads with `api_key`, sent as a bearer token.
+    pub fn with_api_key(self, api_key: impl Into<String>) -> Self {
+        Self { api_key: Some(api_key.into()), ..self }
+    }
+}
+
+#[async_trait(?Send)]
+impl MetadataStorage for ArweaveStorage {
+    async fn add(&self, data: Vec<u8>, _config: &UploadConfig) -> Result<String> {
+        let mut request = self
+            .client
+            .post(format!("{}/tx", self.url))
+            .header("Content-Type", "application/octet-stream")
+            .body(data);
+
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            bail!("Arweave upload failed with status {status}: {}", response.text().await?);
+        }
+
+        let response: ArweaveUploadResponse = response.json().await?;
+        Ok(response.id)
+    }
+
+    async fn pin(&self, _cid: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn uri(&self, cid: &str) -> Uri {
+        Uri::Arweave(format!("ar://{cid}"))
+    }
+}
@@ -1,17 +1,23 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 
+use async_trait::async_trait;
 use camino::Utf8PathBuf;
 use dojo_test_utils::compiler;
+use futures::TryStreamExt;
+use ipfs_api_backend_hyper::{IpfsApi, IpfsClient, TryFromUri};
 use scarb::compiler::Profile;
 use scarb::ops;
+use serde_json::json;
 use url::Url;
 
 use crate::contracts::naming::{get_filename_from_tag, TAG_SEPARATOR};
 use crate::manifest::{CONTRACTS_DIR, MODELS_DIR, WORLD_CONTRACT_TAG};
 use crate::metadata::{
-    dojo_metadata_from_workspace, ArtifactMetadata, Uri, WorldMetadata, ABIS_DIR, BASE_DIR,
-    MANIFESTS_DIR,
+    decode_artifact, dojo_metadata_from_workspace, encode_artifact, ArtifactMetadata,
+    ContentEncoding, MetadataStorage, UploadConfig, Uri, WorldMetadata, ABIS_DIR, BASE_DIR,
+    IPFS_CLIENT_URL, IPFS_PASSWORD, IPFS_USERNAME, MANIFESTS_DIR,
 };
 
 #[tokio::test]
@@ -27,10 +33,133 @@ async fn world_metadata_hash_and_upload() {
         artifacts: ArtifactMetadata {
             abi: Some(Uri::File("src/metadata_test_data/abi.json".into())),
             source: Some(Uri::File("src/metadata_test_data/source.cairo".into())),
+            content_encoding: None,
+            extra: BTreeMap::new(),
         },
     };
 
-    let _ = meta.upload().await.unwrap();
+    let _ = meta.upload(&UploadConfig::default()).await.unwrap();
+}
+
+#[test]
+fn gzip_artifact_roundtrip() {
+    let abi = fs::read("src/metadata_test_data/abi.json").unwrap();
+
+    let encoded = encode_artifact(abi.clone(), Some(ContentEncoding::Gzip)).unwrap();
+    assert_ne!(encoded, abi);
+
+    let decoded = decode_artifact(&encoded, Some(ContentEncoding::Gzip)).unwrap();
+    assert_eq!(decoded, abi);
+}
+
+#[tokio::test]
+async fn compressed_artifact_upload_and_read_back() {
+    let meta = ArtifactMetadata {
+        abi: Some(Uri::File("src/metadata_test_data/abi.json".into())),
+        source: None,
+        content_encoding: Some(ContentEncoding::Gzip),
+        extra: BTreeMap::new(),
+    };
+
+    let hash = meta.upload(&UploadConfig::default()).await.unwrap();
+
+    let client = IpfsClient::from_str(IPFS_CLIENT_URL)
+        .unwrap()
+        .with_credentials(IPFS_USERNAME, IPFS_PASSWORD);
+
+    let uploaded: ArtifactMetadata =
+        serde_json::from_slice(&ipfs_cat(&client, &hash).await).unwrap();
+    assert_eq!(uploaded.content_encoding, Some(ContentEncoding::Gzip));
+
+    let Some(Uri::Ipfs(abi_uri)) = uploaded.abi else { panic!("abi should be uploaded") };
+    let abi = ipfs_cat(&client, abi_uri.trim_start_matches("ipfs://")).await;
+
+    assert_eq!(
+        decode_artifact(&abi, uploaded.content_encoding).unwrap(),
+        fs::read("src/metadata_test_data/abi.json").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn extra_fields_upload_and_read_back() {
+    let extra = BTreeMap::from([
+        ("display_name".to_string(), json!("Position")),
+        ("category".to_string(), json!("movement")),
+        ("docs".to_string(), json!({ "url": "https://book.dojoengine.org" })),
+    ]);
+
+    let meta =
+        ArtifactMetadata { abi: None, source: None, content_encoding: None, extra: extra.clone() };
+
+    let hash = meta.upload(&UploadConfig::default()).await.unwrap();
+
+    let client = IpfsClient::from_str(IPFS_CLIENT_URL)
+        .unwrap()
+        .with_credentials(IPFS_USERNAME, IPFS_PASSWORD);
+
+    let uploaded: ArtifactMetadata =
+        serde_json::from_slice(&ipfs_cat(&client, &hash).await).unwrap();
+    assert_eq!(uploaded.extra, extra);
+}
+
+#[test]
+fn missing_extra_fields_deserialize_to_empty() {
+    let meta: ArtifactMetadata =
+        serde_json::from_value(json!({ "abi": null, "source": null })).unwrap();
+    assert!(meta.extra.is_empty());
+
+    // and empty extra fields are not serialized, to keep the uploaded content unchanged.
+    assert_eq!(serde_json::to_value(&meta).unwrap(), json!({ "abi": null, "source": null }));
+}
+
+/// Storage keeping the added content in memory, the CID being the index of the content.
+#[derive(Default)]
+struct MemoryStorage {
+    added: RefCell<Vec<Vec<u8>>>,
+}
+
+#[async_trait(?Send)]
+impl MetadataStorage for MemoryStorage {
+    async fn add(&self, data: Vec<u8>, _config: &UploadConfig) -> anyhow::Result<String> {
+        let mut added = self.added.borrow_mut();
+        added.push(data);
+        Ok((added.len() - 1).to_string())
+    }
+
+    async fn pin(&self, _cid: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn world_metadata_upload_to_custom_storage() {
+    let meta = WorldMetadata {
+        name: "Test World".to_string(),
+        seed: String::from("dojo_examples"),
+        cover_uri: Some(Uri::File("src/metadata_test_data/cover.png".into())),
+        artifacts: ArtifactMetadata {
+            abi: Some(Uri::File("src/metadata_test_data/abi.json".into())),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let storage = MemoryStorage::default();
+    let cid = meta.upload_to(&storage, &UploadConfig::default()).await.unwrap();
+
+    // The cover and the abi are added before the metadata referencing them.
+    let added = storage.added.into_inner();
+    assert_eq!(cid, "2");
+    assert_eq!(added[0], fs::read("src/metadata_test_data/cover.png").unwrap());
+    assert_eq!(added[1], fs::read("src/metadata_test_data/abi.json").unwrap());
+
+    let uploaded: WorldMetadata = serde_json::from_slice(&added[2]).unwrap();
+    assert_eq!(uploaded.cover_uri, Some(Uri::Ipfs("ipfs://0".to_string())));
+    assert_eq!(uploaded.artifacts.abi, Some(Uri::Ipfs("ipfs://1".to_string())));
+}
+
+async fn ipfs_cat(client: &IpfsClient, hash: &str) -> Vec<u8> {
+    client.cat(hash).map_ok(|chunk| chunk.to_vec()).try_concat().await.unwrap()
 }
 
 #[tokio::test]
@@ -2,12 +2,13 @@ use std::fmt::Display;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
+use serde::Serialize;
 use starknet::core::types::Felt;
 
 use super::{Declarable, MigrationType, StateDiff};
 
 /// Represents differences between a local and remote class.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ClassDiff {
     // The tag is used to identify the corresponding artifact produced by the compiler.
     pub tag: String,
@@ -39,7 +40,7 @@ impl Display for ClassDiff {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ClassMigration {
     pub diff: ClassDiff,
     pub artifact_path: PathBuf,
@@ -2,6 +2,7 @@ use std::fmt::Display;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
+use serde::Serialize;
 use starknet::core::types::{DeclareTransactionResult, Felt};
 
 use super::{Declarable, Deployable, MigrationType, StateDiff, Upgradable};
@@ -9,7 +10,7 @@ use super::{Declarable, Deployable, MigrationType, StateDiff, Upgradable};
 pub type DeclareOutput = DeclareTransactionResult;
 
 /// Represents differences between a local and remote contract.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ContractDiff {
     // The tag is used to identify the corresponding artifact produced by the compiler.
     pub tag: String,
@@ -20,6 +21,10 @@ pub struct ContractDiff {
     pub init_calldata: Vec<String>,
     pub local_writes: Vec<String>,
     pub remote_writes: Vec<String>,
+    /// Resources the contract should own.
+    pub local_owners: Vec<String>,
+    /// The storage layout version the local class expects, if declared in the manifest.
+    pub layout_version: Option<u32>,
 }
 
 impl StateDiff for ContractDiff {
@@ -48,12 +53,15 @@ impl Display for ContractDiff {
 }
 
 // Represents a contract that needs to be migrated to the remote state
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct ContractMigration {
     pub salt: Felt,
     pub diff: ContractDiff,
     pub artifact_path: PathBuf,
     pub contract_address: Felt,
+    /// The storage layout version the new class expects, if known. Checked against the
+    /// deployed contract before upgrading it.
+    pub expected_layout_version: Option<u32>,
 }
 
 impl ContractMigration {
@@ -1,8 +1,9 @@
 use std::fs::File;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use cairo_lang_starknet_classes::contract_class::ContractClass;
@@ -10,8 +11,9 @@ use dojo_utils::{TransactionExt, TransactionWaiter, TransactionWaitingError, Txn
 use starknet::accounts::{Account, AccountError, Call, ConnectedAccount};
 use starknet::core::types::contract::{CompiledClass, SierraClass};
 use starknet::core::types::{
-    BlockId, BlockTag, DeclareTransactionResult, Felt, FlattenedSierraClass,
-    InvokeTransactionResult, ReceiptBlock, StarknetError, TransactionReceiptWithBlockInfo,
+    BlockId, BlockTag, DeclareTransactionResult, FeeEstimate, Felt, FlattenedSierraClass,
+    InvokeTransactionResult, ReceiptBlock, StarknetError, TransactionFinalityStatus,
+    TransactionReceipt, TransactionReceiptWithBlockInfo,
 };
 use starknet::core::utils::{get_contract_address, CairoShortStringToFeltError};
 use starknet::macros::{felt, selector};
@@ -27,6 +29,10 @@ pub mod world;
 
 pub type DeclareOutput = DeclareTransactionResult;
 
+/// The address of the Universal Deployer Contract on devnet.
+pub const UDC_ADDRESS: Felt =
+    felt!("0x41a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf");
+
 #[derive(Clone, Debug)]
 pub struct DeployOutput {
     pub transaction_hash: Felt,
@@ -54,6 +60,17 @@ pub struct RegisterOutput {
     pub registered_models: Vec<String>,
 }
 
+impl RegisterOutput {
+    /// Returns the hashes of the declare transactions followed by the one of the register
+    /// transaction, if any was sent.
+    pub fn transaction_hashes(&self) -> impl Iterator<Item = Felt> + '_ {
+        self.declare_output
+            .iter()
+            .map(|d| d.transaction_hash)
+            .chain(Some(self.transaction_hash).filter(|hash| *hash != Felt::ZERO))
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum MigrationError<S> {
     #[error("Compiling contract.")]
@@ -62,6 +79,10 @@ pub enum MigrationError<S> {
     ClassAlreadyDeclared,
     #[error("Contract already deployed.")]
     ContractAlreadyDeployed(Felt),
+    #[error("Address {address:#x} is already occupied by another contract.")]
+    AddressOccupied { address: Felt },
+    #[error("Account {account:#x} is not the owner of the world.")]
+    NotWorldOwner { account: Felt },
     #[error(transparent)]
     Migrator(#[from] AccountError<S>),
     #[error(transparent)]
@@ -74,6 +95,134 @@ pub enum MigrationError<S> {
     ArtifactError(#[from] anyhow::Error),
     #[error("Bad init calldata.")]
     BadInitCalldata,
+    #[error("Class {class_hash:#x} is declared but still not found after {attempts} attempts.")]
+    ClassNotFound { class_hash: Felt, attempts: u32 },
+}
+
+/// The number of times a declared class is looked up before giving up, see
+/// [`wait_for_class_declared`].
+pub const CLASS_LOOKUP_ATTEMPTS: u32 = 10;
+/// The delay between two lookups of a declared class.
+pub const CLASS_LOOKUP_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Waits for the class `class_hash` to be returned by `get_class`, looking it up at most
+/// `attempts` times every `interval`. Returns `false` if the class is still not found.
+///
+/// A declaration can be accepted before its class is queryable, deploying the class in that
+/// window fails as if it wasn't declared.
+pub async fn wait_for_class_declared<P>(
+    provider: P,
+    class_hash: Felt,
+    attempts: u32,
+    interval: Duration,
+) -> Result<bool, ProviderError>
+where
+    P: Provider,
+{
+    for attempt in 1..=attempts {
+        match provider.get_class(BlockId::Tag(BlockTag::Pending), class_hash).await {
+            Ok(_) => return Ok(true),
+            Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => {}
+            Err(e) => return Err(e),
+        }
+
+        if attempt < attempts {
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    Ok(false)
+}
+
+/// Waits for the declaration `transaction_hash` of `class_hash` to be accepted and, if
+/// `txn_config` waits for the transactions, for the class to be queryable.
+async fn wait_for_declaration<P, S>(
+    provider: &P,
+    transaction_hash: Felt,
+    class_hash: Felt,
+    txn_config: &TxnConfig,
+) -> Result<(), MigrationError<S>>
+where
+    P: Provider + Send + Sync,
+{
+    TransactionWaiter::new(transaction_hash, provider).await?;
+
+    if txn_config.wait {
+        lookup_declared_class(provider, class_hash).await?;
+    }
+
+    Ok(())
+}
+
+/// Waits for the declarations of `outputs`, sent together, to be accepted on L2 and, if
+/// `txn_config` waits for the transactions, for their classes to be queryable.
+pub async fn wait_for_declarations<P, S>(
+    provider: &P,
+    outputs: &[DeclareOutput],
+    txn_config: &TxnConfig,
+) -> Result<(), MigrationError<S>>
+where
+    P: Provider + Send + Sync,
+{
+    let hashes = outputs.iter().map(|o| o.transaction_hash).collect::<Vec<_>>();
+    TransactionWaiter::wait_all(&hashes, provider, TransactionFinalityStatus::AcceptedOnL2).await?;
+
+    if txn_config.wait {
+        for output in outputs {
+            lookup_declared_class(provider, output.class_hash).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fails with [`MigrationError::ClassNotFound`] if `class_hash` can't be queried after
+/// [`CLASS_LOOKUP_ATTEMPTS`] attempts.
+async fn lookup_declared_class<P, S>(
+    provider: &P,
+    class_hash: Felt,
+) -> Result<(), MigrationError<S>>
+where
+    P: Provider + Send + Sync,
+{
+    let attempts = CLASS_LOOKUP_ATTEMPTS;
+
+    match wait_for_class_declared(provider, class_hash, attempts, CLASS_LOOKUP_INTERVAL).await? {
+        true => Ok(()),
+        false => Err(MigrationError::ClassNotFound { class_hash, attempts }),
+    }
+}
+
+/// A class read from its artifact which isn't declared yet, as returned by
+/// [`Declarable::prepare_declaration`].
+#[derive(Debug)]
+pub struct PreparedDeclaration {
+    flattened_class: FlattenedSierraClass,
+    compiled_class_hash: Felt,
+}
+
+impl PreparedDeclaration {
+    /// Sends the declaration with the given `nonce`, without waiting for it.
+    ///
+    /// Several classes can be declared at once by the same account by giving each of them its
+    /// own nonce, and then waited for together with [`wait_for_declarations`].
+    pub async fn send_with_nonce<A>(
+        self,
+        account: A,
+        txn_config: &TxnConfig,
+        nonce: Felt,
+    ) -> Result<DeclareOutput, MigrationError<<A as Account>::SignError>>
+    where
+        A: ConnectedAccount + Send + Sync,
+        <A as ConnectedAccount>::Provider: Send,
+    {
+        account
+            .declare_v2(Arc::new(self.flattened_class), self.compiled_class_hash)
+            .nonce(nonce)
+            .send_with_cfg(txn_config)
+            .await
+            .map_err(MigrationError::Migrator)
+    }
 }
 
 /// Represents the type of migration that should be performed.
@@ -123,13 +272,70 @@ pub trait Declarable {
             .await
             .map_err(MigrationError::Migrator)?;
 
-        TransactionWaiter::new(transaction_hash, account.provider())
-            .await
-            .map_err(MigrationError::WaitingError)?;
+        wait_for_declaration(account.provider(), transaction_hash, class_hash, txn_config).await?;
 
         return Ok(DeclareOutput { transaction_hash, class_hash });
     }
 
+    /// Reads the class from its artifact and checks it isn't declared yet, without sending any
+    /// transaction.
+    ///
+    /// Fails with [`MigrationError::ClassAlreadyDeclared`] like [`Declarable::declare`].
+    async fn prepare_declaration<A>(
+        &self,
+        account: A,
+    ) -> Result<PreparedDeclaration, MigrationError<<A as Account>::SignError>>
+    where
+        A: ConnectedAccount + Send + Sync,
+        <A as ConnectedAccount>::Provider: Send,
+    {
+        let (flattened_class, compiled_class_hash) =
+            prepare_contract_declaration_params(self.artifact_path())?;
+
+        match account
+            .provider()
+            .get_class(BlockId::Tag(BlockTag::Pending), flattened_class.class_hash())
+            .await
+        {
+            Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => {}
+            Ok(_) => return Err(MigrationError::ClassAlreadyDeclared),
+            Err(e) => return Err(MigrationError::Provider(e)),
+        }
+
+        Ok(PreparedDeclaration { flattened_class, compiled_class_hash })
+    }
+
+    /// Estimates the fee of declaring the class, without sending any transaction.
+    ///
+    /// Fails with [`MigrationError::ClassAlreadyDeclared`] like [`Declarable::declare`].
+    async fn estimate_declare_fee<A>(
+        &self,
+        account: A,
+    ) -> Result<FeeEstimate, MigrationError<<A as Account>::SignError>>
+    where
+        A: ConnectedAccount + Send + Sync,
+        <A as ConnectedAccount>::Provider: Send,
+    {
+        let (flattened_class, casm_class_hash) =
+            prepare_contract_declaration_params(self.artifact_path())?;
+
+        match account
+            .provider()
+            .get_class(BlockId::Tag(BlockTag::Pending), flattened_class.class_hash())
+            .await
+        {
+            Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => {}
+            Ok(_) => return Err(MigrationError::ClassAlreadyDeclared),
+            Err(e) => return Err(MigrationError::Provider(e)),
+        }
+
+        account
+            .declare_v2(Arc::new(flattened_class), casm_class_hash)
+            .estimate_fee()
+            .await
+            .map_err(MigrationError::Migrator)
+    }
+
     fn artifact_path(&self) -> &PathBuf;
 }
 
@@ -297,9 +503,8 @@ pub trait Deployable: Declarable + Sync {
 
         let txn = account.execute_v1(vec![Call {
             calldata,
-            // devnet UDC address
             selector: selector!("deployContract"),
-            to: felt!("0x41a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf"),
+            to: UDC_ADDRESS,
         }]);
 
         let InvokeTransactionResult { transaction_hash } =
@@ -397,7 +602,8 @@ pub fn read_class(artifact_path: &PathBuf) -> Result<SierraClass> {
     Ok(contract_artifact)
 }
 
-fn get_compiled_class_hash(artifact_path: &PathBuf) -> Result<Felt> {
+/// Computes the hash of the CASM class compiled from the Sierra class at `artifact_path`.
+pub fn get_compiled_class_hash(artifact_path: &PathBuf) -> Result<Felt> {
     let file = File::open(artifact_path)?;
     let casm_contract_class: ContractClass = serde_json::from_reader(file)?;
     let casm_contract =
@@ -407,6 +613,33 @@ fn get_compiled_class_hash(artifact_path: &PathBuf) -> Result<Felt> {
     Ok(compiled_class.class_hash()?)
 }
 
+/// Reads the address of the contract deployed by the given transaction, from the
+/// `ContractDeployed` event emitted by the UDC.
+pub async fn get_deployed_address_from_receipt<P>(
+    provider: &P,
+    transaction_hash: Felt,
+) -> Result<Felt>
+where
+    P: Provider + Sync,
+{
+    let receipt = provider.get_transaction_receipt(transaction_hash).await?;
+
+    let TransactionReceipt::Invoke(receipt) = receipt.receipt else {
+        return Err(anyhow!("Transaction {transaction_hash:#x} is not an invoke transaction."));
+    };
+
+    receipt
+        .events
+        .iter()
+        .find(|e| {
+            e.from_address == UDC_ADDRESS && e.keys.first() == Some(&selector!("ContractDeployed"))
+        })
+        .and_then(|e| e.data.first().copied())
+        .with_context(|| {
+            format!("No `ContractDeployed` event found in transaction {transaction_hash:#x}.")
+        })
+}
+
 fn get_block_number_from_receipt(receipt: TransactionReceiptWithBlockInfo) -> Option<u64> {
     match receipt.block {
         ReceiptBlock::Pending => None,
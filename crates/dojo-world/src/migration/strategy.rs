@@ -1,33 +1,143 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
 use std::fs;
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Context, Result};
 use camino::Utf8PathBuf;
-use starknet::core::types::Felt;
+use serde::{Serialize, Serializer};
+use starknet::core::types::{BlockId, BlockTag, Felt, StarknetError};
 use starknet::core::utils::{cairo_short_string_to_felt, get_contract_address};
+use starknet::providers::{Provider, ProviderError};
 use starknet_crypto::{poseidon_hash_many, poseidon_hash_single};
 
 use super::class::{ClassDiff, ClassMigration};
 use super::contract::{ContractDiff, ContractMigration};
 use super::world::WorldDiff;
-use super::MigrationType;
+use super::{read_class, MigrationType};
 use crate::contracts::naming;
 use crate::manifest::{CONTRACTS_DIR, MODELS_DIR};
 
-#[derive(Debug, Clone)]
+#[cfg(test)]
+#[path = "strategy_test.rs"]
+mod tests;
+
+/// The maximum size (in bytes) of a Sierra class accepted by Starknet when declaring it.
+pub const DEFAULT_MAX_CLASS_SIZE: usize = 4_089_446;
+
+#[derive(Debug, Clone, Serialize)]
 pub enum MigrationMetadata {
     Contract(ContractDiff),
 }
 
-#[derive(Debug, Clone)]
+/// How the salt of each contract is derived, which determines the address the world deploys it
+/// at.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub enum SaltStrategy {
+    /// The salt is derived from the name of the contract, without its namespace.
+    #[default]
+    FromName,
+    /// The salt is derived from the tag of the contract (`namespace-name`), so that contracts
+    /// with the same name in different namespaces get different addresses.
+    FromNamespacedName,
+    /// The salt of each contract is given by tag. Contracts without one fall back to
+    /// [`SaltStrategy::FromName`].
+    Explicit(HashMap<String, Felt>),
+}
+
+impl SaltStrategy {
+    /// Returns the salt of the contract with the given tag.
+    pub fn salt(&self, tag: &str) -> Felt {
+        match self {
+            SaltStrategy::FromName => generate_salt(&naming::get_name_from_tag(tag)),
+            SaltStrategy::FromNamespacedName => generate_salt(tag),
+            SaltStrategy::Explicit(salts) => match salts.get(tag) {
+                Some(salt) => *salt,
+                None => generate_salt(&naming::get_name_from_tag(tag)),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct MigrationStrategy {
     pub world_address: Felt,
     pub world: Option<ContractMigration>,
     pub base: Option<ClassMigration>,
     pub contracts: Vec<ContractMigration>,
     pub models: Vec<ClassMigration>,
+    #[serde(serialize_with = "serialize_sorted")]
     pub metadata: HashMap<String, MigrationMetadata>,
+    /// If `true`, upgrading a contract whose deployed storage layout version doesn't match the
+    /// expected one is an error instead of a warning.
+    pub strict_layout: bool,
+    /// The maximum size (in bytes) of the classes to declare, as reported by
+    /// [`MigrationStrategy::class_sizes`].
+    pub max_class_size: usize,
+    /// If `true`, a class exceeding `max_class_size` is an error instead of a warning.
+    pub strict_class_size: bool,
+    /// How the salts of the contracts have been derived.
+    pub salt_strategy: SaltStrategy,
+}
+
+/// A compiled artifact referenced by a [`MigrationStrategy`] which is either missing
+/// from the target directory or can't be parsed as a Sierra class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingArtifact {
+    /// The tag of the resource the artifact belongs to.
+    pub tag: String,
+    /// The path where the artifact is expected to be.
+    pub path: PathBuf,
+    /// Why the artifact couldn't be used.
+    pub reason: String,
+}
+
+impl Display for MissingArtifact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.tag, self.path.display(), self.reason)
+    }
+}
+
+/// A resource whose compiled artifact doesn't produce the class hash known on-chain, as reported
+/// by [`check_artifact_freshness`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleResource {
+    /// The tag of the resource.
+    pub tag: String,
+    /// The path of the artifact of the resource.
+    pub path: PathBuf,
+    /// The class hash of the resource, declared on-chain.
+    pub expected_class_hash: Felt,
+    /// The class hash computed from the local artifact.
+    pub artifact_class_hash: Felt,
+}
+
+impl Display for StaleResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}): artifact class hash {:#x} doesn't match {:#x}",
+            self.tag,
+            self.path.display(),
+            self.artifact_class_hash,
+            self.expected_class_hash
+        )
+    }
+}
+
+/// A single step of a migration, as sequenced by [`MigrationStrategy::topological_order`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MigrationItem {
+    /// Declaration of the base class.
+    DeclareBase,
+    /// Deployment or upgrade of the world.
+    World,
+    /// Registration of the model with the given tag.
+    RegisterModel(String),
+    /// Deployment or upgrade of the contract with the given tag.
+    DeployContract(String),
+    /// Write authorization of the `contract` tag on `resource`, as found in its `writes`.
+    Authorize { contract: String, resource: String },
 }
 
 #[derive(Debug)]
@@ -37,6 +147,11 @@ pub struct MigrationItemsInfo {
 }
 
 impl MigrationStrategy {
+    /// Serializes the whole strategy to pretty-printed JSON, to inspect the prepared migration.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
     pub fn info(&self) -> MigrationItemsInfo {
         let mut new = 0;
         let mut update = 0;
@@ -61,6 +176,193 @@ impl MigrationStrategy {
         MigrationItemsInfo { new, update }
     }
 
+    /// Returns the class hashes shared by several models or contracts, with the tags of the
+    /// resources sharing each of them in manifest order.
+    ///
+    /// Such classes are declared only once during the migration. Since the world derives the
+    /// selector of a resource from its class, this is usually a mistake in the project.
+    pub fn duplicate_class_hashes(&self) -> HashMap<Felt, Vec<String>> {
+        let mut tags_by_class: HashMap<Felt, Vec<String>> = HashMap::new();
+
+        let models = self.models.iter().map(|m| (m.diff.local_class_hash, &m.diff.tag));
+        let contracts = self.contracts.iter().map(|c| (c.diff.local_class_hash, &c.diff.tag));
+
+        for (class_hash, tag) in models.chain(contracts) {
+            tags_by_class.entry(class_hash).or_default().push(tag.clone());
+        }
+
+        tags_by_class.retain(|_, tags| tags.len() > 1);
+        tags_by_class
+    }
+
+    /// Returns the classes referenced by the strategy, those of the world, the base, the
+    /// contracts and then the models, with the tag of their resource, their local class hash
+    /// and the path of their artifact.
+    pub fn classes(&self) -> impl Iterator<Item = (&String, Felt, &PathBuf)> {
+        let world =
+            self.world.iter().map(|w| (&w.diff.tag, w.diff.local_class_hash, &w.artifact_path));
+        let base =
+            self.base.iter().map(|b| (&b.diff.tag, b.diff.local_class_hash, &b.artifact_path));
+        let contracts =
+            self.contracts.iter().map(|c| (&c.diff.tag, c.diff.local_class_hash, &c.artifact_path));
+        let models =
+            self.models.iter().map(|m| (&m.diff.tag, m.diff.local_class_hash, &m.artifact_path));
+
+        world.chain(base).chain(contracts).chain(models)
+    }
+
+    /// Checks that every artifact referenced by the strategy exists in `target_dir`
+    /// and can be parsed, before any transaction is sent.
+    ///
+    /// All the missing or invalid artifacts are reported at once.
+    pub fn validate_artifacts(&self, target_dir: &Utf8PathBuf) -> Result<(), Vec<MissingArtifact>> {
+        let mut artifact_paths = HashMap::new();
+        let mut missing = vec![];
+
+        for dir in [target_dir.clone(), target_dir.join(MODELS_DIR), target_dir.join(CONTRACTS_DIR)]
+        {
+            if let Err(e) = read_artifact_paths(&dir, &mut artifact_paths) {
+                missing.push(MissingArtifact {
+                    tag: dir.to_string(),
+                    path: dir.clone().into(),
+                    reason: e.to_string(),
+                });
+            }
+        }
+
+        for (tag, _, expected_path) in self.classes() {
+            let Some(path) = artifact_paths.get(&naming::get_filename_from_tag(tag)) else {
+                missing.push(MissingArtifact {
+                    tag: tag.clone(),
+                    path: expected_path.clone(),
+                    reason: "artifact not found".to_string(),
+                });
+                continue;
+            };
+
+            if let Err(e) = read_class(path) {
+                missing.push(MissingArtifact {
+                    tag: tag.clone(),
+                    path: path.clone(),
+                    reason: format!("invalid artifact: {e}"),
+                });
+            }
+        }
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
+    }
+
+    /// Returns the size (in bytes) of each class referenced by the strategy, with the tag of its
+    /// resource.
+    ///
+    /// The size is the one of the flattened Sierra class, which is the payload of the declare
+    /// transaction.
+    pub fn class_sizes(&self) -> Result<Vec<(String, usize)>> {
+        self.classes()
+            .map(|(tag, _, path)| {
+                let class = read_class(path)?
+                    .flatten()
+                    .map_err(|e| anyhow!("error flattening the class of {tag}: {e}"))?;
+
+                Ok((tag.clone(), serde_json::to_vec(&class)?.len()))
+            })
+            .collect()
+    }
+
+    /// Orders the migration items so that each one comes after the items it depends on.
+    ///
+    /// The world depends on the base class, models and contracts depend on the world, and
+    /// an authorization depends on the deployment of its contract and on the registration
+    /// of the model it targets. Independent items keep their manifest order, so the result
+    /// is deterministic.
+    pub fn topological_order(&self) -> Vec<MigrationItem> {
+        let mut items = vec![];
+
+        if self.base.is_some() {
+            items.push(MigrationItem::DeclareBase);
+        }
+
+        if self.world.is_some() {
+            items.push(MigrationItem::World);
+        }
+
+        items.extend(self.models.iter().map(|m| MigrationItem::RegisterModel(m.diff.tag.clone())));
+        items.extend(
+            self.contracts.iter().map(|c| MigrationItem::DeployContract(c.diff.tag.clone())),
+        );
+
+        for c in &self.contracts {
+            items.extend(c.diff.local_writes.iter().map(|resource| MigrationItem::Authorize {
+                contract: c.diff.tag.clone(),
+                resource: resource.clone(),
+            }));
+        }
+
+        let index_of = |item: &MigrationItem| items.iter().position(|i| i == item);
+
+        // `dependencies[i]` holds the indices of the items that must come before item `i`.
+        let mut dependencies = vec![BTreeSet::new(); items.len()];
+
+        for (i, item) in items.iter().enumerate() {
+            let deps = &mut dependencies[i];
+
+            match item {
+                MigrationItem::DeclareBase => {}
+                MigrationItem::World => deps.extend(index_of(&MigrationItem::DeclareBase)),
+                MigrationItem::RegisterModel(_) | MigrationItem::DeployContract(_) => {
+                    deps.extend(index_of(&MigrationItem::World))
+                }
+                MigrationItem::Authorize { contract, resource } => {
+                    deps.extend(index_of(&MigrationItem::DeployContract(contract.clone())));
+
+                    for m in self.models.iter().filter(|m| writes_model(resource, &m.diff.tag)) {
+                        deps.extend(index_of(&MigrationItem::RegisterModel(m.diff.tag.clone())));
+                    }
+                }
+            }
+        }
+
+        // Repeatedly pick the ready item with the lowest index to keep the manifest order.
+        let mut ordered = Vec::with_capacity(items.len());
+        let mut done = vec![false; items.len()];
+
+        while ordered.len() < items.len() {
+            let next = (0..items.len())
+                .find(|&i| !done[i] && dependencies[i].iter().all(|&d| done[d]))
+                .expect("migration items dependencies can't be cyclic");
+
+            done[next] = true;
+            ordered.push(items[next].clone());
+        }
+
+        ordered
+    }
+
+    /// Returns the models and contracts of the strategy in the order given by
+    /// [`MigrationStrategy::topological_order`], which is the order they are submitted in.
+    pub fn ordered_migrations(&self) -> (Vec<ClassMigration>, Vec<ContractMigration>) {
+        let mut models = vec![];
+        let mut contracts = vec![];
+
+        for item in self.topological_order() {
+            match item {
+                MigrationItem::RegisterModel(tag) => {
+                    models.extend(self.models.iter().find(|m| m.diff.tag == tag).cloned())
+                }
+                MigrationItem::DeployContract(tag) => {
+                    contracts.extend(self.contracts.iter().find(|c| c.diff.tag == tag).cloned())
+                }
+                _ => {}
+            }
+        }
+
+        (models, contracts)
+    }
+
     pub fn resolve_variable(&mut self, world_address: Felt) -> Result<()> {
         for contract in self.contracts.iter_mut() {
             for field in contract.diff.init_calldata.iter_mut() {
@@ -70,7 +372,7 @@ impl MigrationStrategy {
                     match dependency_contract {
                         MigrationMetadata::Contract(c) => {
                             let contract_address = get_contract_address(
-                                generate_salt(&naming::get_name_from_tag(&c.tag)),
+                                self.salt_strategy.salt(&c.tag),
                                 c.base_class_hash,
                                 &[],
                                 world_address,
@@ -93,6 +395,33 @@ impl MigrationStrategy {
     }
 }
 
+/// Serializes a map with its keys sorted, so that the output is deterministic.
+fn serialize_sorted<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// Whether a `writes` entry of a contract targets the model with the given tag.
+///
+/// Models can be referenced by their tag or by their name only, optionally prefixed
+/// with `m:` or `model:`.
+fn writes_model(resource: &str, model_tag: &str) -> bool {
+    let model = match resource.split_once(':') {
+        Some(("m", model)) | Some(("model", model)) => model,
+        Some(_) => return false,
+        None => resource,
+    };
+
+    if naming::is_valid_tag(model) {
+        model == model_tag
+    } else {
+        model == naming::get_name_from_tag(model_tag)
+    }
+}
+
 /// construct migration strategy
 /// evaluate which contracts/classes need to be declared/deployed
 pub fn prepare_for_migration(
@@ -100,6 +429,23 @@ pub fn prepare_for_migration(
     seed: Felt,
     target_dir: &Utf8PathBuf,
     diff: WorldDiff,
+) -> Result<MigrationStrategy> {
+    prepare_for_migration_with_salt_strategy(
+        world_address,
+        seed,
+        target_dir,
+        diff,
+        SaltStrategy::default(),
+    )
+}
+
+/// Like [`prepare_for_migration`], deriving the salts of the contracts with `salt_strategy`.
+pub fn prepare_for_migration_with_salt_strategy(
+    world_address: Option<Felt>,
+    seed: Felt,
+    target_dir: &Utf8PathBuf,
+    diff: WorldDiff,
+    salt_strategy: SaltStrategy,
 ) -> Result<MigrationStrategy> {
     let mut metadata = HashMap::new();
     let mut artifact_paths = HashMap::new();
@@ -120,6 +466,7 @@ pub fn prepare_for_migration(
         &artifact_paths,
         &mut metadata,
         world.is_some(),
+        &salt_strategy,
     )?;
     let models = evaluate_models_to_migrate(&diff.models, &artifact_paths, world.is_some())?;
 
@@ -141,14 +488,63 @@ pub fn prepare_for_migration(
     // If world address is not provided, then we expect the world to be migrated.
     let world_address = world_address.unwrap_or_else(|| world.as_ref().unwrap().contract_address);
 
-    let mut migration =
-        MigrationStrategy { world_address, world, base, contracts, models, metadata };
+    let mut migration = MigrationStrategy {
+        world_address,
+        world,
+        base,
+        contracts,
+        models,
+        metadata,
+        strict_layout: false,
+        max_class_size: DEFAULT_MAX_CLASS_SIZE,
+        strict_class_size: false,
+        salt_strategy,
+    };
 
     migration.resolve_variable(world_address)?;
 
     Ok(migration)
 }
 
+/// Returns the resources of the strategy whose local artifact has drifted from the class
+/// declared on-chain.
+///
+/// Only the resources whose class hash is already declared are checked: the class hash of
+/// their artifact is recomputed and compared to it, which catches artifacts that weren't
+/// rebuilt since the manifest was generated.
+pub async fn check_artifact_freshness<P>(
+    strategy: &MigrationStrategy,
+    provider: P,
+) -> Result<Vec<StaleResource>>
+where
+    P: Provider + Sync,
+{
+    let mut stale = vec![];
+
+    for (tag, class_hash, path) in strategy.classes() {
+        match provider.get_class(BlockId::Tag(BlockTag::Pending), class_hash).await {
+            Ok(_) => {}
+            Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => continue,
+            Err(e) => return Err(e.into()),
+        }
+
+        let artifact_class_hash = read_class(path)?
+            .class_hash()
+            .with_context(|| format!("Failed to compute the class hash of {tag}"))?;
+
+        if artifact_class_hash != class_hash {
+            stale.push(StaleResource {
+                tag: tag.clone(),
+                path: path.clone(),
+                expected_class_hash: class_hash,
+                artifact_class_hash,
+            });
+        }
+    }
+
+    Ok(stale)
+}
+
 fn evaluate_models_to_migrate(
     models: &[ClassDiff],
     artifact_paths: &HashMap<String, PathBuf>,
@@ -189,6 +585,7 @@ fn evaluate_contracts_to_migrate(
     artifact_paths: &HashMap<String, PathBuf>,
     metadata: &mut HashMap<String, MigrationMetadata>,
     world_contract_will_migrate: bool,
+    salt_strategy: &SaltStrategy,
 ) -> Result<Vec<ContractMigration>> {
     let mut comps_to_migrate = vec![];
 
@@ -204,7 +601,8 @@ fn evaluate_contracts_to_migrate(
                 comps_to_migrate.push(ContractMigration {
                     diff: c.clone(),
                     artifact_path: path.clone(),
-                    salt: generate_salt(&naming::get_name_from_tag(&c.tag)),
+                    salt: salt_strategy.salt(&c.tag),
+                    expected_layout_version: c.layout_version,
                     ..Default::default()
                 });
             }
@@ -0,0 +1,188 @@
+use super::*;
+
+fn model(tag: &str) -> ClassMigration {
+    ClassMigration {
+        diff: ClassDiff { tag: tag.to_string(), ..Default::default() },
+        ..Default::default()
+    }
+}
+
+fn contract(tag: &str, writes: &[&str]) -> ContractMigration {
+    ContractMigration {
+        diff: ContractDiff {
+            tag: tag.to_string(),
+            local_writes: writes.iter().map(|w| w.to_string()).collect(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn strategy(contracts: Vec<ContractMigration>, models: Vec<ClassMigration>) -> MigrationStrategy {
+    MigrationStrategy {
+        world_address: Felt::ZERO,
+        world: Some(ContractMigration::default()),
+        base: Some(ClassMigration::default()),
+        contracts,
+        models,
+        metadata: HashMap::new(),
+        strict_layout: false,
+        max_class_size: DEFAULT_MAX_CLASS_SIZE,
+        strict_class_size: false,
+        salt_strategy: SaltStrategy::default(),
+    }
+}
+
+#[test]
+fn topological_order_registers_model_before_authorization() {
+    let strategy = strategy(
+        vec![contract("ns-actions", &["m:Position"])],
+        vec![model("ns-Moves"), model("ns-Position")],
+    );
+
+    let order = strategy.topological_order();
+    let position = |item: MigrationItem| order.iter().position(|i| *i == item).unwrap();
+
+    let authorize = position(MigrationItem::Authorize {
+        contract: "ns-actions".to_string(),
+        resource: "m:Position".to_string(),
+    });
+
+    assert!(position(MigrationItem::RegisterModel("ns-Position".to_string())) < authorize);
+    assert!(position(MigrationItem::DeployContract("ns-actions".to_string())) < authorize);
+    assert!(
+        position(MigrationItem::World)
+            < position(MigrationItem::DeployContract("ns-actions".to_string()))
+    );
+    assert_eq!(order[0], MigrationItem::DeclareBase);
+}
+
+#[test]
+fn topological_order_is_deterministic() {
+    let strategy = strategy(
+        vec![contract("ns-b", &["ns-Position"]), contract("ns-a", &["ns:ns"])],
+        vec![model("ns-Position")],
+    );
+
+    assert_eq!(
+        strategy.topological_order(),
+        vec![
+            MigrationItem::DeclareBase,
+            MigrationItem::World,
+            MigrationItem::RegisterModel("ns-Position".to_string()),
+            MigrationItem::DeployContract("ns-b".to_string()),
+            MigrationItem::DeployContract("ns-a".to_string()),
+            MigrationItem::Authorize {
+                contract: "ns-b".to_string(),
+                resource: "ns-Position".to_string()
+            },
+            MigrationItem::Authorize {
+                contract: "ns-a".to_string(),
+                resource: "ns:ns".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn ordered_migrations_register_model_before_writing_contract() {
+    // The contract writing `ns-Position` is listed before the model itself.
+    let strategy =
+        strategy(vec![contract("ns-actions", &["ns-Position"])], vec![model("ns-Position")]);
+
+    let order = strategy.topological_order();
+    let position = |item: MigrationItem| order.iter().position(|i| *i == item).unwrap();
+
+    assert!(
+        position(MigrationItem::RegisterModel("ns-Position".to_string()))
+            < position(MigrationItem::Authorize {
+                contract: "ns-actions".to_string(),
+                resource: "ns-Position".to_string(),
+            })
+    );
+
+    let (models, contracts) = strategy.ordered_migrations();
+    assert_eq!(models.len(), 1);
+    assert_eq!(models[0].diff.tag, "ns-Position");
+    assert_eq!(contracts.len(), 1);
+    assert_eq!(contracts[0].diff.tag, "ns-actions");
+}
+
+#[test]
+fn writes_model_matches_tag_and_name() {
+    assert!(writes_model("m:Position", "ns-Position"));
+    assert!(writes_model("model:ns-Position", "ns-Position"));
+    assert!(writes_model("Position", "ns-Position"));
+    assert!(!writes_model("other-Position", "ns-Position"));
+    assert!(!writes_model("ns:Position", "ns-Position"));
+}
+
+#[test]
+fn duplicate_class_hashes_groups_shared_classes() {
+    let mut first = contract("ns-first", &[]);
+    first.diff.local_class_hash = Felt::ONE;
+    let mut second = contract("ns-second", &[]);
+    second.diff.local_class_hash = Felt::ONE;
+    let mut other = contract("ns-other", &[]);
+    other.diff.local_class_hash = Felt::TWO;
+
+    let mut position = model("ns-Position");
+    position.diff.local_class_hash = Felt::THREE;
+
+    let strategy = strategy(vec![first, second, other], vec![position]);
+
+    let duplicates = strategy.duplicate_class_hashes();
+    assert_eq!(
+        duplicates,
+        HashMap::from([(Felt::ONE, vec!["ns-first".to_string(), "ns-second".to_string()])])
+    );
+}
+
+#[test]
+fn to_json_includes_world_class_hash_and_models() {
+    let mut strategy = strategy(vec![contract("ns-actions", &[])], vec![model("ns-Position")]);
+    strategy.world.as_mut().unwrap().diff.local_class_hash = Felt::from(0x1234);
+
+    let json: serde_json::Value = serde_json::from_str(&strategy.to_json().unwrap()).unwrap();
+
+    assert_eq!(json["world"]["diff"]["local_class_hash"], "0x1234");
+    assert_eq!(json["models"][0]["diff"]["tag"], "ns-Position");
+    assert_eq!(json["contracts"][0]["diff"]["tag"], "ns-actions");
+}
+
+#[test]
+fn salt_strategies_derive_salts_from_tag() {
+    let tag = "ns-actions";
+    let explicit = Felt::from(42);
+
+    assert_eq!(SaltStrategy::FromName.salt(tag), generate_salt("actions"));
+    assert_eq!(SaltStrategy::FromNamespacedName.salt(tag), generate_salt(tag));
+
+    let strategy = SaltStrategy::Explicit(HashMap::from([(tag.to_string(), explicit)]));
+    assert_eq!(strategy.salt(tag), explicit);
+    assert_eq!(strategy.salt("ns-other"), generate_salt("other"));
+}
+
+#[test]
+fn contracts_to_migrate_expect_the_layout_version_of_the_manifest() {
+    let diff = ContractDiff {
+        tag: "ns-actions".to_string(),
+        layout_version: Some(2),
+        ..Default::default()
+    };
+    let artifact_paths = HashMap::from([(
+        naming::get_filename_from_tag("ns-actions"),
+        PathBuf::from("ns-actions.json"),
+    )]);
+
+    let contracts = evaluate_contracts_to_migrate(
+        &[diff],
+        &artifact_paths,
+        &mut HashMap::new(),
+        false,
+        &SaltStrategy::default(),
+    )
+    .unwrap();
+
+    assert_eq!(contracts[0].expected_layout_version, Some(2));
+}
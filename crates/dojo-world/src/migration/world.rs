@@ -84,6 +84,8 @@ impl WorldDiff {
                                 .map(|r| r.inner.writes.clone())
                         })
                         .unwrap_or_default(),
+                    local_owners: contract.inner.owners.clone(),
+                    layout_version: contract.inner.layout_version,
                 }
             })
             .collect::<Vec<_>>();
@@ -104,6 +106,8 @@ impl WorldDiff {
             init_calldata: vec![],
             local_writes: vec![],
             remote_writes: vec![],
+            local_owners: vec![],
+            layout_version: None,
         };
 
         let mut diff = WorldDiff { world, base, contracts, models };
@@ -124,6 +128,77 @@ impl WorldDiff {
         count
     }
 
+    /// Counts the new, changed and unchanged models and contracts of the diff, and tells whether
+    /// the world is deployed or upgraded.
+    ///
+    /// The summary is meant to be displayed before migrating, like `2 models changed, 1 new
+    /// contract, world will be upgraded`.
+    pub fn summary(&self) -> WorldDiffSummary {
+        let mut summary = WorldDiffSummary {
+            world: match self.world.remote_class_hash {
+                None => WorldStatus::New,
+                Some(remote) if remote != self.world.local_class_hash => WorldStatus::Upgraded,
+                Some(_) => WorldStatus::Unchanged,
+            },
+            ..Default::default()
+        };
+
+        for model in &self.models {
+            summary.models.count(model.local_class_hash, model.remote_class_hash);
+        }
+
+        for contract in &self.contracts {
+            summary.contracts.count(contract.local_class_hash, contract.remote_class_hash);
+        }
+
+        summary
+    }
+
+    /// Renders the diff as an aligned text table, with one row per resource giving its change
+    /// kind and its class hash, as `remote -> local` when it changes.
+    ///
+    /// Resources only present on the remote world are not part of the diff, so they are not
+    /// listed.
+    pub fn render_table(&self) -> String {
+        let mut rows = vec![
+            diff_row(&self.world.tag, self.world.local_class_hash, self.world.remote_class_hash),
+            diff_row(&self.base.tag, self.base.local_class_hash, self.base.remote_class_hash),
+        ];
+        rows.extend(
+            self.models.iter().map(|m| diff_row(&m.tag, m.local_class_hash, m.remote_class_hash)),
+        );
+        rows.extend(
+            self.contracts
+                .iter()
+                .map(|c| diff_row(&c.tag, c.local_class_hash, c.remote_class_hash)),
+        );
+
+        let header = ["RESOURCE".to_string(), "CHANGE".to_string(), "CLASS HASH".to_string()];
+
+        let mut widths = header.clone().map(|h| h.len());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let mut table = String::new();
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = format!(
+                "{:<w0$}  {:<w1$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                w0 = widths[0],
+                w1 = widths[1]
+            );
+            table.push_str(line.trim_end());
+            table.push('\n');
+        }
+
+        table
+    }
+
     pub fn update_order(&mut self, default_namespace: &str) -> Result<()> {
         let mut ts = TopologicalSort::<String>::new();
 
@@ -187,6 +262,89 @@ impl WorldDiff {
     }
 }
 
+/// What a migration does to the world, as reported by [`WorldDiff::summary`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WorldStatus {
+    /// The world isn't deployed yet.
+    #[default]
+    New,
+    /// The world is deployed with another class and will be upgraded.
+    Upgraded,
+    Unchanged,
+}
+
+/// Counts of the resources of a kind in a [`WorldDiffSummary`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeCounts {
+    /// Resources absent from the remote world.
+    pub new: usize,
+    /// Resources whose class hash differs from the remote one.
+    pub changed: usize,
+    pub unchanged: usize,
+}
+
+impl ChangeCounts {
+    fn count(&mut self, local: Felt, remote: Option<Felt>) {
+        match remote {
+            None => self.new += 1,
+            Some(remote) if remote == local => self.unchanged += 1,
+            Some(_) => self.changed += 1,
+        }
+    }
+}
+
+/// Overview of the changes of a [`WorldDiff`], displayed as a one-line report.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WorldDiffSummary {
+    pub world: WorldStatus,
+    pub models: ChangeCounts,
+    pub contracts: ChangeCounts,
+}
+
+impl Display for WorldDiffSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = |count: usize| if count == 1 { "" } else { "s" };
+
+        let mut parts = vec![];
+
+        for (noun, counts) in [("model", self.models), ("contract", self.contracts)] {
+            let ChangeCounts { new, changed, unchanged } = counts;
+
+            if new > 0 {
+                parts.push(format!("{new} new {noun}{}", s(new)));
+            }
+            if changed > 0 {
+                parts.push(format!("{changed} {noun}{} changed", s(changed)));
+            }
+            if unchanged > 0 {
+                parts.push(format!("{unchanged} {noun}{} unchanged", s(unchanged)));
+            }
+        }
+
+        parts.push(
+            match self.world {
+                WorldStatus::New => "world will be deployed",
+                WorldStatus::Upgraded => "world will be upgraded",
+                WorldStatus::Unchanged => "world is up to date",
+            }
+            .to_string(),
+        );
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Returns the resource, change kind and class hash cells of a row of [`WorldDiff::render_table`].
+fn diff_row(tag: &str, local: Felt, remote: Option<Felt>) -> [String; 3] {
+    let (kind, class_hash) = match remote {
+        None => ("new", format!("-> {local:#x}")),
+        Some(remote) if remote == local => ("unchanged", format!("{local:#x}")),
+        Some(remote) => ("changed", format!("{remote:#x} -> {local:#x}")),
+    };
+
+    [tag.to_string(), kind.to_string(), class_hash]
+}
+
 impl Display for WorldDiff {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}", self.world)?;
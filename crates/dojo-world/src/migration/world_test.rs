@@ -194,3 +194,88 @@ fn updating_order_when_cyclic_dependency_fail() {
 
     assert!(diff.update_order("ns").is_err_and(|e| e.to_string().contains("Cyclic")));
 }
+
+#[test]
+fn render_table_lists_each_resource() {
+    let diff = WorldDiff {
+        world: ContractDiff {
+            tag: WORLD_CONTRACT_TAG.to_string(),
+            local_class_hash: felt!("0x66"),
+            remote_class_hash: Some(felt!("0x44")),
+            ..Default::default()
+        },
+        base: ClassDiff {
+            tag: BASE_CONTRACT_TAG.to_string(),
+            local_class_hash: felt!("0x77"),
+            remote_class_hash: Some(felt!("0x77")),
+            ..Default::default()
+        },
+        models: vec![ClassDiff {
+            tag: get_tag("dojo_mock", "model"),
+            local_class_hash: felt!("0x11"),
+            remote_class_hash: None,
+            ..Default::default()
+        }],
+        contracts: vec![ContractDiff {
+            tag: get_tag("dojo_mock", "my_contract"),
+            local_class_hash: felt!("0x1111"),
+            remote_class_hash: Some(felt!("0x1111")),
+            ..Default::default()
+        }],
+    };
+
+    let table = diff.render_table();
+    let lines = table.lines().collect::<Vec<_>>();
+
+    assert_eq!(
+        lines,
+        vec![
+            "RESOURCE               CHANGE     CLASS HASH",
+            "dojo-world             changed    0x44 -> 0x66",
+            "dojo-base              unchanged  0x77",
+            "dojo_mock-model        new        -> 0x11",
+            "dojo_mock-my_contract  unchanged  0x1111",
+        ]
+    );
+}
+
+#[test]
+fn summary_counts_changes_by_kind() {
+    let model = |name: &str, local: Felt, remote: Option<Felt>| ClassDiff {
+        tag: get_tag("dojo_mock", name),
+        local_class_hash: local,
+        remote_class_hash: remote,
+        ..Default::default()
+    };
+
+    let diff = WorldDiff {
+        world: ContractDiff {
+            tag: WORLD_CONTRACT_TAG.to_string(),
+            local_class_hash: felt!("0x66"),
+            remote_class_hash: Some(felt!("0x44")),
+            ..Default::default()
+        },
+        base: ClassDiff { tag: BASE_CONTRACT_TAG.to_string(), ..Default::default() },
+        models: vec![
+            model("a", felt!("0x11"), Some(felt!("0x10"))),
+            model("b", felt!("0x12"), Some(felt!("0x10"))),
+            model("c", felt!("0x13"), Some(felt!("0x13"))),
+        ],
+        contracts: vec![ContractDiff {
+            tag: get_tag("dojo_mock", "my_contract"),
+            local_class_hash: felt!("0x1111"),
+            remote_class_hash: None,
+            ..Default::default()
+        }],
+    };
+
+    let summary = diff.summary();
+
+    assert_eq!(summary.world, WorldStatus::Upgraded);
+    assert_eq!(summary.models, ChangeCounts { new: 0, changed: 2, unchanged: 1 });
+    assert_eq!(summary.contracts, ChangeCounts { new: 1, changed: 0, unchanged: 0 });
+    assert_eq!(
+        summary.to_string(),
+        "2 models changed, 1 model unchanged, 1 new contract, world will be upgraded"
+    );
+}
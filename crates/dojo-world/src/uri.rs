@@ -14,6 +14,7 @@ pub enum UriParseError {
 pub enum Uri {
     Http(Url),
     Ipfs(String),
+    Arweave(String),
     File(PathBuf),
 }
 
@@ -25,6 +26,7 @@ impl Serialize for Uri {
         match self {
             Uri::Http(url) => serializer.serialize_str(url.as_ref()),
             Uri::Ipfs(ipfs) => serializer.serialize_str(ipfs),
+            Uri::Arweave(arweave) => serializer.serialize_str(arweave),
             Uri::File(path) => serializer.serialize_str(&format!("file://{}", path.display())),
         }
     }
@@ -40,6 +42,17 @@ impl<'de> Deserialize<'de> for Uri {
     }
 }
 
+impl std::fmt::Display for Uri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Uri::Http(url) => write!(f, "{url}"),
+            Uri::Ipfs(ipfs) => write!(f, "{ipfs}"),
+            Uri::Arweave(arweave) => write!(f, "{arweave}"),
+            Uri::File(path) => write!(f, "file://{}", path.display()),
+        }
+    }
+}
+
 impl Uri {
     pub fn cid(&self) -> Option<&str> {
         match self {
@@ -48,9 +61,19 @@ impl Uri {
         }
     }
 
+    /// Returns the id of the Arweave transaction holding the content of an `ar://` URI.
+    pub fn arweave_id(&self) -> Option<&str> {
+        match self {
+            Uri::Arweave(value) => value.strip_prefix("ar://"),
+            _ => None,
+        }
+    }
+
     pub fn from_string(s: &str) -> Result<Self> {
         if s.starts_with("ipfs://") {
             Ok(Uri::Ipfs(s.to_string()))
+        } else if s.starts_with("ar://") {
+            Ok(Uri::Arweave(s.to_string()))
         } else if let Some(path) = s.strip_prefix("file://") {
             Ok(Uri::File(PathBuf::from(&path)))
         } else if let Ok(url) = Url::parse(s) {
@@ -93,6 +116,16 @@ mod tests {
         assert!(matches!(file_uri, Uri::File(_)));
     }
 
+    #[test]
+    fn test_uri_display_matches_serialize() {
+        let ipfs_uri =
+            Uri::Ipfs("ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string());
+        let file_uri = Uri::File(PathBuf::from("/path/to/file"));
+
+        assert_eq!(ipfs_uri.to_string(), "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG");
+        assert_eq!(file_uri.to_string(), "file:///path/to/file");
+    }
+
     #[test]
     fn test_uri_cid() {
         let ipfs_uri =
@@ -103,14 +136,32 @@ mod tests {
         assert_eq!(http_uri.cid(), None);
     }
 
+    #[test]
+    fn test_uri_arweave_id() {
+        let arweave_uri =
+            Uri::Arweave("ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U".to_string());
+        let ipfs_uri =
+            Uri::Ipfs("ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string());
+
+        assert_eq!(arweave_uri.arweave_id(), Some("bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U"));
+        assert_eq!(arweave_uri.cid(), None);
+        assert_eq!(ipfs_uri.arweave_id(), None);
+        assert_eq!(
+            serde_json::to_string(&arweave_uri).unwrap(),
+            "\"ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U\""
+        );
+    }
+
     #[test]
     fn test_uri_from_str() {
         let ipfs_str = "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+        let arweave_str = "ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U";
         let file_str = "file:///path/to/file";
         let http_str = "http://example.com";
         let invalid_str = "invalid_uri";
 
         assert!(matches!(Uri::from_string(ipfs_str).unwrap(), Uri::Ipfs(_)));
+        assert!(matches!(Uri::from_string(arweave_str).unwrap(), Uri::Arweave(_)));
         assert!(matches!(Uri::from_string(file_str).unwrap(), Uri::File(_)));
         assert!(matches!(Uri::from_string(http_str).unwrap(), Uri::Http(_)));
         assert!(Uri::from_string(invalid_str).is_err());
@@ -0,0 +1,232 @@
+//! Export of the chain state as a genesis configuration.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+
+use alloy_primitives::U256;
+use anyhow::{bail, Context, Result};
+use katana_executor::ExecutorFactory;
+use katana_primitives::class::{ClassHash, CompiledClass, FlattenedSierraClass};
+use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
+use katana_primitives::genesis::constant::get_fee_token_balance_base_storage_address;
+use katana_primitives::genesis::json::{
+    ClassNameOrHash, FeeTokenConfigJson, GenesisAccountJson, GenesisClassJson, GenesisContractJson,
+    GenesisJson, PathOrFullArtifact, UniversalDeployerConfigJson,
+};
+use katana_primitives::FieldElement;
+use katana_provider::traits::block::BlockNumberProvider;
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::state_update::StateUpdateProvider;
+use serde_json::{json, Value};
+
+use super::Backend;
+
+type Storage = HashMap<StorageKey, StorageValue>;
+
+/// Serializes the current state of the chain into a genesis JSON that another Katana instance can
+/// boot from. See [`genesis_json`] for what is included.
+pub fn export_genesis<EF: ExecutorFactory>(
+    backend: &Backend<EF>,
+    writer: impl Write,
+) -> Result<()> {
+    let genesis = genesis_json(backend)?;
+    serde_json::to_writer_pretty(writer, &genesis)?;
+    Ok(())
+}
+
+/// Builds a [`GenesisJson`] out of the latest state of the chain.
+///
+/// Every declared class is embedded as a full artifact, and every contract is exported with its
+/// class hash, nonce, storage and fee token balance. The predeployed accounts keep their private
+/// keys so that the new instance exposes the same dev accounts. The block header fields are those
+/// of the original genesis.
+///
+/// Only the state produced by this instance is exported, the state lazily fetched from a forked
+/// network is not included.
+#[allow(deprecated)]
+pub fn genesis_json<EF: ExecutorFactory>(backend: &Backend<EF>) -> Result<GenesisJson> {
+    let provider = backend.blockchain.provider();
+    let genesis = &backend.config.genesis;
+
+    let mut declared: BTreeSet<ClassHash> = BTreeSet::new();
+    let mut classes: HashMap<ContractAddress, ClassHash> = HashMap::new();
+    let mut nonces: HashMap<ContractAddress, Nonce> = HashMap::new();
+    let mut storage: HashMap<ContractAddress, Storage> = HashMap::new();
+
+    // Apply the state updates of every block in order, so that only the latest values remain.
+    for number in genesis.number..=provider.latest_number()? {
+        let updates = provider
+            .state_update(number.into())?
+            .with_context(|| format!("Missing state update for block {number}"))?;
+
+        declared.extend(updates.declared_classes.into_keys());
+        classes.extend(updates.contract_updates);
+        nonces.extend(updates.nonce_updates);
+
+        for (address, entries) in updates.storage_updates {
+            storage.entry(address).or_default().extend(entries);
+        }
+    }
+
+    let state = provider.latest()?;
+    let mut genesis_classes = Vec::with_capacity(declared.len());
+
+    for class_hash in declared {
+        let artifact = match state.sierra_class(class_hash)? {
+            Some(class) => sierra_class_artifact(class)?,
+            None => match state.class(class_hash)? {
+                Some(CompiledClass::Deprecated(class)) => serde_json::to_value(class)?,
+                Some(CompiledClass::Class(_)) => {
+                    bail!("Missing Sierra definition for class {class_hash:#x}")
+                }
+                None => bail!("Missing definition for class {class_hash:#x}"),
+            },
+        };
+
+        genesis_classes.push(GenesisClassJson {
+            class: PathOrFullArtifact::Artifact(artifact),
+            class_hash: Some(class_hash),
+            name: None,
+        });
+    }
+
+    let fee_token_address = genesis.fee_token.address;
+    let fee_token_storage = storage.remove(&fee_token_address).unwrap_or_default();
+    let balance = |address: ContractAddress| balance_of(&fee_token_storage, address);
+
+    let universal_deployer = genesis.universal_deployer.as_ref().map(|udc| {
+        let class_hash = classes.remove(&udc.address).unwrap_or(udc.class_hash);
+        UniversalDeployerConfigJson {
+            address: Some(udc.address),
+            class: Some(ClassNameOrHash::Hash(class_hash)),
+            storage: storage.remove(&udc.address),
+        }
+    });
+
+    let mut accounts = HashMap::new();
+    for (address, account) in genesis.accounts() {
+        let address = *address;
+        let class_hash = classes.remove(&address).unwrap_or(account.class_hash());
+
+        accounts.insert(
+            address,
+            GenesisAccountJson {
+                public_key: account.public_key(),
+                balance: balance(address),
+                nonce: nonces.remove(&address),
+                class: Some(ClassNameOrHash::Hash(class_hash)),
+                storage: storage.remove(&address),
+                private_key: account.private_key(),
+            },
+        );
+    }
+
+    // Whatever is left are the contracts deployed on top of the genesis, or the addresses that
+    // only have storage (eg, the block hash registry).
+    let fee_token_class =
+        classes.remove(&fee_token_address).unwrap_or(genesis.fee_token.class_hash);
+    let addresses: BTreeSet<ContractAddress> =
+        classes.keys().chain(nonces.keys()).chain(storage.keys()).copied().collect();
+
+    let contracts = addresses
+        .into_iter()
+        .map(|address| {
+            let contract = GenesisContractJson {
+                class: classes.remove(&address).map(ClassNameOrHash::Hash),
+                balance: balance(address),
+                nonce: nonces.remove(&address),
+                storage: storage.remove(&address),
+            };
+            (address, contract)
+        })
+        .collect();
+
+    let fee_token = FeeTokenConfigJson {
+        name: genesis.fee_token.name.clone(),
+        symbol: genesis.fee_token.symbol.clone(),
+        address: Some(fee_token_address),
+        decimals: genesis.fee_token.decimals,
+        class: Some(ClassNameOrHash::Hash(fee_token_class)),
+        storage: Some(fee_token_storage),
+    };
+
+    Ok(GenesisJson {
+        parent_hash: genesis.parent_hash,
+        state_root: genesis.state_root,
+        number: genesis.number,
+        timestamp: genesis.timestamp,
+        sequencer_address: genesis.sequencer_address,
+        gas_prices: genesis.gas_prices.clone(),
+        classes: genesis_classes,
+        fee_token,
+        universal_deployer,
+        accounts,
+        contracts,
+    })
+}
+
+/// Reads the fee token balance of `address` from the fee token storage. Returns `None` if the
+/// balance has never been set.
+fn balance_of(fee_token_storage: &Storage, address: ContractAddress) -> Option<U256> {
+    let base = get_fee_token_balance_base_storage_address(address);
+    let low = fee_token_storage.get(&base);
+    let high = fee_token_storage.get(&(base + FieldElement::ONE));
+
+    if low.is_none() && high.is_none() {
+        return None;
+    }
+
+    let to_u256 = |value: Option<&FieldElement>| {
+        U256::from_be_bytes(value.copied().unwrap_or_default().to_bytes_be())
+    };
+
+    Some(to_u256(low) + (to_u256(high) << 128))
+}
+
+/// Rebuilds the contract class artifact of a flattened Sierra class, in the same format as the
+/// artifacts produced by the compiler. The debug info isn't part of the flattened class so it's
+/// left empty.
+fn sierra_class_artifact(class: FlattenedSierraClass) -> Result<Value> {
+    let abi: Value =
+        if class.abi.is_empty() { json!([]) } else { serde_json::from_str(&class.abi)? };
+
+    Ok(json!({
+        "sierra_program": class.sierra_program,
+        "sierra_program_debug_info": {
+            "type_names": [],
+            "libfunc_names": [],
+            "user_func_names": []
+        },
+        "contract_class_version": class.contract_class_version,
+        "entry_points_by_type": class.entry_points_by_type,
+        "abi": abi
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use alloy_primitives::U256;
+    use katana_primitives::genesis::constant::get_fee_token_balance_base_storage_address;
+    use katana_primitives::FieldElement;
+
+    use super::balance_of;
+
+    #[test]
+    fn balance_is_read_from_low_and_high_slots() {
+        let address = FieldElement::from(0x1337u32).into();
+        let base = get_fee_token_balance_base_storage_address(address);
+
+        let storage = HashMap::from([
+            (base, FieldElement::from(5u8)),
+            (base + FieldElement::ONE, FieldElement::ONE),
+        ]);
+        let expected = U256::from(5u8) + (U256::from(1u8) << 128);
+        assert_eq!(balance_of(&storage, address), Some(expected));
+
+        let other = FieldElement::from(0x1338u32).into();
+        assert_eq!(balance_of(&storage, other), None);
+    }
+}
@@ -6,6 +6,7 @@ use katana_primitives::block::{
 };
 use katana_primitives::chain::ChainId;
 use katana_primitives::env::BlockEnv;
+use katana_primitives::message::MessagingStatus;
 use katana_primitives::transaction::TxHash;
 use katana_primitives::version::CURRENT_STARKNET_VERSION;
 use katana_primitives::FieldElement;
@@ -22,6 +23,7 @@ use tracing::{info, trace};
 
 pub mod config;
 pub mod contract;
+pub mod genesis;
 pub mod storage;
 
 use self::config::StarknetConfig;
@@ -43,6 +45,8 @@ pub struct Backend<EF: ExecutorFactory> {
     pub chain_id: ChainId,
     /// The block context generator.
     pub block_context_generator: RwLock<BlockContextGenerator>,
+    /// The progress of the messaging service, if enabled.
+    pub messaging_status: RwLock<MessagingStatus>,
 
     pub executor_factory: Arc<EF>,
 }
@@ -118,6 +122,7 @@ impl<EF: ExecutorFactory> Backend<EF> {
             config,
             executor_factory,
             block_context_generator: RwLock::new(block_context_generator),
+            messaging_status: Default::default(),
         }
     }
 
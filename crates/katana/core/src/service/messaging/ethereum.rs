@@ -8,7 +8,7 @@ use alloy_primitives::{Address, U256};
 use alloy_provider::{Provider, ReqwestProvider};
 use alloy_rpc_types_eth::{BlockNumberOrTag, Filter, FilterBlockOption, FilterSet, Log, Topic};
 use alloy_sol_types::{sol, SolEvent};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
 use katana_primitives::chain::ChainId;
 use katana_primitives::receipt::MessageToL1;
@@ -20,6 +20,7 @@ use katana_primitives::FieldElement;
 use starknet::core::types::EthAddress;
 use tracing::{debug, trace, warn};
 
+use super::service::with_timeout;
 use super::{Error, MessagingConfig, Messenger, MessengerResult, LOG_TARGET};
 
 sol! {
@@ -48,15 +49,28 @@ sol! {
 pub struct EthereumMessaging {
     provider: Arc<ReqwestProvider<Ethereum>>,
     messaging_contract_address: Address,
+    /// The number of blocks on top of a block before its messages are gathered.
+    confirmations: u64,
 }
 
 impl EthereumMessaging {
     pub async fn new(config: MessagingConfig) -> Result<EthereumMessaging> {
+        let provider =
+            Arc::new(ReqwestProvider::<Ethereum>::new_http(reqwest::Url::parse(&config.rpc_url)?));
+
+        if let Some(chain_id) = config.chain_id {
+            let actual_chain_id =
+                with_timeout(config.rpc_timeout, async { Ok(provider.get_chain_id().await?) })
+                    .await?;
+            if actual_chain_id != chain_id {
+                bail!("Settlement chain id is {actual_chain_id}, expected {chain_id}.");
+            }
+        }
+
         Ok(EthereumMessaging {
-            provider: Arc::new(ReqwestProvider::<Ethereum>::new_http(reqwest::Url::parse(
-                &config.rpc_url,
-            )?)),
+            provider,
             messaging_contract_address: config.contract_address.parse::<Address>()?,
+            confirmations: config.confirmations,
         })
     }
 
@@ -121,7 +135,10 @@ impl Messenger for EthereumMessaging {
         max_blocks: u64,
         chain_id: ChainId,
     ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)> {
-        let chain_latest_block: u64 = self.provider.get_block_number().await?;
+        // The blocks without enough confirmations may still be reorged, they are skipped until
+        // they have.
+        let chain_latest_block: u64 =
+            self.provider.get_block_number().await?.saturating_sub(self.confirmations);
         trace!(target: LOG_TARGET, from_block, max_blocks, ?chain_id, latest_block = chain_latest_block, "Gathering messages ethereum.");
 
         // +1 as the from_block counts as 1 block fetched.
@@ -251,6 +268,7 @@ mod tests {
     use starknet::macros::{felt, selector};
 
     use super::*;
+    use crate::service::messaging::CONFIG_CHAIN_EVM;
 
     #[test]
     fn l1_handler_tx_from_log_parse_ok() {
@@ -334,4 +352,31 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    fn evm_settlement_chain_config() {
+        // Configs without the EVM fields gather the messages up to the latest block.
+        let config: MessagingConfig = serde_json::from_str(include_str!(
+            "../../../../contracts/messaging/anvil.messaging.json"
+        ))
+        .unwrap();
+        assert_eq!(config.chain_id, None);
+        assert_eq!(config.confirmations, 0);
+
+        let config: MessagingConfig = serde_json::from_value(serde_json::json!({
+            "chain": "evm",
+            "rpc_url": "http://127.0.0.1:8545",
+            "contract_address": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "sender_address": "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+            "private_key": "",
+            "interval": 2,
+            "from_block": 0,
+            "chain_id": 10,
+            "confirmations": 12
+        }))
+        .unwrap();
+        assert_eq!(config.chain, CONFIG_CHAIN_EVM);
+        assert_eq!(config.chain_id, Some(10));
+        assert_eq!(config.confirmations, 12);
+    }
 }
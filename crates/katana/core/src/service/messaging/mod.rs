@@ -17,6 +17,10 @@
 //! updates on Ethereum, since the process of proving and verifying of state updates, and then
 //! posting in on the settlement layer are not yet present in Katana.
 //!
+//! Any other EVM chain running the messaging contract, like an L2 rollup, can be used as settlement
+//! chain with the `evm` chain. Its `chain_id` can be given to check the RPC endpoint at startup, and
+//! the messages can be gathered only once their block has a number of `confirmations`.
+//!
 //! Katana also has a `starknet-messaging` feature, where an opiniated implementation of L2 <-> L3
 //! messaging is implemented using Starknet as settlement chain.
 //!
@@ -38,6 +42,7 @@ mod service;
 mod starknet;
 
 use std::path::Path;
+use std::time::Duration;
 
 use ::starknet::providers::ProviderError as StarknetProviderError;
 use alloy_transport::TransportError;
@@ -46,7 +51,7 @@ use async_trait::async_trait;
 use ethereum::EthereumMessaging;
 use katana_primitives::chain::ChainId;
 use katana_primitives::receipt::MessageToL1;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tracing::{error, info};
 
 pub use self::service::{MessagingOutcome, MessagingService};
@@ -55,6 +60,7 @@ use self::starknet::StarknetMessaging;
 
 pub(crate) const LOG_TARGET: &str = "messaging";
 pub(crate) const CONFIG_CHAIN_ETHEREUM: &str = "ethereum";
+pub(crate) const CONFIG_CHAIN_EVM: &str = "evm";
 #[cfg(feature = "starknet-messaging")]
 pub(crate) const CONFIG_CHAIN_STARKNET: &str = "starknet";
 
@@ -70,6 +76,8 @@ pub enum Error {
     GatherError,
     #[error("Failed to send messages to settlement chain")]
     SendError,
+    #[error("Settlement chain RPC call timed out")]
+    Timeout,
     #[error(transparent)]
     Provider(ProviderError),
 }
@@ -89,9 +97,9 @@ impl From<TransportError> for Error {
 }
 
 /// The config used to initialize the messaging service.
-#[derive(Debug, Default, Deserialize, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MessagingConfig {
-    /// The settlement chain.
+    /// The settlement chain: `ethereum`, `evm` for any other EVM chain, or `starknet`.
     pub chain: String,
     /// The RPC-URL of the settlement chain.
     pub rpc_url: String,
@@ -107,6 +115,36 @@ pub struct MessagingConfig {
     pub interval: u64,
     /// The block on settlement chain from where Katana will start fetching messages.
     pub from_block: u64,
+    /// The maximum time, in seconds in the config file, to wait for a settlement chain RPC call
+    /// to complete. If `None`, calls are never timed out.
+    #[serde(
+        default,
+        serialize_with = "serialize_timeout_secs",
+        deserialize_with = "deserialize_timeout_secs"
+    )]
+    pub rpc_timeout: Option<Duration>,
+    /// The chain id of an EVM settlement chain. If set, the RPC endpoint is checked to serve this
+    /// chain when the messaging is initialized.
+    #[serde(default)]
+    pub chain_id: Option<u64>,
+    /// The number of blocks an EVM settlement chain must have on top of a block before the
+    /// messages of this block are gathered. `0` gathers the messages up to the latest block.
+    #[serde(default)]
+    pub confirmations: u64,
+}
+
+fn serialize_timeout_secs<S>(timeout: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    timeout.map(|timeout| timeout.as_secs()).serialize(serializer)
+}
+
+fn deserialize_timeout_secs<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_secs))
 }
 
 impl MessagingConfig {
@@ -172,16 +210,18 @@ pub enum MessengerMode {
 impl MessengerMode {
     pub async fn from_config(config: MessagingConfig) -> MessengerResult<Self> {
         match config.chain.as_str() {
-            CONFIG_CHAIN_ETHEREUM => match EthereumMessaging::new(config).await {
-                Ok(m_eth) => {
-                    info!(target: LOG_TARGET, "Messaging enabled [Ethereum].");
-                    Ok(MessengerMode::Ethereum(m_eth))
+            CONFIG_CHAIN_ETHEREUM | CONFIG_CHAIN_EVM => {
+                match EthereumMessaging::new(config).await {
+                    Ok(m_eth) => {
+                        info!(target: LOG_TARGET, "Messaging enabled [Ethereum].");
+                        Ok(MessengerMode::Ethereum(m_eth))
+                    }
+                    Err(e) => {
+                        error!(target: LOG_TARGET,  error = %e, "Ethereum messenger init.");
+                        Err(Error::InitError)
+                    }
                 }
-                Err(e) => {
-                    error!(target: LOG_TARGET,  error = %e, "Ethereum messenger init.");
-                    Err(Error::InitError)
-                }
-            },
+            }
 
             #[cfg(feature = "starknet-messaging")]
             CONFIG_CHAIN_STARKNET => match StarknetMessaging::new(config).await {
@@ -12,9 +12,9 @@ use katana_primitives::transaction::{ExecutableTxWithHash, L1HandlerTx, TxHash};
 use katana_provider::traits::block::BlockNumberProvider;
 use katana_provider::traits::transaction::ReceiptProvider;
 use tokio::time::{interval_at, Instant, Interval};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use super::{MessagingConfig, Messenger, MessengerMode, MessengerResult, LOG_TARGET};
+use super::{Error, MessagingConfig, Messenger, MessengerMode, MessengerResult, LOG_TARGET};
 use crate::backend::Backend;
 use crate::service::TxPool;
 
@@ -38,6 +38,8 @@ pub struct MessagingService<EF: ExecutorFactory> {
     send_from_block: u64,
     /// The message sending future.
     msg_send_fut: Option<MessageSettlingFuture>,
+    /// The maximum time to wait for a settlement chain RPC call.
+    rpc_timeout: Option<Duration>,
 }
 
 impl<EF: ExecutorFactory> MessagingService<EF> {
@@ -49,6 +51,7 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
         backend: Arc<Backend<EF>>,
     ) -> anyhow::Result<Self> {
         let gather_from_block = config.from_block;
+        let rpc_timeout = config.rpc_timeout;
         let interval = interval_from_seconds(config.interval);
         let messenger = match MessengerMode::from_config(config).await {
             Ok(m) => Arc::new(m),
@@ -69,6 +72,7 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
             send_from_block: 0,
             msg_gather_fut: None,
             msg_send_fut: None,
+            rpc_timeout,
         })
     }
 
@@ -77,6 +81,7 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
         pool: TxPool,
         backend: Arc<Backend<EF>>,
         from_block: u64,
+        rpc_timeout: Option<Duration>,
     ) -> MessengerResult<(u64, usize)> {
         // 200 avoids any possible rejection from RPC with possibly lot's of messages.
         // TODO: May this be configurable?
@@ -84,8 +89,11 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
 
         match messenger.as_ref() {
             MessengerMode::Ethereum(inner) => {
-                let (block_num, txs) =
-                    inner.gather_messages(from_block, max_block, backend.chain_id).await?;
+                let (block_num, txs) = with_timeout(
+                    rpc_timeout,
+                    inner.gather_messages(from_block, max_block, backend.chain_id),
+                )
+                .await?;
                 let txs_count = txs.len();
 
                 txs.into_iter().for_each(|tx| {
@@ -102,8 +110,11 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
 
             #[cfg(feature = "starknet-messaging")]
             MessengerMode::Starknet(inner) => {
-                let (block_num, txs) =
-                    inner.gather_messages(from_block, max_block, backend.chain_id).await?;
+                let (block_num, txs) = with_timeout(
+                    rpc_timeout,
+                    inner.gather_messages(from_block, max_block, backend.chain_id),
+                )
+                .await?;
                 let txs_count = txs.len();
 
                 txs.into_iter().for_each(|tx| {
@@ -124,6 +135,7 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
         block_num: u64,
         backend: Arc<Backend<EF>>,
         messenger: Arc<MessengerMode>,
+        rpc_timeout: Option<Duration>,
     ) -> MessengerResult<Option<(u64, usize)>> {
         let Some(messages) = ReceiptProvider::receipts_by_block(
             backend.blockchain.provider(),
@@ -139,18 +151,20 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
         } else {
             match messenger.as_ref() {
                 MessengerMode::Ethereum(inner) => {
-                    let hashes = inner.send_messages(&messages).await.map(|hashes| {
-                        hashes.iter().map(|h| format!("{h:#x}")).collect::<Vec<_>>()
-                    })?;
+                    let hashes =
+                        with_timeout(rpc_timeout, inner.send_messages(&messages)).await.map(
+                            |hashes| hashes.iter().map(|h| format!("{h:#x}")).collect::<Vec<_>>(),
+                        )?;
                     trace_msg_to_l1_sent(&messages, &hashes);
                     Ok(Some((block_num, hashes.len())))
                 }
 
                 #[cfg(feature = "starknet-messaging")]
                 MessengerMode::Starknet(inner) => {
-                    let hashes = inner.send_messages(&messages).await.map(|hashes| {
-                        hashes.iter().map(|h| format!("{h:#x}")).collect::<Vec<_>>()
-                    })?;
+                    let hashes =
+                        with_timeout(rpc_timeout, inner.send_messages(&messages)).await.map(
+                            |hashes| hashes.iter().map(|h| format!("{h:#x}")).collect::<Vec<_>>(),
+                        )?;
                     trace_msg_to_l1_sent(&messages, &hashes);
                     Ok(Some((block_num, hashes.len())))
                 }
@@ -188,6 +202,7 @@ impl<EF: ExecutorFactory> Stream for MessagingService<EF> {
                     pin.pool.clone(),
                     pin.backend.clone(),
                     pin.gather_from_block,
+                    pin.rpc_timeout,
                 )));
             }
 
@@ -199,6 +214,7 @@ impl<EF: ExecutorFactory> Stream for MessagingService<EF> {
                         pin.send_from_block,
                         pin.backend.clone(),
                         pin.messenger.clone(),
+                        pin.rpc_timeout,
                     )))
                 }
             }
@@ -209,6 +225,7 @@ impl<EF: ExecutorFactory> Stream for MessagingService<EF> {
             match gather_fut.poll_unpin(cx) {
                 Poll::Ready(Ok((last_block, msg_count))) => {
                     pin.gather_from_block = last_block + 1;
+                    pin.backend.messaging_status.write().gathered_block = Some(last_block);
                     return Poll::Ready(Some(MessagingOutcome::Gather {
                         lastest_block: last_block,
                         msg_count,
@@ -234,6 +251,7 @@ impl<EF: ExecutorFactory> Stream for MessagingService<EF> {
                     // +1 to move to the next local block to check messages to be
                     // sent on the settlement chain.
                     pin.send_from_block += 1;
+                    pin.backend.messaging_status.write().sent_block = Some(block_num);
                     return Poll::Ready(Some(MessagingOutcome::Send { block_num, msg_count }));
                 }
                 Poll::Ready(Err(e)) => {
@@ -262,6 +280,29 @@ fn interval_from_seconds(secs: u64) -> Interval {
     interval
 }
 
+/// Awaits a settlement chain call, failing with [`Error::Timeout`] if it doesn't complete
+/// within `timeout`. The service will then retry on the next interval.
+pub(super) async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    fut: impl Future<Output = MessengerResult<T>>,
+) -> MessengerResult<T> {
+    let Some(timeout) = timeout else {
+        return fut.await;
+    };
+
+    match tokio::time::timeout(timeout, fut).await {
+        Ok(res) => res,
+        Err(_) => {
+            warn!(
+                target: LOG_TARGET,
+                timeout = ?timeout,
+                "Settlement chain RPC call timed out."
+            );
+            Err(Error::Timeout)
+        }
+    }
+}
+
 fn trace_msg_to_l1_sent(messages: &[MessageToL1], hashes: &[String]) {
     assert_eq!(messages.len(), hashes.len());
 
@@ -324,3 +365,42 @@ fn trace_l1_handler_tx_exec(hash: TxHash, tx: &L1HandlerTx) {
         "L1Handler transaction added to the pool.",
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use katana_primitives::chain::{ChainId, NamedChainId};
+
+    use super::*;
+    use crate::service::messaging::ethereum::EthereumMessaging;
+    use crate::service::messaging::CONFIG_CHAIN_ETHEREUM;
+
+    #[tokio::test]
+    async fn settlement_rpc_calls_time_out_on_unresponsive_endpoint() {
+        // Accepts connections but never answers any request.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let rpc_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let config = MessagingConfig {
+            chain: CONFIG_CHAIN_ETHEREUM.to_string(),
+            rpc_url,
+            contract_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+            rpc_timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+
+        let messenger = EthereumMessaging::new(config.clone()).await.unwrap();
+
+        // Every poll times out instead of hanging, so the service can keep polling.
+        for _ in 0..2 {
+            let res = with_timeout(
+                config.rpc_timeout,
+                messenger.gather_messages(0, 200, ChainId::Named(NamedChainId::Goerli)),
+            )
+            .await;
+
+            assert!(matches!(res, Err(Error::Timeout)));
+        }
+    }
+}
@@ -126,7 +126,7 @@ pub fn transact<S: StateReader>(
     match transact_inner(state, block_context, simulation_flags, to_executor_tx(tx.clone())) {
         Ok((info, fee)) => {
             // get the trace and receipt from the execution info
-            let trace = to_exec_info(info);
+            let trace = to_exec_info(info, fee.unit);
             let receipt = build_receipt(tx.tx_ref(), fee, &trace);
             ExecutionResult::new_success(receipt, trace)
         }
@@ -559,8 +559,11 @@ fn starknet_api_ethaddr_to_felt(
     FieldElement::from_bytes_be(&bytes)
 }
 
-pub fn to_exec_info(exec_info: TransactionExecutionInfo) -> TxExecInfo {
+pub fn to_exec_info(exec_info: TransactionExecutionInfo, fee_unit: PriceUnit) -> TxExecInfo {
     TxExecInfo {
+        l1_gas_consumed: exec_info.transaction_receipt.gas.l1_gas,
+        l1_data_gas_consumed: exec_info.transaction_receipt.gas.l1_data_gas,
+        fee_unit: Some(fee_unit),
         validate_call_info: exec_info.validate_call_info.map(to_call_info),
         execute_call_info: exec_info.execute_call_info.map(to_call_info),
         fee_transfer_call_info: exec_info.fee_transfer_call_info.map(to_call_info),
@@ -63,6 +63,7 @@ pub(crate) fn build_receipt(tx: TxRef<'_>, fee: TxFeeInfo, info: &TxExecInfo) ->
             revert_error,
             messages_sent,
             message_hash: tx.message_hash,
+            paid_fee_on_l1: tx.paid_fee_on_l1,
             execution_resources: actual_resources,
         }),
 
@@ -148,9 +149,12 @@ fn get_l2_to_l1_messages_recur(info: &CallInfo) -> Vec<MessageToL1> {
 #[cfg(test)]
 mod tests {
     use katana_primitives::event::OrderedEvent;
+    use katana_primitives::fee::TxFeeInfo;
     use katana_primitives::message::OrderedL2ToL1Message;
     use katana_primitives::receipt::{Event, MessageToL1};
-    use katana_primitives::trace::CallInfo;
+    use katana_primitives::trace::{CallInfo, TxExecInfo};
+    use katana_primitives::transaction::{L1HandlerTx, TxRef};
+    use starknet::core::types::PriceUnit;
     use starknet::macros::felt;
 
     fn call_info() -> CallInfo {
@@ -248,4 +252,14 @@ mod tests {
 
         similar_asserts::assert_eq!(events, expected_messages)
     }
+
+    #[test]
+    fn l1_handler_receipt_reports_message_fee() {
+        let tx = L1HandlerTx { paid_fee_on_l1: 1337, ..Default::default() };
+        let fee =
+            TxFeeInfo { gas_consumed: 0, gas_price: 0, overall_fee: 0, unit: PriceUnit::Wei };
+
+        let receipt = super::build_receipt(TxRef::L1Handler(&tx), fee, &TxExecInfo::default());
+        assert_eq!(receipt.message_fee(), Some(1337));
+    }
 }
@@ -475,6 +475,10 @@ impl Katana {
             cmd.arg("--validate-max-steps").arg(validate_max_steps.to_string());
         }
 
+        if let Some(genesis) = self.genesis {
+            cmd.arg("--genesis").arg(genesis);
+        }
+
         let mut child = cmd.spawn().map_err(Error::SpawnError)?;
         let stdout = child.stdout.as_mut().ok_or(Error::NoStderr)?;
 
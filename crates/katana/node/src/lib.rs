@@ -149,6 +149,7 @@ pub async fn start(
         blockchain,
         executor_factory,
         block_context_generator,
+        messaging_status: Default::default(),
         config: starknet_config,
     });
 
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use katana_cairo::cairo_vm::types::builtin_name::BuiltinName;
+
+use crate::trace::ExecutionResources;
+
+/// Error returned when a name doesn't refer to any builtin known to the Cairo VM.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown builtin {0}")]
+pub struct UnknownBuiltin(pub String);
+
+/// Parses a builtin name, with or without the `_builtin` suffix, so that resources reported by
+/// different Starknet versions are classified the same way.
+pub fn parse_builtin(name: &str) -> Result<BuiltinName, UnknownBuiltin> {
+    BuiltinName::from_str_with_suffix(name)
+        .or_else(|| BuiltinName::from_str(name))
+        .ok_or_else(|| UnknownBuiltin(name.to_string()))
+}
+
+/// A builtin as named in execution resources.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Builtin {
+    /// A builtin known to the Cairo VM.
+    Known(BuiltinName),
+    /// A name that doesn't refer to any builtin known to the Cairo VM, kept as is so that
+    /// resources reported by newer Starknet versions are not lost.
+    Other(String),
+}
+
+impl FromStr for Builtin {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match parse_builtin(s) {
+            Ok(builtin) => Builtin::Known(builtin),
+            Err(UnknownBuiltin(name)) => Builtin::Other(name),
+        })
+    }
+}
+
+impl Display for Builtin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Builtin::Known(builtin) => write!(f, "{}", builtin.to_str_with_suffix()),
+            Builtin::Other(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// Parses a builtin instance counter keyed by raw builtin names.
+///
+/// Counters of names referring to the same builtin (eg, `pedersen` and `pedersen_builtin`)
+/// are summed. Unknown names are counted as [`Builtin::Other`].
+pub fn parse_builtin_counts<'a, I, K>(counters: I) -> HashMap<Builtin, u64>
+where
+    I: IntoIterator<Item = (K, &'a usize)>,
+    K: AsRef<str>,
+{
+    let mut counts = HashMap::new();
+    for (name, count) in counters {
+        let builtin = Builtin::from_str(name.as_ref()).unwrap_or_else(|e| match e {});
+        *counts.entry(builtin).or_default() += *count as u64;
+    }
+    counts
+}
+
+/// Returns the builtin instance counter of the execution resources.
+pub fn builtin_counts(resources: &ExecutionResources) -> HashMap<Builtin, u64> {
+    resources
+        .builtin_instance_counter
+        .iter()
+        .map(|(name, count)| (Builtin::Known(*name), *count as u64))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use katana_cairo::cairo_vm::types::builtin_name::BuiltinName;
+
+    use super::{parse_builtin, parse_builtin_counts, Builtin, UnknownBuiltin};
+
+    #[test]
+    fn parse_resources_builtins() {
+        let counters: HashMap<String, usize> = [
+            ("range_check_builtin", 31),
+            ("pedersen_builtin", 4),
+            ("pedersen", 1),
+            ("poseidon_builtin", 2),
+            ("ec_op_builtin", 3),
+            ("ecdsa_builtin", 1),
+            ("bitwise_builtin", 6),
+            ("keccak_builtin", 1),
+            ("segment_arena_builtin", 2),
+            ("range_check96", 8),
+        ]
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect();
+
+        let counts = parse_builtin_counts(&counters);
+        let count = |builtin: BuiltinName| counts[&Builtin::Known(builtin)];
+
+        assert_eq!(counts.len(), 9);
+        assert_eq!(count(BuiltinName::range_check), 31);
+        assert_eq!(count(BuiltinName::pedersen), 5);
+        assert_eq!(count(BuiltinName::poseidon), 2);
+        assert_eq!(count(BuiltinName::ec_op), 3);
+        assert_eq!(count(BuiltinName::ecdsa), 1);
+        assert_eq!(count(BuiltinName::bitwise), 6);
+        assert_eq!(count(BuiltinName::keccak), 1);
+        assert_eq!(count(BuiltinName::segment_arena), 2);
+        assert_eq!(count(BuiltinName::range_check96), 8);
+    }
+
+    #[test]
+    fn unknown_builtins_fall_back_to_other() {
+        assert_eq!(parse_builtin("range_check").unwrap(), BuiltinName::range_check);
+        assert_eq!(parse_builtin("gas_builtin"), Err(UnknownBuiltin("gas_builtin".to_string())));
+
+        let counters =
+            HashMap::from([("foo_builtin".to_string(), 5), ("pedersen_builtin".to_string(), 2)]);
+        let counts = parse_builtin_counts(&counters);
+
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&Builtin::Other("foo_builtin".to_string())], 5);
+        assert_eq!(counts[&Builtin::Known(BuiltinName::pedersen)], 2);
+
+        let other: Builtin = "foo_builtin".parse().unwrap();
+        assert_eq!(other.to_string(), "foo_builtin");
+        assert_eq!(Builtin::Known(BuiltinName::pedersen).to_string(), "pedersen_builtin");
+    }
+}
@@ -105,7 +105,7 @@ lazy_static! {
 ///
 /// This is to compute the base storage address of the balance because the fee token balance is
 /// stored as a U256 value and as such has to be split into two U128 values (low and high).
-pub(super) fn get_fee_token_balance_base_storage_address(address: ContractAddress) -> FieldElement {
+pub fn get_fee_token_balance_base_storage_address(address: ContractAddress) -> FieldElement {
     get_storage_var_address("ERC20_balances", &[address.into()]).unwrap()
 }
 
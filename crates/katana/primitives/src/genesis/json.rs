@@ -656,6 +656,101 @@ pub fn from_base64(data: &[u8]) -> Result<GenesisJson, GenesisJsonError> {
     Ok(serde_json::from_slice::<GenesisJson>(&decoded)?)
 }
 
+/// The entries that differ between two collections of a [GenesisJson], identified by their keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntriesDiff<K> {
+    /// Entries only present in the second genesis.
+    pub added: Vec<K>,
+    /// Entries only present in the first genesis.
+    pub removed: Vec<K>,
+    /// Entries present in both genesis, but with different values.
+    pub changed: Vec<K>,
+}
+
+impl<K> Default for EntriesDiff<K> {
+    fn default() -> Self {
+        Self { added: Vec::new(), removed: Vec::new(), changed: Vec::new() }
+    }
+}
+
+impl<K> EntriesDiff<K> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The differences between two [GenesisJson]s, as returned by [diff].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GenesisDiff {
+    /// The prefunded accounts, identified by their address.
+    pub accounts: EntriesDiff<ContractAddress>,
+    /// The predeployed contracts, identified by their address.
+    pub contracts: EntriesDiff<ContractAddress>,
+    /// The declared classes, identified by their name or else by their class hash.
+    pub classes: EntriesDiff<ClassNameOrHash>,
+}
+
+impl GenesisDiff {
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.contracts.is_empty() && self.classes.is_empty()
+    }
+}
+
+/// Computes the differences between the accounts, contracts and classes of two [GenesisJson]s,
+/// going from `a` to `b`.
+///
+/// Classes that have neither a name nor a class hash can't be identified without computing their
+/// hash, so they are not part of the diff.
+pub fn diff(a: &GenesisJson, b: &GenesisJson) -> GenesisDiff {
+    fn sorted<V>(map: &HashMap<ContractAddress, V>) -> Vec<(ContractAddress, &V)> {
+        let mut entries = map.iter().map(|(k, v)| (*k, v)).collect::<Vec<_>>();
+        entries.sort_by_key(|(k, _)| *k);
+        entries
+    }
+
+    fn classes(genesis: &GenesisJson) -> Vec<(ClassNameOrHash, &GenesisClassJson)> {
+        genesis
+            .classes
+            .iter()
+            .filter_map(|class| match (&class.name, class.class_hash) {
+                (Some(name), _) => Some((ClassNameOrHash::Name(name.clone()), class)),
+                (None, Some(hash)) => Some((ClassNameOrHash::Hash(hash), class)),
+                (None, None) => None,
+            })
+            .collect()
+    }
+
+    GenesisDiff {
+        accounts: diff_entries(sorted(&a.accounts), sorted(&b.accounts)),
+        contracts: diff_entries(sorted(&a.contracts), sorted(&b.contracts)),
+        classes: diff_entries(classes(a), classes(b)),
+    }
+}
+
+fn diff_entries<K, V>(a: Vec<(K, &V)>, b: Vec<(K, &V)>) -> EntriesDiff<K>
+where
+    K: PartialEq + Clone,
+    V: PartialEq,
+{
+    let mut diff = EntriesDiff::default();
+
+    for (key, value) in &a {
+        match b.iter().find(|(k, _)| k == key) {
+            Some((_, other)) if other != value => diff.changed.push(key.clone()),
+            Some(_) => {}
+            None => diff.removed.push(key.clone()),
+        }
+    }
+
+    for (key, _) in b {
+        if !a.iter().any(|(k, _)| *k == key) {
+            diff.added.push(key);
+        }
+    }
+
+    diff
+}
+
 fn class_artifact_at_path(
     base_path: PathBuf,
     relative_path: &PathBuf,
@@ -1268,4 +1363,32 @@ mod tests {
             res.unwrap_err().to_string().contains(&format!("Class name '{name}' already exists"))
         )
     }
+
+    #[test]
+    fn diff_reports_added_account() {
+        let a = GenesisJson::load("./src/genesis/test-genesis.json").unwrap();
+        let mut b = a.clone();
+
+        let address = ContractAddress::from(felt!("0x1337"));
+        let account = GenesisAccountJson {
+            public_key: felt!("0x1"),
+            balance: Some(U256::from(1000)),
+            nonce: None,
+            class: None,
+            storage: None,
+            private_key: None,
+        };
+        b.accounts.insert(address, account);
+
+        let genesis_diff = diff(&a, &b);
+        assert_eq!(genesis_diff.accounts.added, vec![address]);
+        assert!(genesis_diff.accounts.removed.is_empty());
+        assert!(genesis_diff.accounts.changed.is_empty());
+        assert!(genesis_diff.contracts.is_empty());
+        assert!(genesis_diff.classes.is_empty());
+
+        // the diff the other way around reports the account as removed
+        assert_eq!(diff(&b, &a).accounts.removed, vec![address]);
+        assert!(diff(&a, &a).is_empty());
+    }
 }
@@ -1,6 +1,16 @@
 use crate::contract::ContractAddress;
 use crate::FieldElement;
 
+/// The progress of the messaging service with the settlement chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessagingStatus {
+    /// The latest settlement chain block from which messages have been gathered.
+    pub gathered_block: Option<u64>,
+    /// The latest local block from which messages have been sent to the settlement chain.
+    pub sent_block: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OrderedL2ToL1Message {
@@ -70,6 +70,8 @@ pub struct L1HandlerTxReceipt {
     pub events: Vec<Event>,
     /// The hash of the L1 message
     pub message_hash: B256,
+    /// The fee paid on L1 when sending the message (in Wei).
+    pub paid_fee_on_l1: u128,
     /// Messages sent to L1.
     pub messages_sent: Vec<MessageToL1>,
     /// Revert error message if the transaction execution failed.
@@ -154,6 +156,15 @@ impl Receipt {
         }
     }
 
+    /// Returns the fee paid on L1 for the message handled by the transaction, if it's a
+    /// `L1Handler` transaction.
+    pub fn message_fee(&self) -> Option<u128> {
+        match self {
+            Receipt::L1Handler(rct) => Some(rct.paid_fee_on_l1),
+            _ => None,
+        }
+    }
+
     pub fn fee(&self) -> &TxFeeInfo {
         match self {
             Receipt::Invoke(rct) => &rct.fee,
@@ -1,9 +1,14 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::AddAssign;
 
 use katana_cairo::cairo_vm::vm;
+use serde_json::json;
+use starknet::core::types::PriceUnit;
 
 use crate::class::ClassHash;
-use crate::contract::ContractAddress;
+use crate::contract::{ContractAddress, StorageKey};
 use crate::event::OrderedEvent;
 use crate::message::OrderedL2ToL1Message;
 use crate::FieldElement;
@@ -19,13 +24,299 @@ pub struct TxExecInfo {
     pub execute_call_info: Option<CallInfo>,
     /// Fee transfer call info; [None] for `L1Handler`.
     pub fee_transfer_call_info: Option<CallInfo>,
-    /// The actual fee that was charged (in Wei).
+    /// The actual fee that was charged, in the unit given by `fee_unit`.
     pub actual_fee: u128,
     /// Actual execution resources the transaction is charged for,
     /// including L1 gas and additional OS resources estimation.
     pub actual_resources: TxResources,
     /// Error string for reverted transactions; [None] if transaction execution was successful.
     pub revert_error: Option<String>,
+    /// The L1 gas consumed by the transaction.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub l1_gas_consumed: u128,
+    /// The L1 data gas consumed by the transaction to publish its state diff as blobs.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub l1_data_gas_consumed: u128,
+    /// The unit of `actual_fee`: [`PriceUnit::Wei`] if the fee is paid in ETH, or
+    /// [`PriceUnit::Fri`] if it is paid in STRK by a v3 transaction. [None] for the traces
+    /// recorded before the unit was tracked.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fee_unit: Option<PriceUnit>,
+}
+
+impl TxExecInfo {
+    /// Returns the storage slots accessed by the transaction, across the validate, execute and
+    /// fee transfer calls (including all of their inner calls).
+    ///
+    /// Each entry is the storage context of the call (ie the contract whose storage is being
+    /// accessed, which for library calls is the calling contract) paired with the storage key.
+    /// The result is deduplicated and sorted.
+    ///
+    /// The execution trace only records which keys were accessed and the values that were read,
+    /// not the values that were written, so this cannot be used to reconstruct a write set. The
+    /// read values also can't be paired with their keys as the accessed keys are unordered. Use
+    /// the post-execution state diff to obtain the written values.
+    pub fn storage_reads(&self) -> Vec<(ContractAddress, StorageKey)> {
+        let mut reads = BTreeSet::new();
+
+        for call in self.call_infos() {
+            call.collect_storage_reads(&mut reads);
+        }
+
+        reads.into_iter().collect()
+    }
+
+    /// Returns the storage keys accessed by the transaction, read or written, grouped by the
+    /// contract whose storage they belong to.
+    ///
+    /// Like [`TxExecInfo::storage_reads`], the keys of a library call are attributed to the
+    /// calling contract (ie the `contract_address` of the call) and not to the contract the code
+    /// is taken from.
+    pub fn accessed_storage_keys(&self) -> HashMap<ContractAddress, HashSet<StorageKey>> {
+        let mut keys: HashMap<ContractAddress, HashSet<StorageKey>> = HashMap::new();
+
+        for call in self.call_infos().flat_map(CallInfo::iter) {
+            keys.entry(call.contract_address)
+                .or_default()
+                .extend(call.accessed_storage_keys.iter().copied());
+        }
+
+        keys
+    }
+
+    /// Returns the events emitted by the transaction, across the validate, execute and fee
+    /// transfer calls (including all of their inner calls), in the order they were emitted.
+    ///
+    /// The `order` of the events is only relative to the other events of the same top-level
+    /// call, so the events of each call are ordered on their own and the calls follow each
+    /// other in the order they are executed.
+    pub fn events_in_order(&self) -> Vec<&OrderedEvent> {
+        self.call_infos()
+            .flat_map(|call| {
+                let mut events = call.iter().flat_map(|c| &c.events).collect::<Vec<_>>();
+                events.sort_by_key(|event| event.order);
+                events
+            })
+            .collect()
+    }
+
+    /// Returns the L2 to L1 messages sent by the transaction in the order they were sent, like
+    /// [`TxExecInfo::events_in_order`].
+    pub fn messages_in_order(&self) -> Vec<&OrderedL2ToL1Message> {
+        self.call_infos()
+            .flat_map(|call| {
+                let mut messages =
+                    call.iter().flat_map(|c| &c.l2_to_l1_messages).collect::<Vec<_>>();
+                messages.sort_by_key(|message| message.order);
+                messages
+            })
+            .collect()
+    }
+
+    /// The top-level calls of the transaction, in the order they are executed.
+    fn call_infos(&self) -> impl Iterator<Item = &CallInfo> {
+        [&self.validate_call_info, &self.execute_call_info, &self.fee_transfer_call_info]
+            .into_iter()
+            .flatten()
+    }
+
+    /// Compares the execution info with `other`, field by field, and returns every mismatch.
+    ///
+    /// The fee, the resources (per builtin for the VM resources), the revert error and the call
+    /// trees are compared. The calls are compared recursively, on their retdata, events,
+    /// messages and storage accesses among others. An empty result means that both executions
+    /// match.
+    pub fn diff(&self, other: &Self) -> Vec<TraceDiff> {
+        let mut diffs = Vec::new();
+
+        diff_field(&mut diffs, "actual_fee", &self.actual_fee, &other.actual_fee);
+        diff_field(&mut diffs, "fee_unit", &self.fee_unit, &other.fee_unit);
+        diff_field(&mut diffs, "l1_gas_consumed", &self.l1_gas_consumed, &other.l1_gas_consumed);
+        diff_field(
+            &mut diffs,
+            "l1_data_gas_consumed",
+            &self.l1_data_gas_consumed,
+            &other.l1_data_gas_consumed,
+        );
+        diff_tx_resources(&mut diffs, &self.actual_resources, &other.actual_resources);
+        diff_field(&mut diffs, "revert_error", &self.revert_error, &other.revert_error);
+
+        let calls = [
+            ("validate_call_info", &self.validate_call_info, &other.validate_call_info),
+            ("execute_call_info", &self.execute_call_info, &other.execute_call_info),
+            ("fee_transfer_call_info", &self.fee_transfer_call_info, &other.fee_transfer_call_info),
+        ];
+
+        for (path, left, right) in calls {
+            match (left, right) {
+                (Some(left), Some(right)) => diff_calls(&mut diffs, path, left, right),
+                (None, None) => {}
+                _ => diffs.push(TraceDiff {
+                    path: path.to_string(),
+                    left: if left.is_some() { "Some(..)" } else { "None" }.to_string(),
+                    right: if right.is_some() { "Some(..)" } else { "None" }.to_string(),
+                }),
+            }
+        }
+
+        diffs
+    }
+
+    /// Exports the call trees of the transaction to the nested JSON format consumed by the
+    /// DevTools flame-chart viewers.
+    ///
+    /// This is not the trace of the RPC spec: each node only holds the selector, the contract
+    /// address, the call type, the gas consumed, whether the call failed and its inner calls as
+    /// `children`. The `validate`, `execute` and `fee_transfer` trees are `null` if the
+    /// transaction has no such call.
+    pub fn to_devtools_json(&self) -> serde_json::Value {
+        json!({
+            "validate": self.validate_call_info.as_ref().map(CallInfo::to_devtools_json),
+            "execute": self.execute_call_info.as_ref().map(CallInfo::to_devtools_json),
+            "fee_transfer": self.fee_transfer_call_info.as_ref().map(CallInfo::to_devtools_json),
+        })
+    }
+}
+
+/// A mismatch between two [`TxExecInfo`], see [`TxExecInfo::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceDiff {
+    /// The path of the mismatching field, eg `execute_call_info.inner_calls[0].retdata`.
+    pub path: String,
+    /// The debug representation of the field in the compared execution info.
+    pub left: String,
+    /// The debug representation of the field in the execution info it is compared to.
+    pub right: String,
+}
+
+impl fmt::Display for TraceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} != {}", self.path, self.left, self.right)
+    }
+}
+
+fn diff_field<T: PartialEq + fmt::Debug>(
+    diffs: &mut Vec<TraceDiff>,
+    path: impl Into<String>,
+    left: &T,
+    right: &T,
+) {
+    if left != right {
+        diffs.push(TraceDiff {
+            path: path.into(),
+            left: format!("{left:?}"),
+            right: format!("{right:?}"),
+        });
+    }
+}
+
+fn diff_tx_resources(diffs: &mut Vec<TraceDiff>, left: &TxResources, right: &TxResources) {
+    let path = "actual_resources";
+    diff_field(
+        diffs,
+        format!("{path}.n_reverted_steps"),
+        &left.n_reverted_steps,
+        &right.n_reverted_steps,
+    );
+    diff_vm_resources(
+        diffs,
+        &format!("{path}.vm_resources"),
+        &left.vm_resources,
+        &right.vm_resources,
+    );
+    diff_field(
+        diffs,
+        format!("{path}.data_availability"),
+        &left.data_availability,
+        &right.data_availability,
+    );
+    diff_field(
+        diffs,
+        format!("{path}.total_gas_consumed"),
+        &left.total_gas_consumed,
+        &right.total_gas_consumed,
+    );
+}
+
+fn diff_vm_resources(
+    diffs: &mut Vec<TraceDiff>,
+    path: &str,
+    left: &ExecutionResources,
+    right: &ExecutionResources,
+) {
+    diff_field(diffs, format!("{path}.n_steps"), &left.n_steps, &right.n_steps);
+    diff_field(
+        diffs,
+        format!("{path}.n_memory_holes"),
+        &left.n_memory_holes,
+        &right.n_memory_holes,
+    );
+
+    // A builtin missing from the counter wasn't used, which is the same as being used 0 times.
+    let left_builtins = &left.builtin_instance_counter;
+    let right_builtins = &right.builtin_instance_counter;
+    let mut builtins = left_builtins
+        .keys()
+        .chain(right_builtins.keys().filter(|name| !left_builtins.contains_key(*name)))
+        .collect::<Vec<_>>();
+    builtins.sort_by_key(|name| format!("{name:?}"));
+
+    for name in builtins {
+        diff_field(
+            diffs,
+            format!("{path}.builtin_instance_counter.{name:?}"),
+            &left_builtins.get(name).copied().unwrap_or_default(),
+            &right_builtins.get(name).copied().unwrap_or_default(),
+        );
+    }
+}
+
+fn diff_calls(diffs: &mut Vec<TraceDiff>, path: &str, left: &CallInfo, right: &CallInfo) {
+    diff_field(
+        diffs,
+        format!("{path}.contract_address"),
+        &left.contract_address,
+        &right.contract_address,
+    );
+    diff_field(
+        diffs,
+        format!("{path}.entry_point_selector"),
+        &left.entry_point_selector,
+        &right.entry_point_selector,
+    );
+    diff_field(diffs, format!("{path}.calldata"), &left.calldata, &right.calldata);
+    diff_field(diffs, format!("{path}.retdata"), &left.retdata, &right.retdata);
+    diff_field(diffs, format!("{path}.events"), &left.events, &right.events);
+    diff_field(
+        diffs,
+        format!("{path}.l2_to_l1_messages"),
+        &left.l2_to_l1_messages,
+        &right.l2_to_l1_messages,
+    );
+    diff_field(
+        diffs,
+        format!("{path}.accessed_storage_keys"),
+        &left.accessed_storage_keys.iter().collect::<BTreeSet<_>>(),
+        &right.accessed_storage_keys.iter().collect::<BTreeSet<_>>(),
+    );
+    diff_field(
+        diffs,
+        format!("{path}.storage_read_values"),
+        &left.storage_read_values,
+        &right.storage_read_values,
+    );
+    diff_field(diffs, format!("{path}.gas_consumed"), &left.gas_consumed, &right.gas_consumed);
+    diff_field(diffs, format!("{path}.failed"), &left.failed, &right.failed);
+
+    diff_field(
+        diffs,
+        format!("{path}.inner_calls.len()"),
+        &left.inner_calls.len(),
+        &right.inner_calls.len(),
+    );
+    for (i, (left, right)) in left.inner_calls.iter().zip(&right.inner_calls).enumerate() {
+        diff_calls(diffs, &format!("{path}.inner_calls[{i}]"), left, right);
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -107,3 +398,358 @@ pub struct CallInfo {
     /// True if the execution has failed, false otherwise.
     pub failed: bool,
 }
+
+impl CallInfo {
+    /// Exports the call and its inner calls as a node of the DevTools trace format, see
+    /// [`TxExecInfo::to_devtools_json`].
+    pub fn to_devtools_json(&self) -> serde_json::Value {
+        let call_type = match self.call_type {
+            CallType::Call => "CALL",
+            CallType::Delegate => "DELEGATE",
+        };
+
+        json!({
+            "selector": format!("{:#x}", self.entry_point_selector),
+            "contract_address": self.contract_address.to_string(),
+            "call_type": call_type,
+            "gas_consumed": self.gas_consumed,
+            "failed": self.failed,
+            "children": self.inner_calls.iter().map(Self::to_devtools_json).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Returns a depth-first iterator over the call and all of its inner calls, each call being
+    /// yielded before its inner calls.
+    pub fn iter(&self) -> CallInfoIter<'_> {
+        CallInfoIter { stack: vec![self] }
+    }
+
+    /// Returns the gas consumed by the call and all of its inner calls.
+    pub fn total_gas_consumed(&self) -> u128 {
+        self.iter().map(|call| call.gas_consumed).sum()
+    }
+
+    /// Returns the execution resources used by the call and all of its inner calls, the steps,
+    /// memory holes and builtin instances of all the calls being summed up.
+    pub fn aggregate_resources(&self) -> ExecutionResources {
+        self.iter().map(|call| &call.execution_resources).sum::<TotalResources>().0
+    }
+
+    fn collect_storage_reads(&self, reads: &mut BTreeSet<(ContractAddress, StorageKey)>) {
+        for call in self.iter() {
+            let address = call.contract_address;
+            reads.extend(call.accessed_storage_keys.iter().map(|key| (address, *key)));
+        }
+    }
+}
+
+/// Depth-first iterator over a [`CallInfo`] tree, see [`CallInfo::iter`].
+#[derive(Debug, Clone)]
+pub struct CallInfoIter<'a> {
+    stack: Vec<&'a CallInfo>,
+}
+
+impl<'a> Iterator for CallInfoIter<'a> {
+    type Item = &'a CallInfo;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let call = self.stack.pop()?;
+        self.stack.extend(call.inner_calls.iter().rev());
+        Some(call)
+    }
+}
+
+/// Sum of several [`ExecutionResources`], see [`CallInfo::aggregate_resources`].
+///
+/// [`ExecutionResources`] is defined by cairo-vm, so the sum is implemented on this wrapper.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TotalResources(pub ExecutionResources);
+
+impl AddAssign<&ExecutionResources> for TotalResources {
+    fn add_assign(&mut self, resources: &ExecutionResources) {
+        self.0.n_steps += resources.n_steps;
+        self.0.n_memory_holes += resources.n_memory_holes;
+
+        for (builtin, count) in &resources.builtin_instance_counter {
+            *self.0.builtin_instance_counter.entry(*builtin).or_default() += count;
+        }
+    }
+}
+
+impl<'a> Sum<&'a ExecutionResources> for TotalResources {
+    fn sum<I: Iterator<Item = &'a ExecutionResources>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut total, resources| {
+            total += resources;
+            total
+        })
+    }
+}
+
+impl<'a> IntoIterator for &'a CallInfo {
+    type Item = &'a CallInfo;
+    type IntoIter = CallInfoIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_cairo::cairo_vm::types::builtin_name::BuiltinName;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    #[test]
+    fn storage_reads_matches_accessed_keys() {
+        let erc20 = ContractAddress(felt!("0x1"));
+        let account = ContractAddress(felt!("0x2"));
+
+        let transfer = CallInfo {
+            contract_address: erc20,
+            accessed_storage_keys: HashSet::from([felt!("0x10"), felt!("0x11")]),
+            storage_read_values: vec![felt!("0x64"), felt!("0x0")],
+            ..Default::default()
+        };
+
+        let execute = CallInfo {
+            contract_address: account,
+            accessed_storage_keys: HashSet::from([felt!("0x20")]),
+            storage_read_values: vec![felt!("0x1")],
+            inner_calls: vec![transfer.clone()],
+            ..Default::default()
+        };
+
+        let info = TxExecInfo {
+            execute_call_info: Some(execute),
+            fee_transfer_call_info: Some(transfer),
+            ..Default::default()
+        };
+
+        let expected =
+            vec![(erc20, felt!("0x10")), (erc20, felt!("0x11")), (account, felt!("0x20"))];
+
+        assert_eq!(info.storage_reads(), expected);
+    }
+
+    #[test]
+    fn accessed_storage_keys_are_attributed_to_the_storage_contract() {
+        let account = ContractAddress(felt!("0x1"));
+        let library = ContractAddress(felt!("0x2"));
+
+        let library_call = CallInfo {
+            call_type: CallType::Delegate,
+            contract_address: account,
+            code_address: Some(library),
+            accessed_storage_keys: HashSet::from([felt!("0x10"), felt!("0x11")]),
+            ..Default::default()
+        };
+
+        let execute = CallInfo {
+            contract_address: account,
+            accessed_storage_keys: HashSet::from([felt!("0x10")]),
+            inner_calls: vec![library_call],
+            ..Default::default()
+        };
+
+        let info = TxExecInfo { execute_call_info: Some(execute), ..Default::default() };
+
+        let expected = HashMap::from([(account, HashSet::from([felt!("0x10"), felt!("0x11")]))]);
+        assert_eq!(info.accessed_storage_keys(), expected);
+    }
+
+    #[test]
+    fn iter_walks_the_call_tree_depth_first() {
+        let call = |selector: FieldElement, inner_calls: Vec<CallInfo>| CallInfo {
+            entry_point_selector: selector,
+            inner_calls,
+            ..Default::default()
+        };
+
+        let first = call(felt!("0x2"), vec![call(felt!("0x3"), vec![])]);
+        let second = call(felt!("0x4"), vec![]);
+        let tree = call(felt!("0x1"), vec![first, second]);
+
+        let selectors = tree.iter().map(|c| c.entry_point_selector).collect::<Vec<_>>();
+        assert_eq!(selectors, vec![felt!("0x1"), felt!("0x2"), felt!("0x3"), felt!("0x4")]);
+    }
+
+    #[test]
+    fn call_tree_gas_and_resources_are_aggregated() {
+        let resources =
+            |n_steps, n_memory_holes, builtins: &[(BuiltinName, usize)]| ExecutionResources {
+                n_steps,
+                n_memory_holes,
+                builtin_instance_counter: builtins.iter().cloned().collect(),
+            };
+
+        let transfer = CallInfo {
+            gas_consumed: 30,
+            execution_resources: resources(100, 2, &[(BuiltinName::range_check, 4)]),
+            ..Default::default()
+        };
+
+        let approve = CallInfo {
+            gas_consumed: 20,
+            execution_resources: resources(50, 0, &[(BuiltinName::pedersen, 1)]),
+            ..Default::default()
+        };
+
+        let execute = CallInfo {
+            gas_consumed: 100,
+            execution_resources: resources(200, 1, &[(BuiltinName::range_check, 6)]),
+            inner_calls: vec![CallInfo { inner_calls: vec![transfer], ..approve }],
+            ..Default::default()
+        };
+
+        // The gas and resources of each call are its own, the totals cover the whole sub-tree.
+        assert_eq!(execute.total_gas_consumed(), 150);
+        assert_eq!(execute.inner_calls[0].total_gas_consumed(), 50);
+
+        let expected =
+            resources(350, 3, &[(BuiltinName::range_check, 10), (BuiltinName::pedersen, 1)]);
+        assert_eq!(execute.aggregate_resources(), expected);
+        assert_ne!(execute.aggregate_resources(), execute.execution_resources);
+    }
+
+    #[test]
+    fn events_and_messages_are_merged_in_emission_order() {
+        let event = |order: u64| OrderedEvent { order, keys: vec![], data: vec![order.into()] };
+        let message = |order: u64| OrderedL2ToL1Message { order, ..Default::default() };
+
+        let inner = CallInfo {
+            events: vec![event(1)],
+            l2_to_l1_messages: vec![message(0)],
+            ..Default::default()
+        };
+
+        let execute = CallInfo {
+            events: vec![event(2), event(0)],
+            l2_to_l1_messages: vec![message(1)],
+            inner_calls: vec![inner],
+            ..Default::default()
+        };
+
+        // The order of the validate events isn't related to the order of the execute events.
+        let validate = CallInfo { events: vec![event(3)], ..Default::default() };
+
+        let info = TxExecInfo {
+            validate_call_info: Some(validate),
+            execute_call_info: Some(execute),
+            ..Default::default()
+        };
+
+        let events = info.events_in_order().into_iter().cloned().collect::<Vec<_>>();
+        assert_eq!(events, vec![event(3), event(0), event(1), event(2)]);
+
+        let orders = info.messages_in_order().iter().map(|m| m.order).collect::<Vec<_>>();
+        assert_eq!(orders, vec![0, 1]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn traces_without_gas_fields_are_deserialized() {
+        let info = TxExecInfo {
+            actual_fee: 10,
+            l1_gas_consumed: 5,
+            l1_data_gas_consumed: 2,
+            fee_unit: Some(PriceUnit::Fri),
+            ..Default::default()
+        };
+
+        let mut json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["fee_unit"], "FRI");
+
+        let fields = json.as_object_mut().unwrap();
+        for field in ["l1_gas_consumed", "l1_data_gas_consumed", "fee_unit"] {
+            fields.remove(field);
+        }
+
+        let historical: TxExecInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(historical, TxExecInfo { actual_fee: 10, ..Default::default() });
+    }
+
+    #[test]
+    fn devtools_json_nests_inner_calls() {
+        let transfer = CallInfo {
+            contract_address: ContractAddress(felt!("0x1")),
+            entry_point_selector: felt!("0xa"),
+            call_type: CallType::Delegate,
+            gas_consumed: 30,
+            failed: true,
+            ..Default::default()
+        };
+
+        let execute = CallInfo {
+            contract_address: ContractAddress(felt!("0x2")),
+            entry_point_selector: felt!("0xb"),
+            gas_consumed: 100,
+            inner_calls: vec![transfer],
+            ..Default::default()
+        };
+
+        let info = TxExecInfo { execute_call_info: Some(execute), ..Default::default() };
+
+        let expected = json!({
+            "validate": null,
+            "execute": {
+                "selector": "0xb",
+                "contract_address": "0x2",
+                "call_type": "CALL",
+                "gas_consumed": 100,
+                "failed": false,
+                "children": [{
+                    "selector": "0xa",
+                    "contract_address": "0x1",
+                    "call_type": "DELEGATE",
+                    "gas_consumed": 30,
+                    "failed": true,
+                    "children": [],
+                }],
+            },
+            "fee_transfer": null,
+        });
+
+        assert_eq!(info.to_devtools_json(), expected);
+    }
+
+    #[test]
+    fn diff_reports_mismatches_with_their_path() {
+        let transfer = CallInfo { retdata: vec![felt!("0x1")], ..Default::default() };
+        let execute = CallInfo { inner_calls: vec![transfer], ..Default::default() };
+
+        let mut actual_resources = TxResources::default();
+        actual_resources.vm_resources.builtin_instance_counter.insert(BuiltinName::pedersen, 2);
+
+        let reference = TxExecInfo {
+            execute_call_info: Some(execute),
+            actual_fee: 10,
+            actual_resources,
+            ..Default::default()
+        };
+        assert!(reference.diff(&reference).is_empty());
+
+        let mut katana = reference.clone();
+        katana.actual_fee = 12;
+        katana.revert_error = Some("reverted".to_string());
+        katana.fee_transfer_call_info = Some(CallInfo::default());
+        katana.actual_resources.vm_resources.builtin_instance_counter.clear();
+        katana.execute_call_info.as_mut().unwrap().inner_calls[0].retdata = vec![felt!("0x2")];
+
+        let paths = katana.diff(&reference).into_iter().map(|d| d.path).collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "actual_fee",
+                "actual_resources.vm_resources.builtin_instance_counter.pedersen",
+                "revert_error",
+                "execute_call_info.inner_calls[0].retdata",
+                "fee_transfer_call_info",
+            ]
+        );
+
+        let diff = &katana.diff(&reference)[0];
+        assert_eq!(diff.to_string(), "actual_fee: 12 != 10");
+    }
+}
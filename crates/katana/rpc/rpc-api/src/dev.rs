@@ -1,5 +1,7 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::message::MessagingStatus;
 use katana_primitives::FieldElement;
 use katana_rpc_types::account::Account;
 
@@ -26,6 +28,19 @@ pub trait DevApi {
         value: FieldElement,
     ) -> RpcResult<()>;
 
+    /// Returns the hashes of the transactions executed in the pending block but not yet mined.
+    #[method(name = "pendingTransactions")]
+    async fn pending_transactions(&self) -> RpcResult<Vec<FieldElement>>;
+
+    /// Returns the progress of the messaging service. Empty if messaging is disabled.
+    #[method(name = "messagingStatus")]
+    async fn messaging_status(&self) -> RpcResult<MessagingStatus>;
+
+    /// Returns the latest state of the chain as a genesis configuration that another instance can
+    /// boot from.
+    #[method(name = "exportGenesis")]
+    async fn export_genesis(&self) -> RpcResult<GenesisJson>;
+
     #[method(name = "predeployedAccounts")]
     async fn predeployed_accounts(&self) -> RpcResult<Vec<Account>>;
 }
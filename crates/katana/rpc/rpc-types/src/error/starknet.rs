@@ -8,6 +8,8 @@ use katana_provider::error::ProviderError;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::trace::TxTraceError;
+
 /// Possible list of errors that can be returned by the Starknet API according to the spec: <https://github.com/starkware-libs/starknet-specs>.
 #[derive(Debug, thiserror::Error, Clone, Serialize)]
 #[serde(untagged)]
@@ -161,6 +163,12 @@ impl From<ContinuationTokenError> for StarknetApiError {
     }
 }
 
+impl From<TxTraceError> for StarknetApiError {
+    fn from(value: TxTraceError) -> Self {
+        StarknetApiError::UnexpectedError { reason: value.to_string() }
+    }
+}
+
 impl From<anyhow::Error> for StarknetApiError {
     fn from(value: anyhow::Error) -> Self {
         StarknetApiError::UnexpectedError { reason: value.to_string() }
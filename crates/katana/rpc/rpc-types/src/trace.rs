@@ -1,9 +1,13 @@
 use katana_cairo::cairo_vm::types::builtin_name::BuiltinName;
-use katana_primitives::trace::{CallInfo, TxExecInfo};
+use katana_primitives::receipt::Receipt;
+use katana_primitives::trace::{CallInfo, ExecutionResources, TxExecInfo};
 use katana_primitives::transaction::TxHash;
 use serde::{Deserialize, Serialize};
 use starknet::core::types::{
-    CallType, ComputationResources, EntryPointType, OrderedEvent, OrderedMessage,
+    CallType, ComputationResources, DataAvailabilityResources, DataResources,
+    DeclareTransactionTrace, DeployAccountTransactionTrace, EntryPointType, ExecuteInvocation,
+    InvokeTransactionTrace, L1HandlerTransactionTrace, OrderedEvent, OrderedMessage,
+    RevertedInvocation, TransactionTrace,
 };
 
 use crate::utils::get_builtin_instance_count;
@@ -43,40 +47,9 @@ impl From<CallInfo> for FunctionInvocation {
             })
             .collect();
 
-        let resources = info.execution_resources;
-
         // TODO: replace execution resources type in primitive CallInfo with an already defined
         // `TxExecutionResources`
-        let execution_resources = ComputationResources {
-            steps: resources.n_steps as u64,
-            memory_holes: Some(resources.n_memory_holes as u64),
-            range_check_builtin_applications: get_builtin_instance_count(
-                &resources,
-                BuiltinName::range_check,
-            ),
-            pedersen_builtin_applications: get_builtin_instance_count(
-                &resources,
-                BuiltinName::pedersen,
-            ),
-            poseidon_builtin_applications: get_builtin_instance_count(
-                &resources,
-                BuiltinName::poseidon,
-            ),
-            ec_op_builtin_applications: get_builtin_instance_count(&resources, BuiltinName::ec_op),
-            ecdsa_builtin_applications: get_builtin_instance_count(&resources, BuiltinName::ecdsa),
-            bitwise_builtin_applications: get_builtin_instance_count(
-                &resources,
-                BuiltinName::bitwise,
-            ),
-            keccak_builtin_applications: get_builtin_instance_count(
-                &resources,
-                BuiltinName::keccak,
-            ),
-            segment_arena_builtin: get_builtin_instance_count(
-                &resources,
-                BuiltinName::segment_arena,
-            ),
-        };
+        let execution_resources = computation_resources(&info.execution_resources);
 
         Self(starknet::core::types::FunctionInvocation {
             calls,
@@ -96,6 +69,107 @@ impl From<CallInfo> for FunctionInvocation {
     }
 }
 
+/// Error returned when the execution info of a transaction can't be converted into a trace.
+#[derive(Debug, thiserror::Error)]
+pub enum TxTraceError {
+    #[error("Missing the execute call info of a successful {0} transaction")]
+    MissingExecuteCallInfo(&'static str),
+}
+
+/// The trace of a transaction, in the format returned by the `starknet_traceTransaction` RPC
+/// method.
+#[derive(Debug)]
+pub struct TxTrace(pub TransactionTrace);
+
+impl TxTrace {
+    /// Builds the trace from the execution info of a transaction. The `receipt` of the
+    /// transaction determines which kind of trace is built.
+    ///
+    /// Fails if the execute call info of a transaction which didn't revert is missing.
+    pub fn new(info: TxExecInfo, receipt: &Receipt) -> Result<Self, TxTraceError> {
+        let validate_invocation = info.validate_call_info.map(|f| FunctionInvocation::from(f).0);
+        let execute_invocation = info.execute_call_info.map(|f| FunctionInvocation::from(f).0);
+        let fee_transfer_invocation =
+            info.fee_transfer_call_info.map(|f| FunctionInvocation::from(f).0);
+
+        let resources = info.actual_resources;
+        let execution_resources = starknet::core::types::ExecutionResources {
+            computation_resources: computation_resources(&resources.vm_resources),
+            data_resources: DataResources {
+                data_availability: DataAvailabilityResources {
+                    l1_gas: resources.data_availability.l1_gas as u64,
+                    l1_data_gas: resources.data_availability.l1_data_gas as u64,
+                },
+            },
+        };
+
+        // TODO: compute the state diff
+        let state_diff = None;
+
+        let trace = match receipt {
+            Receipt::Invoke(_) => TransactionTrace::Invoke(InvokeTransactionTrace {
+                validate_invocation,
+                execute_invocation: match info.revert_error {
+                    Some(revert_reason) => {
+                        ExecuteInvocation::Reverted(RevertedInvocation { revert_reason })
+                    }
+                    None => ExecuteInvocation::Success(
+                        execute_invocation.ok_or(TxTraceError::MissingExecuteCallInfo("invoke"))?,
+                    ),
+                },
+                fee_transfer_invocation,
+                state_diff,
+                execution_resources,
+            }),
+
+            Receipt::Declare(_) => TransactionTrace::Declare(DeclareTransactionTrace {
+                validate_invocation,
+                fee_transfer_invocation,
+                state_diff,
+                execution_resources,
+            }),
+
+            Receipt::DeployAccount(_) => {
+                TransactionTrace::DeployAccount(DeployAccountTransactionTrace {
+                    validate_invocation,
+                    constructor_invocation: execute_invocation
+                        .ok_or(TxTraceError::MissingExecuteCallInfo("deploy account"))?,
+                    fee_transfer_invocation,
+                    state_diff,
+                    execution_resources,
+                })
+            }
+
+            Receipt::L1Handler(_) => TransactionTrace::L1Handler(L1HandlerTransactionTrace {
+                function_invocation: execute_invocation
+                    .ok_or(TxTraceError::MissingExecuteCallInfo("l1 handler"))?,
+                state_diff,
+                execution_resources,
+            }),
+        };
+
+        Ok(Self(trace))
+    }
+}
+
+fn computation_resources(resources: &ExecutionResources) -> ComputationResources {
+    ComputationResources {
+        steps: resources.n_steps as u64,
+        memory_holes: Some(resources.n_memory_holes as u64),
+        range_check_builtin_applications: get_builtin_instance_count(
+            resources,
+            BuiltinName::range_check,
+        ),
+        pedersen_builtin_applications: get_builtin_instance_count(resources, BuiltinName::pedersen),
+        poseidon_builtin_applications: get_builtin_instance_count(resources, BuiltinName::poseidon),
+        ec_op_builtin_applications: get_builtin_instance_count(resources, BuiltinName::ec_op),
+        ecdsa_builtin_applications: get_builtin_instance_count(resources, BuiltinName::ecdsa),
+        bitwise_builtin_applications: get_builtin_instance_count(resources, BuiltinName::bitwise),
+        keccak_builtin_applications: get_builtin_instance_count(resources, BuiltinName::keccak),
+        segment_arena_builtin: get_builtin_instance_count(resources, BuiltinName::segment_arena),
+    }
+}
+
 /// The type returned by the `saya_getTransactionExecutionsByBlock` RPC method.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TxExecutionInfo {
@@ -104,3 +178,86 @@ pub struct TxExecutionInfo {
     /// The transaction execution trace.
     pub trace: TxExecInfo,
 }
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::event::OrderedEvent;
+    use katana_primitives::fee::TxFeeInfo;
+    use katana_primitives::receipt::InvokeTxReceipt;
+    use katana_primitives::trace::{self, TxResources};
+    use katana_primitives::FieldElement;
+    use starknet::core::types::PriceUnit;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    fn invoke_receipt(revert_error: Option<String>) -> Receipt {
+        Receipt::Invoke(InvokeTxReceipt {
+            fee: TxFeeInfo { gas_consumed: 0, gas_price: 0, overall_fee: 0, unit: PriceUnit::Wei },
+            events: vec![],
+            messages_sent: vec![],
+            revert_error,
+            execution_resources: TxResources::default(),
+        })
+    }
+
+    fn call(class_hash: FieldElement) -> CallInfo {
+        CallInfo { class_hash: Some(class_hash), ..Default::default() }
+    }
+
+    #[test]
+    fn invoke_exec_info_is_converted_to_rpc_trace() {
+        let inner = CallInfo {
+            call_type: trace::CallType::Delegate,
+            events: vec![OrderedEvent { order: 0, keys: vec![felt!("0x1")], data: vec![] }],
+            ..call(felt!("0xb"))
+        };
+        let execute = CallInfo { inner_calls: vec![inner], ..call(felt!("0xa")) };
+
+        let info = TxExecInfo {
+            validate_call_info: Some(call(felt!("0xc"))),
+            execute_call_info: Some(execute),
+            fee_transfer_call_info: Some(call(felt!("0xd"))),
+            ..Default::default()
+        };
+
+        let trace = TxTrace::new(info, &invoke_receipt(None)).unwrap();
+        let TransactionTrace::Invoke(trace) = trace.0 else {
+            panic!("expected an invoke trace");
+        };
+
+        assert_eq!(trace.validate_invocation.unwrap().class_hash, felt!("0xc"));
+        assert_eq!(trace.fee_transfer_invocation.unwrap().class_hash, felt!("0xd"));
+
+        let ExecuteInvocation::Success(execute) = trace.execute_invocation else {
+            panic!("expected a successful execution");
+        };
+        assert_eq!(execute.class_hash, felt!("0xa"));
+        assert_eq!(execute.calls[0].class_hash, felt!("0xb"));
+        assert_eq!(execute.calls[0].call_type, CallType::Delegate);
+        assert_eq!(execute.calls[0].entry_point_type, EntryPointType::External);
+        assert_eq!(execute.calls[0].events[0].keys, vec![felt!("0x1")]);
+    }
+
+    #[test]
+    fn reverted_invoke_exec_info_is_converted_to_rpc_trace() {
+        let revert_error = "execution failed".to_string();
+        let info = TxExecInfo { revert_error: Some(revert_error.clone()), ..Default::default() };
+
+        let receipt = invoke_receipt(Some(revert_error.clone()));
+        let TransactionTrace::Invoke(trace) = TxTrace::new(info, &receipt).unwrap().0 else {
+            panic!("expected an invoke trace");
+        };
+
+        assert_eq!(
+            trace.execute_invocation,
+            ExecuteInvocation::Reverted(RevertedInvocation { revert_reason: revert_error })
+        );
+    }
+
+    #[test]
+    fn successful_exec_info_without_execute_call_info_is_rejected() {
+        let result = TxTrace::new(TxExecInfo::default(), &invoke_receipt(None));
+        assert!(matches!(result, Err(TxTraceError::MissingExecuteCallInfo("invoke"))));
+    }
+}
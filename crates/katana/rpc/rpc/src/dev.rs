@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
 use jsonrpsee::core::{async_trait, Error};
+use katana_core::backend::genesis::genesis_json;
 use katana_core::backend::Backend;
 use katana_core::service::block_producer::{BlockProducer, BlockProducerMode, PendingExecutor};
 use katana_executor::ExecutorFactory;
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::message::MessagingStatus;
 use katana_primitives::FieldElement;
 use katana_rpc_api::dev::DevApiServer;
 use katana_rpc_types::account::Account;
@@ -92,6 +95,23 @@ impl<EF: ExecutorFactory> DevApiServer for DevApi<EF> {
         Ok(())
     }
 
+    async fn pending_transactions(&self) -> Result<Vec<FieldElement>, Error> {
+        let hashes = match self.pending_executor() {
+            Some(exec) => exec.read().transactions().iter().map(|(tx, _)| tx.hash).collect(),
+            None => vec![],
+        };
+
+        Ok(hashes)
+    }
+
+    async fn messaging_status(&self) -> Result<MessagingStatus, Error> {
+        Ok(*self.backend.messaging_status.read())
+    }
+
+    async fn export_genesis(&self) -> Result<GenesisJson, Error> {
+        genesis_json(&self.backend).map_err(|e| Error::Custom(e.to_string()))
+    }
+
     #[allow(deprecated)]
     async fn predeployed_accounts(&self) -> Result<Vec<Account>, Error> {
         Ok(self.backend.config.genesis.accounts().map(|e| Account::new(*e.0, e.1)).collect())
@@ -3,19 +3,13 @@ use jsonrpsee::types::error::{CallError, METHOD_NOT_FOUND_CODE};
 use jsonrpsee::types::ErrorObject;
 use katana_executor::{ExecutionResult, ExecutorFactory, ResultAndStates};
 use katana_primitives::block::BlockIdOrTag;
-use katana_primitives::receipt::Receipt;
 use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxHash};
 use katana_rpc_api::starknet::StarknetTraceApiServer;
 use katana_rpc_types::error::starknet::StarknetApiError;
-use katana_rpc_types::trace::FunctionInvocation;
+use katana_rpc_types::trace::TxTrace;
 use katana_rpc_types::transaction::BroadcastedTx;
 use katana_rpc_types::{FeeEstimate, SimulationFlag};
-use starknet::core::types::{
-    ComputationResources, DataAvailabilityResources, DataResources, DeclareTransactionTrace,
-    DeployAccountTransactionTrace, ExecuteInvocation, ExecutionResources, InvokeTransactionTrace,
-    L1HandlerTransactionTrace, RevertedInvocation, SimulatedTransaction, TransactionTrace,
-    TransactionTraceWithHash,
-};
+use starknet::core::types::{SimulatedTransaction, TransactionTrace, TransactionTraceWithHash};
 
 use super::StarknetApi;
 
@@ -101,86 +95,8 @@ impl<EF: ExecutorFactory> StarknetTraceApiServer for StarknetApi<EF> {
             for (i, ResultAndStates { result, .. }) in results.into_iter().enumerate() {
                 match result {
                     ExecutionResult::Success { trace, receipt } => {
-                        let fee_transfer_invocation =
-                            trace.fee_transfer_call_info.map(|f| FunctionInvocation::from(f).0);
-                        let validate_invocation =
-                            trace.validate_call_info.map(|f| FunctionInvocation::from(f).0);
-                        let execute_invocation =
-                            trace.execute_call_info.map(|f| FunctionInvocation::from(f).0);
-                        let revert_reason = trace.revert_error;
-                        // TODO: compute the state diff
-                        let state_diff = None;
-
-                        let execution_resources = ExecutionResources {
-                            computation_resources: ComputationResources {
-                                steps: 0,
-                                memory_holes: None,
-                                segment_arena_builtin: None,
-                                ecdsa_builtin_applications: None,
-                                ec_op_builtin_applications: None,
-                                keccak_builtin_applications: None,
-                                bitwise_builtin_applications: None,
-                                pedersen_builtin_applications: None,
-                                poseidon_builtin_applications: None,
-                                range_check_builtin_applications: None,
-                            },
-                            data_resources: DataResources {
-                                data_availability: DataAvailabilityResources {
-                                    l1_gas: 0,
-                                    l1_data_gas: 0,
-                                },
-                            },
-                        };
-
-                        let transaction_trace = match receipt {
-                            Receipt::Invoke(_) => {
-                                TransactionTrace::Invoke(InvokeTransactionTrace {
-                                    fee_transfer_invocation,
-                                    validate_invocation,
-                                    state_diff,
-                                    execute_invocation: if let Some(revert_reason) = revert_reason {
-                                        ExecuteInvocation::Reverted(RevertedInvocation {
-                                            revert_reason,
-                                        })
-                                    } else {
-                                        ExecuteInvocation::Success(
-                                            execute_invocation
-                                                .expect("should exist if not reverted"),
-                                        )
-                                    },
-                                    execution_resources: execution_resources.clone(),
-                                })
-                            }
-
-                            Receipt::Declare(_) => {
-                                TransactionTrace::Declare(DeclareTransactionTrace {
-                                    fee_transfer_invocation,
-                                    validate_invocation,
-                                    state_diff,
-                                    execution_resources: execution_resources.clone(),
-                                })
-                            }
-
-                            Receipt::DeployAccount(_) => {
-                                TransactionTrace::DeployAccount(DeployAccountTransactionTrace {
-                                    fee_transfer_invocation,
-                                    validate_invocation,
-                                    state_diff,
-                                    constructor_invocation: execute_invocation
-                                        .expect("should exist bcs tx succeed"),
-                                    execution_resources: execution_resources.clone(),
-                                })
-                            }
-
-                            Receipt::L1Handler(_) => {
-                                TransactionTrace::L1Handler(L1HandlerTransactionTrace {
-                                    state_diff,
-                                    function_invocation: execute_invocation
-                                        .expect("should exist bcs tx succeed"),
-                                    execution_resources,
-                                })
-                            }
-                        };
+                        let transaction_trace =
+                            TxTrace::new(trace, &receipt).map_err(StarknetApiError::from)?.0;
 
                         let fee = receipt.fee();
                         simulated.push(SimulatedTransaction {
@@ -1,7 +1,7 @@
 use std::fs::File;
 use std::path::PathBuf;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use katana_cairo::lang::starknet_classes::casm_contract_class::CasmContractClass;
 use katana_cairo::lang::starknet_classes::contract_class::ContractClass;
 use katana_primitives::conversion::rpc::CompiledClass;
@@ -20,6 +20,26 @@ pub fn prepare_contract_declaration_params(
     Ok((flattened_class, compiled_class_hash))
 }
 
+/// Recomputes the compiled class hash from the Sierra class at `artifact_path` and checks that it
+/// matches `compiled_class_hash`, since a mismatched pair is only rejected once declared.
+#[allow(dead_code)]
+pub fn verify_compiled_class_hash(
+    artifact_path: &PathBuf,
+    compiled_class_hash: Felt,
+) -> Result<()> {
+    let expected = get_compiled_class_hash(artifact_path)
+        .map_err(|e| anyhow!("error computing compiled class hash: {e}"))?;
+
+    if expected != compiled_class_hash {
+        bail!(
+            "compiled class hash mismatch: expected {expected:#x} from the Sierra class, got \
+             {compiled_class_hash:#x}"
+        );
+    }
+
+    Ok(())
+}
+
 fn get_flattened_class(artifact_path: &PathBuf) -> Result<FlattenedSierraClass> {
     let file = File::open(artifact_path)?;
     let contract_artifact: SierraClass = serde_json::from_reader(&file)?;
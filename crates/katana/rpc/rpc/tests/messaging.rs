@@ -12,9 +12,7 @@ use katana_primitives::utils::transaction::{
     compute_l1_handler_tx_hash, compute_l1_to_l2_message_hash, compute_l2_to_l1_message_hash,
 };
 use katana_rpc_types::receipt::ReceiptBlock;
-use katana_runner::{KatanaRunner, KatanaRunnerConfig};
-use rand::Rng;
-use serde_json::json;
+use katana_runner::{AnvilRunner, KatanaRunner, KatanaRunnerConfig, MessagingConfig};
 use starknet::accounts::{Account, ConnectedAccount};
 use starknet::contract::ContractFactory;
 use starknet::core::types::{
@@ -24,7 +22,6 @@ use starknet::core::types::{
 use starknet::core::utils::get_contract_address;
 use starknet::macros::selector;
 use starknet::providers::Provider;
-use tempfile::tempdir;
 
 mod common;
 
@@ -46,15 +43,12 @@ abigen!(CairoMessagingContract, "crates/katana/rpc/rpc/tests/test_data/cairo_l1_
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_messaging() {
-    // TODO: If there's a way to get the endpoint of anvil from the `l1_provider`, we could
-    // remove that and use default anvil to let the OS assign the port.
-    let port: u16 = rand::thread_rng().gen_range(35000..65000);
-
-    let l1_provider = {
-        ProviderBuilder::new()
-            .with_recommended_fillers()
-            .on_anvil_with_wallet_and_config(|anvil| anvil.port(port))
-    };
+    let anvil = AnvilRunner::new().unwrap();
+
+    let l1_provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(anvil.wallet(0))
+        .on_http(anvil.url());
 
     // Deploy the core messaging contract on L1
     let core_contract = StarknetContract::deploy(&l1_provider).await.unwrap();
@@ -63,24 +57,21 @@ async fn test_messaging() {
     let l1_test_contract = Contract1::deploy(&l1_provider, *core_contract.address()).await.unwrap();
 
     // Prepare Katana + Messaging Contract
-    let messaging_config = json!({
-        "chain": "ethereum",
-        "rpc_url": format!("http://localhost:{}", port),
-        "contract_address": core_contract.address().to_string(),
-        "sender_address": l1_provider.default_signer_address(),
-        "private_key": "",
-        "interval": 2,
-        "from_block": 0
-    })
-    .to_string();
-
-    let dir = tempdir().expect("failed creating temp dir");
-    let path = dir.path().join("temp-anvil-messaging.json");
-    std::fs::write(&path, messaging_config.as_bytes()).expect("failed to write config to file");
+    let messaging_config = MessagingConfig {
+        chain: "ethereum".to_string(),
+        rpc_url: anvil.endpoint(),
+        contract_address: core_contract.address().to_string(),
+        sender_address: l1_provider.default_signer_address().to_string(),
+        private_key: "".to_string(),
+        interval: 2,
+        from_block: 0,
+        ..Default::default()
+    };
 
     let katana_runner = KatanaRunner::new_with_config(KatanaRunnerConfig {
         n_accounts: 2,
-        messaging: Some(path.to_str().unwrap().to_string()),
+        messaging: Some(messaging_config.into()),
+        dev: true,
         ..Default::default()
     })
     .unwrap();
@@ -169,8 +160,12 @@ async fn test_messaging() {
 
         assert!(receipt.status(), "failed to send L1 -> L2 message");
 
-        // Wait for the tx to be mined on L2 (Katana)
-        tokio::time::sleep(Duration::from_secs(5)).await;
+        // Wait for Katana to gather the messages of the L1 block including the tx
+        let l1_block = receipt.block_number.expect("L1 tx must be mined");
+        katana_runner
+            .wait_for_messaging_synced(l1_block, Duration::from_secs(30))
+            .await
+            .expect("L1 messages not gathered");
 
         // In an l1_handler transaction, the first element of the calldata is always the Ethereum
         // address of the sender (msg.sender).
@@ -187,6 +182,11 @@ async fn test_messaging() {
             nonce.to::<u64>().into(),
         );
 
+        // The message has been gathered, wait for the l1 handler tx to be mined on L2 (Katana)
+        TransactionWaiter::new(tx_hash, katana_account.provider())
+            .await
+            .expect("l1 handler tx failed");
+
         // fetch the transaction
         let tx = katana_account
             .provider()
@@ -255,13 +255,20 @@ async fn test_messaging() {
             .await
             .expect("Call to send_message_value failed");
 
-        TransactionWaiter::new(res.transaction_hash, katana_account.provider())
+        let receipt = TransactionWaiter::new(res.transaction_hash, katana_account.provider())
             .with_tx_status(TransactionFinalityStatus::AcceptedOnL2)
             .await
             .expect("send message to l1 tx failed");
 
-        // Wait for the tx to be mined on L1 (Anvil)
-        tokio::time::sleep(Duration::from_secs(3)).await;
+        let ReceiptBlock::Block { block_number, .. } = receipt.block else {
+            panic!("send message to l1 tx not mined");
+        };
+
+        // Wait for the messages of the block to be sent and mined on L1 (Anvil)
+        katana_runner
+            .wait_for_messages_sent(block_number, Duration::from_secs(30))
+            .await
+            .expect("L2 messages not sent");
 
         // Query the core messaging contract to check that the l2 -> l1 message hash have been
         // registered. If the message is registered, calling `l2ToL1Messages` of the L1 core
@@ -2,6 +2,7 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use dojo_test_utils::sequencer::{get_default_test_starknet_config, TestSequencer};
 use dojo_utils::TransactionWaiter;
@@ -39,7 +40,7 @@ async fn fetch_traces_from_block() {
     let res = account.declare_v2(contract.clone(), compiled_class_hash).send().await.unwrap();
     // wait for the tx to be mined
     TransactionWaiter::new(res.transaction_hash, account.provider())
-        .with_interval(200)
+        .with_interval(Duration::from_millis(200))
         .await
         .expect("tx failed");
 
@@ -58,7 +59,7 @@ async fn fetch_traces_from_block() {
 
         // wait for the tx to be mined
         TransactionWaiter::new(res.transaction_hash, account.provider())
-            .with_interval(200)
+            .with_interval(Duration::from_millis(200))
             .await
             .expect("tx failed");
 
@@ -108,7 +109,7 @@ async fn fetch_traces_from_pending_block() {
     let res = account.declare_v2(contract.clone(), compiled_class_hash).send().await.unwrap();
     // wait for the tx to be mined
     TransactionWaiter::new(res.transaction_hash, account.provider())
-        .with_interval(200)
+        .with_interval(Duration::from_millis(200))
         .await
         .expect("tx failed");
 
@@ -129,7 +130,7 @@ async fn fetch_traces_from_pending_block() {
 
         // wait for the tx to be mined
         TransactionWaiter::new(res.transaction_hash, account.provider())
-            .with_interval(200)
+            .with_interval(Duration::from_millis(200))
             .await
             .expect("tx failed");
 
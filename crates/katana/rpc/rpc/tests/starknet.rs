@@ -91,6 +91,20 @@ async fn declare_and_deploy_contract() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn verify_compiled_class_hash() -> Result<()> {
+    let path: PathBuf = PathBuf::from("tests/test_data/cairo1_contract.json");
+    let (_, compiled_class_hash) = common::prepare_contract_declaration_params(&path)?;
+
+    common::verify_compiled_class_hash(&path, compiled_class_hash)?;
+
+    let err = common::verify_compiled_class_hash(&path, compiled_class_hash + Felt::ONE)
+        .expect_err("a mismatched compiled class hash should be rejected");
+    assert!(err.to_string().contains("compiled class hash mismatch"));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn declare_and_deploy_legacy_contract() -> Result<()> {
     let sequencer =
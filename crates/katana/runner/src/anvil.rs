@@ -0,0 +1,89 @@
+use alloy::network::EthereumWallet;
+use alloy::node_bindings::{Anvil, AnvilInstance};
+use alloy::primitives::Address;
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::Result;
+use url::Url;
+
+use crate::utils::find_free_port;
+
+/// Configuration for the [`AnvilRunner`].
+#[derive(Debug, Default, Clone)]
+pub struct AnvilRunnerConfig {
+    /// The port to run anvil on, if None, a random free port is chosen.
+    pub port: Option<u16>,
+    /// The RPC URL of the chain to fork, if None, anvil starts from an empty chain.
+    pub fork_url: Option<String>,
+    /// The block number to fork at, if None, the latest block of the forked chain.
+    pub fork_block_number: Option<u64>,
+    /// The mnemonic the accounts are derived from, if None, the default one of anvil.
+    pub mnemonic: Option<String>,
+    /// The number of prefunded accounts, if None, the default of anvil.
+    pub n_accounts: Option<u16>,
+}
+
+/// An anvil instance, to be used as the L1 of messaging tests.
+#[derive(Debug)]
+pub struct AnvilRunner {
+    instance: AnvilInstance,
+}
+
+impl AnvilRunner {
+    /// Creates a new AnvilRunner with default values.
+    pub fn new() -> Result<Self> {
+        Self::new_with_config(AnvilRunnerConfig::default())
+    }
+
+    /// Creates a new AnvilRunner with the given configuration.
+    pub fn new_with_config(config: AnvilRunnerConfig) -> Result<Self> {
+        let mut anvil = Anvil::new().port(config.port.unwrap_or_else(find_free_port));
+
+        if let Some(fork_url) = config.fork_url {
+            anvil = anvil.fork(fork_url);
+        }
+
+        if let Some(block_number) = config.fork_block_number {
+            anvil = anvil.fork_block_number(block_number);
+        }
+
+        if let Some(mnemonic) = config.mnemonic {
+            anvil = anvil.mnemonic(mnemonic);
+        }
+
+        if let Some(n_accounts) = config.n_accounts {
+            anvil = anvil.args(["--accounts".to_string(), n_accounts.to_string()]);
+        }
+
+        Ok(Self { instance: anvil.try_spawn()? })
+    }
+
+    pub fn endpoint(&self) -> String {
+        self.instance.endpoint()
+    }
+
+    pub fn url(&self) -> Url {
+        self.instance.endpoint_url()
+    }
+
+    /// The addresses of the prefunded accounts.
+    pub fn addresses(&self) -> &[Address] {
+        self.instance.addresses()
+    }
+
+    /// Returns the signer of the prefunded account at `index`.
+    pub fn signer(&self, index: usize) -> PrivateKeySigner {
+        self.instance.keys()[index].clone().into()
+    }
+
+    /// Returns a wallet signing with the prefunded account at `index`, to build a provider
+    /// sending transactions from it.
+    pub fn wallet(&self, index: usize) -> EthereumWallet {
+        EthereumWallet::from(self.signer(index))
+    }
+
+    /// Returns the chain id of anvil, which is the one of the forked chain when forking.
+    pub async fn chain_id(&self) -> Result<u64> {
+        Ok(ProviderBuilder::new().on_http(self.url()).get_chain_id().await?)
+    }
+}
@@ -1,16 +1,25 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod anvil;
 mod logs;
+mod messaging;
 mod prefunded;
 mod utils;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+pub use anvil::{AnvilRunner, AnvilRunnerConfig};
+use anyhow::{bail, Context, Result};
 use assert_fs::TempDir;
 use katana_node_bindings::{Katana, KatanaInstance};
+use katana_primitives::message::MessagingStatus;
+pub use messaging::{Messaging, MessagingConfig};
 pub use runner_macro::{katana_test, runner};
+use serde::de::DeserializeOwned;
+use serde_json::json;
 use starknet::core::types::Felt;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
@@ -24,6 +33,10 @@ pub struct KatanaRunner {
     provider: JsonRpcClient<HttpTransport>,
     log_file_path: PathBuf,
     contract: Mutex<Option<Felt>>,
+    /// The output of the process, if [`KatanaRunnerConfig::capture_output`] is set.
+    output: Option<Arc<std::sync::Mutex<String>>>,
+    /// The dir of the messaging config file, if written from a [`MessagingConfig`].
+    _messaging_dir: Option<TempDir>,
 }
 
 /// Configuration for the KatanaRunner.
@@ -43,12 +56,21 @@ pub struct KatanaRunnerConfig {
     pub port: Option<u16>,
     /// The path where to log info, if None, logs are stored in a temp dir.
     pub log_path: Option<PathBuf>,
-    /// The messaging config file
-    pub messaging: Option<String>,
-    /// The path to the database dir.
+    /// The messaging config, either a file or a [`MessagingConfig`].
+    pub messaging: Option<Messaging>,
+    /// The path to the database dir. If None, the chain is kept in memory.
+    ///
+    /// The dir is left untouched when the runner is dropped, so another runner can be started
+    /// from the state of the chain with the same `db_dir`.
     pub db_dir: Option<PathBuf>,
     /// Whether to run the katana runner with the `dev` rpc endpoints.
     pub dev: bool,
+    /// The path to the genesis file to boot from. If set, `n_accounts` is ignored since the
+    /// accounts are those of the genesis.
+    pub genesis: Option<PathBuf>,
+    /// Whether to keep the output of the process in memory, to be read with
+    /// [`KatanaRunner::logs`].
+    pub capture_output: bool,
 }
 
 impl Default for KatanaRunnerConfig {
@@ -64,17 +86,98 @@ impl Default for KatanaRunnerConfig {
             messaging: None,
             db_dir: None,
             dev: false,
+            genesis: None,
+            capture_output: false,
         }
     }
 }
 
 impl KatanaRunnerConfig {
+    /// Returns a builder for the config, with all the fields set to their default values.
+    pub fn builder() -> KatanaRunnerConfigBuilder {
+        KatanaRunnerConfigBuilder::default()
+    }
+
     pub fn with_db_dir(mut self, db_dir: &str) -> Self {
         self.db_dir = Some(PathBuf::from(db_dir));
         self
     }
 }
 
+/// Builder for [`KatanaRunnerConfig`].
+///
+/// Only the fields that are explicitly set differ from [`KatanaRunnerConfig::default`].
+#[derive(Debug, Default)]
+pub struct KatanaRunnerConfigBuilder {
+    config: KatanaRunnerConfig,
+}
+
+impl KatanaRunnerConfigBuilder {
+    pub fn program_name(mut self, program_name: impl Into<String>) -> Self {
+        self.config.program_name = Some(program_name.into());
+        self
+    }
+
+    pub fn run_name(mut self, run_name: impl Into<String>) -> Self {
+        self.config.run_name = Some(run_name.into());
+        self
+    }
+
+    pub fn n_accounts(mut self, n_accounts: u16) -> Self {
+        self.config.n_accounts = n_accounts;
+        self
+    }
+
+    pub fn disable_fee(mut self, disable_fee: bool) -> Self {
+        self.config.disable_fee = disable_fee;
+        self
+    }
+
+    pub fn block_time(mut self, block_time: u64) -> Self {
+        self.config.block_time = Some(block_time);
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.config.port = Some(port);
+        self
+    }
+
+    pub fn log_path(mut self, log_path: impl Into<PathBuf>) -> Self {
+        self.config.log_path = Some(log_path.into());
+        self
+    }
+
+    pub fn messaging(mut self, messaging: impl Into<Messaging>) -> Self {
+        self.config.messaging = Some(messaging.into());
+        self
+    }
+
+    pub fn db_dir(mut self, db_dir: impl Into<PathBuf>) -> Self {
+        self.config.db_dir = Some(db_dir.into());
+        self
+    }
+
+    pub fn dev(mut self, dev: bool) -> Self {
+        self.config.dev = dev;
+        self
+    }
+
+    pub fn genesis(mut self, genesis: impl Into<PathBuf>) -> Self {
+        self.config.genesis = Some(genesis.into());
+        self
+    }
+
+    pub fn capture_output(mut self, capture_output: bool) -> Self {
+        self.config.capture_output = capture_output;
+        self
+    }
+
+    pub fn build(self) -> KatanaRunnerConfig {
+        self.config
+    }
+}
+
 impl KatanaRunner {
     /// Creates a new KatanaRunner with default values.
     pub fn new() -> Result<Self> {
@@ -99,18 +202,26 @@ impl KatanaRunner {
         let mut builder = Katana::new()
             .path(program)
             .port(port)
-            .accounts(n_accounts)
             .json_log(true)
             .max_connections(10000)
             .dev(config.dev)
             .fee(!config.disable_fee);
 
+        // The accounts of a genesis file can't be combined with generated ones.
+        builder = match config.genesis {
+            Some(genesis) => builder.genesis(genesis),
+            None => builder.accounts(n_accounts),
+        };
+
         if let Some(block_time_ms) = config.block_time {
             builder = builder.block_time(block_time_ms);
         }
 
-        if let Some(messaging_file) = config.messaging {
+        let mut messaging_dir = None;
+        if let Some(messaging) = config.messaging {
+            let (messaging_file, dir) = messaging.into_file()?;
             builder = builder.messaging(messaging_file);
+            messaging_dir = dir;
         }
 
         if let Some(path) = config.db_dir {
@@ -136,21 +247,38 @@ impl KatanaRunner {
             log_dir.join(log_filename)
         };
 
+        let output = config.capture_output.then(Arc::default);
+
         let log_file_path_sent = log_file_path.clone();
+        let output_sent = output.clone();
         thread::spawn(move || {
-            utils::listen_to_stdout(&log_file_path_sent, stdout);
+            utils::listen_to_stdout(&log_file_path_sent, stdout, output_sent.as_deref());
         });
 
         let provider = JsonRpcClient::new(HttpTransport::new(katana.endpoint_url()));
         let contract = Mutex::new(Option::None);
 
-        Ok(KatanaRunner { instance: katana, provider, log_file_path, contract })
+        Ok(KatanaRunner {
+            instance: katana,
+            provider,
+            log_file_path,
+            contract,
+            output,
+            _messaging_dir: messaging_dir,
+        })
     }
 
     pub fn log_file_path(&self) -> &PathBuf {
         &self.log_file_path
     }
 
+    /// Returns the output of the process captured so far, one log line per line.
+    ///
+    /// Always empty if [`KatanaRunnerConfig::capture_output`] isn't set.
+    pub fn logs(&self) -> String {
+        self.output.as_ref().map(|output| output.lock().unwrap().clone()).unwrap_or_default()
+    }
+
     pub fn provider(&self) -> &JsonRpcClient<HttpTransport> {
         &self.provider
     }
@@ -178,6 +306,100 @@ impl KatanaRunner {
     pub async fn contract(&self) -> Option<Felt> {
         *self.contract.lock().await
     }
+
+    /// Returns the hashes of the transactions executed in the pending block but not yet mined.
+    ///
+    /// Requires the `dev` rpc endpoints. Always empty if no `block_time` is configured, since
+    /// blocks are then mined as soon as a transaction is received.
+    pub async fn pending_transactions(&self) -> Result<Vec<Felt>> {
+        self.dev_request("dev_pendingTransactions").await
+    }
+
+    /// Mines a new block with the pending transactions without waiting for the block time.
+    ///
+    /// Requires the `dev` rpc endpoints.
+    pub async fn generate_block(&self) -> Result<()> {
+        self.dev_request("dev_generateBlock").await
+    }
+
+    /// Returns the progress of the messaging service.
+    ///
+    /// Requires the `dev` rpc endpoints.
+    pub async fn messaging_status(&self) -> Result<MessagingStatus> {
+        self.dev_request("dev_messagingStatus").await
+    }
+
+    /// Waits until the messaging service has gathered the messages of the settlement chain up
+    /// to `l1_block` included.
+    ///
+    /// Requires the `dev` rpc endpoints.
+    pub async fn wait_for_messaging_synced(&self, l1_block: u64, timeout: Duration) -> Result<()> {
+        self.wait_for_messaging_status(timeout, |status| {
+            status.gathered_block.is_some_and(|block| block >= l1_block)
+        })
+        .await
+        .with_context(|| format!("Messages of settlement block {l1_block} not gathered"))
+    }
+
+    /// Waits until the messaging service has sent the messages of the local blocks up to
+    /// `block` included to the settlement chain.
+    ///
+    /// Requires the `dev` rpc endpoints.
+    pub async fn wait_for_messages_sent(&self, block: u64, timeout: Duration) -> Result<()> {
+        self.wait_for_messaging_status(timeout, |status| {
+            status.sent_block.is_some_and(|sent| sent >= block)
+        })
+        .await
+        .with_context(|| format!("Messages of block {block} not sent"))
+    }
+
+    /// Exports the latest state of the chain to a genesis file at `path`, which another
+    /// runner can boot from with [`KatanaRunnerConfig::genesis`].
+    ///
+    /// Requires the `dev` rpc endpoints.
+    pub async fn export_genesis(&self, path: impl AsRef<Path>) -> Result<()> {
+        let genesis: serde_json::Value = self.dev_request("dev_exportGenesis").await?;
+        std::fs::write(path, serde_json::to_vec_pretty(&genesis)?)?;
+        Ok(())
+    }
+
+    async fn wait_for_messaging_status<F>(&self, timeout: Duration, predicate: F) -> Result<()>
+    where
+        F: Fn(&MessagingStatus) -> bool,
+    {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let status = self.messaging_status().await?;
+            if predicate(&status) {
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                bail!("Timed out after {timeout:?}, messaging status: {status:?}");
+            }
+
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    async fn dev_request<T: DeserializeOwned>(&self, method: &str) -> Result<T> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] });
+
+        let response = reqwest::Client::new()
+            .post(self.url())
+            .json(&request)
+            .send()
+            .await?
+            .json::<serde_json::Value>()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            bail!("Request `{method}` failed: {error}");
+        }
+
+        Ok(serde_json::from_value(response["result"].clone())?)
+    }
 }
 
 /// Determines the default program path for the katana runner based on the KATANA_RUNNER_BIN
@@ -200,4 +422,46 @@ mod tests {
         std::env::remove_var("KATANA_RUNNER_BIN");
         assert_eq!(determine_default_program_path(), "katana");
     }
+
+    #[test]
+    fn config_builder_uses_defaults_for_unset_fields() {
+        let config = KatanaRunnerConfig::builder().n_accounts(10).build();
+        let default = KatanaRunnerConfig::default();
+
+        assert_eq!(config.n_accounts, 10);
+        assert_eq!(config.program_name, default.program_name);
+        assert_eq!(config.run_name, default.run_name);
+        assert_eq!(config.disable_fee, default.disable_fee);
+        assert_eq!(config.block_time, default.block_time);
+        assert_eq!(config.port, default.port);
+        assert_eq!(config.log_path, default.log_path);
+        assert_eq!(config.messaging, default.messaging);
+        assert_eq!(config.db_dir, default.db_dir);
+        assert_eq!(config.dev, default.dev);
+        assert_eq!(config.genesis, default.genesis);
+        assert_eq!(config.capture_output, default.capture_output);
+    }
+
+    #[test]
+    fn messaging_config_is_written_to_a_file() {
+        let config = MessagingConfig {
+            chain: "ethereum".to_string(),
+            rpc_url: "http://localhost:8545".to_string(),
+            interval: 2,
+            rpc_timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+
+        let (path, dir) = Messaging::from(config.clone()).into_file().unwrap();
+        let written: MessagingConfig =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written, config);
+
+        drop(dir);
+        assert!(!path.exists());
+
+        let (path, dir) = Messaging::from("messaging.json").into_file().unwrap();
+        assert_eq!(path, PathBuf::from("messaging.json"));
+        assert!(dir.is_none());
+    }
 }
@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use assert_fs::TempDir;
+pub use katana_core::service::messaging::MessagingConfig;
+
+/// The messaging of a runner, either a config file or a config written to a file when the
+/// runner starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Messaging {
+    File(PathBuf),
+    Config(MessagingConfig),
+}
+
+impl Messaging {
+    /// Returns the path of the config file to give to Katana.
+    ///
+    /// For [`Messaging::Config`] the file is written in a temp dir, which is deleted once the
+    /// returned [`TempDir`] is dropped.
+    pub(crate) fn into_file(self) -> Result<(PathBuf, Option<TempDir>)> {
+        match self {
+            Self::File(path) => Ok((path, None)),
+            Self::Config(config) => {
+                let dir = TempDir::new()?;
+                let path = dir.join("messaging.json");
+                std::fs::write(&path, serde_json::to_vec(&config)?)?;
+                Ok((path, Some(dir)))
+            }
+        }
+    }
+}
+
+impl From<PathBuf> for Messaging {
+    fn from(path: PathBuf) -> Self {
+        Self::File(path)
+    }
+}
+
+impl From<&str> for Messaging {
+    fn from(path: &str) -> Self {
+        Self::File(path.into())
+    }
+}
+
+impl From<String> for Messaging {
+    fn from(path: String) -> Self {
+        Self::File(path.into())
+    }
+}
+
+impl From<MessagingConfig> for Messaging {
+    fn from(config: MessagingConfig) -> Self {
+        Self::Config(config)
+    }
+}
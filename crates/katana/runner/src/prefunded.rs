@@ -1,5 +1,5 @@
 use starknet::accounts::{ExecutionEncoding, SingleOwnerAccount};
-use starknet::core::types::{BlockId, BlockTag};
+use starknet::core::types::{BlockId, BlockTag, Felt};
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
 use starknet::signers::LocalWallet;
@@ -19,6 +19,19 @@ impl KatanaRunner {
         &self.accounts_data()[index]
     }
 
+    /// Returns the address and the private key of the prefunded account at `index`, to sign
+    /// transactions for it with another client than [`KatanaRunner::account`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the account has no private key.
+    pub fn account_credentials(&self, index: usize) -> (Felt, Felt) {
+        let account = self.account_data(index);
+        let private_key =
+            account.private_key.as_ref().expect("Account does not have a private key");
+        (account.address, private_key.secret_scalar())
+    }
+
     pub fn account(
         &self,
         index: usize,
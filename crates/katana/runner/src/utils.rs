@@ -3,12 +3,14 @@ use std::io::{BufRead, BufReader, Write};
 use std::net::TcpListener;
 use std::path::Path;
 use std::process::ChildStdout;
+use std::sync::Mutex;
 
 pub fn find_free_port() -> u16 {
     TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port() // This might need to me mutexed
 }
 
-pub fn listen_to_stdout(log_file: &Path, stdout: ChildStdout) {
+/// Writes the lines of `stdout` to `log_file`, and appends them to `output` if given.
+pub fn listen_to_stdout(log_file: &Path, stdout: ChildStdout, output: Option<&Mutex<String>>) {
     let reader = BufReader::new(stdout);
 
     if let Some(dir_path) = log_file.parent() {
@@ -21,5 +23,11 @@ pub fn listen_to_stdout(log_file: &Path, stdout: ChildStdout) {
     for line in reader.lines() {
         let line = line.expect("failed to read line from subprocess stdout");
         writeln!(log_writer, "{}", line).expect("failed to write to log file");
+
+        if let Some(output) = output {
+            let mut output = output.lock().unwrap();
+            output.push_str(&line);
+            output.push('\n');
+        }
     }
 }
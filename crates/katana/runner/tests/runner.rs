@@ -1,5 +1,14 @@
+use std::time::{Duration, Instant};
+
+use assert_fs::TempDir;
 use katana_runner::*;
+use starknet::accounts::{Account, Call};
+use starknet::contract::ContractFactory;
+use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::core::utils::get_selector_from_name;
+use starknet::macros::felt;
 use starknet::providers::Provider;
+use starknet::signers::SigningKey;
 
 #[katana_test(2, false)]
 async fn test_run() {
@@ -21,3 +30,136 @@ async fn test_run() {
 async fn basic_macro_usage() {
     let _block_number = runner.provider().block_number().await.unwrap();
 }
+
+#[tokio::test]
+async fn pending_transactions_are_listed_until_mined() {
+    let runner = KatanaRunner::new_with_config(
+        KatanaRunnerConfig::builder().block_time(60_000).dev(true).build(),
+    )
+    .expect("failed to start katana");
+
+    let account = runner.account(0);
+    let fee_token = felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7");
+
+    let tx = account
+        .execute_v1(vec![Call {
+            to: fee_token,
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![runner.account_data(1).address, Felt::ONE, Felt::ZERO],
+        }])
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(runner.pending_transactions().await.unwrap(), vec![tx.transaction_hash]);
+
+    runner.generate_block().await.unwrap();
+
+    assert!(runner.pending_transactions().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn captured_output_contains_mined_block() {
+    let runner =
+        KatanaRunner::new_with_config(KatanaRunnerConfig::builder().capture_output(true).build())
+            .expect("failed to start katana");
+
+    let account = runner.account(0);
+    let fee_token = felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7");
+
+    account
+        .execute_v1(vec![Call {
+            to: fee_token,
+            selector: get_selector_from_name("transfer").unwrap(),
+            calldata: vec![runner.account_data(1).address, Felt::ONE, Felt::ZERO],
+        }])
+        .send()
+        .await
+        .unwrap();
+
+    // The output is read in the background, so the log line may come a bit after the block.
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !logs_block_mined(&runner.logs(), 1) {
+        assert!(Instant::now() < deadline, "block not logged, output:\n{}", runner.logs());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Returns whether the JSON logs of katana contain the mining of the block `number`.
+fn logs_block_mined(logs: &str, number: u64) -> bool {
+    logs.lines().filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok()).any(
+        |log| {
+            let fields = &log["fields"];
+            // The block number is recorded with its `Display` implementation.
+            fields["message"] == "Block mined." && fields["block_number"] == number.to_string()
+        },
+    )
+}
+
+#[tokio::test]
+async fn chain_is_restored_from_db_dir() {
+    let db_dir = TempDir::new().unwrap();
+    let config = || KatanaRunnerConfig::builder().db_dir(db_dir.path()).build();
+
+    let (address, class_hash) = {
+        let runner = KatanaRunner::new_with_config(config()).expect("failed to start katana");
+        let account = runner.account(0);
+
+        // Deploys another instance of the account class.
+        let class_hash = runner
+            .provider()
+            .get_class_hash_at(BlockId::Tag(BlockTag::Latest), account.address())
+            .await
+            .unwrap();
+        let deployment = ContractFactory::new(class_hash, &account).deploy_v1(
+            vec![felt!("0x1")],
+            felt!("0x2"),
+            false,
+        );
+        let tx = deployment.send().await.unwrap();
+
+        // The block must be mined, and thus written to the db, before the runner is stopped.
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while runner.provider().get_transaction_receipt(tx.transaction_hash).await.is_err() {
+            assert!(Instant::now() < deadline, "deployment not mined");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        (deployment.deployed_address(), class_hash)
+    };
+
+    let runner = KatanaRunner::new_with_config(config()).expect("failed to restart katana");
+
+    let restored_class_hash = runner
+        .provider()
+        .get_class_hash_at(BlockId::Tag(BlockTag::Latest), address)
+        .await
+        .expect("deployed contract should be restored from the db");
+    assert_eq!(restored_class_hash, class_hash);
+}
+
+#[tokio::test]
+async fn anvil_runner_uses_the_configured_accounts() {
+    let anvil = AnvilRunner::new_with_config(AnvilRunnerConfig {
+        n_accounts: Some(3),
+        ..Default::default()
+    })
+    .expect("failed to start anvil");
+
+    assert_eq!(anvil.addresses().len(), 3);
+    assert_eq!(anvil.signer(0).address(), anvil.addresses()[0]);
+    // The default chain id of anvil.
+    assert_eq!(anvil.chain_id().await.unwrap(), 31337);
+}
+
+#[tokio::test]
+async fn account_credentials_sign_for_the_account() {
+    let runner = KatanaRunner::new().expect("failed to start katana");
+
+    let (address, private_key) = runner.account_credentials(0);
+    let signing_key = SigningKey::from_secret_scalar(private_key);
+
+    assert_eq!(address, runner.account(0).address());
+    let expected_key = runner.account_data(0).private_key.as_ref().unwrap().verifying_key();
+    assert_eq!(signing_key.verifying_key().scalar(), expected_key.scalar());
+}
@@ -8,6 +8,7 @@ use postcard;
 
 use super::{Compress, Decompress};
 use crate::error::CodecError;
+use crate::migration::v1;
 use crate::models::block::StoredBlockBodyIndices;
 use crate::models::contract::ContractInfoChangeList;
 use crate::models::list::BlockList;
@@ -42,5 +43,7 @@ impl_compress_and_decompress_for_table_values!(
     BlockList,
     GenericContractInfo,
     StoredBlockBodyIndices,
-    ContractInfoChangeList
+    ContractInfoChangeList,
+    v1::Receipt,
+    v1::TxExecInfo
 );
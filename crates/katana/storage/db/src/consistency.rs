@@ -0,0 +1,215 @@
+//! Consistency checks between related tables of the database, and the maintenance operations
+//! that must keep them consistent.
+
+use std::collections::{HashMap, HashSet};
+
+use katana_primitives::block::BlockNumber;
+
+use crate::abstraction::{DbCursor, DbCursorMut, DbTx, DbTxMut};
+use crate::error::DatabaseError;
+use crate::mdbx::DbEnv;
+use crate::models::storage::ContractStorageKey;
+use crate::tables;
+
+/// A mismatch between the [`StorageChangeSet`](tables::StorageChangeSet) and
+/// [`StorageChangeHistory`](tables::StorageChangeHistory) tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// The block is in the block list of the storage key, but there is no history entry for
+    /// the key at that block.
+    MissingHistoryEntry { key: ContractStorageKey, block: BlockNumber },
+    /// There is a history entry for the storage key at the block, but the block is not in the
+    /// block list of the key.
+    MissingChangeSetEntry { key: ContractStorageKey, block: BlockNumber },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConsistencyError {
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+
+    #[error("found {} inconsistencies between the storage tables", .0.len())]
+    Inconsistent(Vec<Inconsistency>),
+}
+
+/// Cross-checks the storage change set against the storage change history.
+///
+/// Every block in the [`BlockList`](crate::models::list::BlockList) of a storage key must
+/// have a corresponding history entry, and every history entry must be listed in the block list
+/// of its key. All the orphans found in either table are reported.
+pub fn validate_storage_consistency(env: &DbEnv) -> Result<(), ConsistencyError> {
+    let tx = env.tx()?;
+
+    let mut history = HashSet::new();
+    let mut cursor = tx.cursor::<tables::StorageChangeHistory>()?;
+    for entry in cursor.walk(None)? {
+        let (block, entry) = entry?;
+        history.insert((entry.key, block));
+    }
+
+    let mut inconsistencies = Vec::new();
+    let mut cursor = tx.cursor::<tables::StorageChangeSet>()?;
+    for entry in cursor.walk(None)? {
+        let (key, blocks) = entry?;
+
+        for block in blocks.iter() {
+            if !history.remove(&(key.clone(), block)) {
+                inconsistencies
+                    .push(Inconsistency::MissingHistoryEntry { key: key.clone(), block });
+            }
+        }
+    }
+
+    // Whatever is left in the history isn't listed in any block list.
+    let mut orphans = history.into_iter().collect::<Vec<_>>();
+    orphans.sort_by_key(|(key, block)| (*block, key.contract_address, key.key));
+    inconsistencies.extend(
+        orphans.into_iter().map(|(key, block)| Inconsistency::MissingChangeSetEntry { key, block }),
+    );
+
+    tx.commit()?;
+
+    if inconsistencies.is_empty() {
+        Ok(())
+    } else {
+        Err(ConsistencyError::Inconsistent(inconsistencies))
+    }
+}
+
+/// Prunes the storage change history of all the blocks below `below`, returning the number of
+/// pruned entries.
+///
+/// The pruned blocks are removed from the [`BlockList`](crate::models::list::BlockList) of their
+/// storage keys in the same transaction, and the keys left without any change are removed from
+/// the change set, so both tables remain consistent.
+pub fn prune_history(env: &DbEnv, below: BlockNumber) -> Result<usize, DatabaseError> {
+    let tx = env.tx_mut()?;
+
+    let mut pruned: HashMap<ContractStorageKey, Vec<BlockNumber>> = HashMap::new();
+    let mut count = 0;
+
+    let mut cursor = tx.cursor_mut::<tables::StorageChangeHistory>()?;
+    let mut walker = cursor.walk(None)?;
+    while let Some(entry) = walker.next() {
+        let (block, entry) = entry?;
+        if block >= below {
+            break;
+        }
+
+        walker.delete_current()?;
+        pruned.entry(entry.key).or_default().push(block);
+        count += 1;
+    }
+
+    for (key, blocks) in pruned {
+        let Some(mut list) = tx.get::<tables::StorageChangeSet>(key.clone())? else { continue };
+        for block in blocks {
+            list.remove(block);
+        }
+
+        if list.is_empty() {
+            tx.delete::<tables::StorageChangeSet>(key, None)?;
+        } else {
+            tx.put::<tables::StorageChangeSet>(key, list)?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::contract::ContractAddress;
+    use starknet::macros::felt;
+
+    use super::*;
+    use crate::mdbx::test_utils::create_test_db;
+    use crate::mdbx::DbEnvKind;
+    use crate::models::list::BlockList;
+    use crate::models::storage::ContractStorageEntry;
+
+    fn storage_key(key: u8) -> ContractStorageKey {
+        ContractStorageKey { contract_address: ContractAddress(felt!("0x1337")), key: key.into() }
+    }
+
+    fn insert_change(env: &DbEnv, key: ContractStorageKey, block: BlockNumber) {
+        let tx = env.tx_mut().unwrap();
+
+        let mut list = tx.get::<tables::StorageChangeSet>(key.clone()).unwrap().unwrap_or_default();
+        list.insert(block);
+        tx.put::<tables::StorageChangeSet>(key.clone(), list).unwrap();
+
+        let entry = ContractStorageEntry { key, value: felt!("0x1") };
+        tx.put::<tables::StorageChangeHistory>(block, entry).unwrap();
+
+        tx.commit().unwrap();
+    }
+
+    #[test]
+    fn consistent_storage_tables() {
+        let env = create_test_db(DbEnvKind::RW);
+
+        insert_change(&env, storage_key(1), 1);
+        insert_change(&env, storage_key(1), 2);
+        insert_change(&env, storage_key(2), 2);
+
+        validate_storage_consistency(&env).unwrap();
+    }
+
+    #[test]
+    fn orphaned_entries_are_reported() {
+        let env = create_test_db(DbEnvKind::RW);
+
+        insert_change(&env, storage_key(1), 1);
+        insert_change(&env, storage_key(1), 2);
+
+        let tx = env.tx_mut().unwrap();
+        // a block listed in the change set without its history entry
+        tx.put::<tables::StorageChangeSet>(storage_key(2), BlockList::from([3])).unwrap();
+        // a history entry whose block isn't in the change set
+        let entry = ContractStorageEntry { key: storage_key(3), value: felt!("0x2") };
+        tx.put::<tables::StorageChangeHistory>(4, entry).unwrap();
+        tx.commit().unwrap();
+
+        let Err(ConsistencyError::Inconsistent(inconsistencies)) =
+            validate_storage_consistency(&env)
+        else {
+            panic!("expected inconsistencies to be reported");
+        };
+
+        assert_eq!(
+            inconsistencies,
+            vec![
+                Inconsistency::MissingHistoryEntry { key: storage_key(2), block: 3 },
+                Inconsistency::MissingChangeSetEntry { key: storage_key(3), block: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn pruning_history_updates_block_lists() {
+        let env = create_test_db(DbEnvKind::RW);
+
+        insert_change(&env, storage_key(1), 1);
+        insert_change(&env, storage_key(1), 2);
+        insert_change(&env, storage_key(1), 4);
+        insert_change(&env, storage_key(2), 2);
+        insert_change(&env, storage_key(2), 3);
+        // a key only changed in the pruned blocks
+        insert_change(&env, storage_key(3), 1);
+
+        let pruned = prune_history(&env, 3).unwrap();
+        assert_eq!(pruned, 4);
+
+        let tx = env.tx().unwrap();
+        let list = tx.get::<tables::StorageChangeSet>(storage_key(1)).unwrap().unwrap();
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![4]);
+        let list = tx.get::<tables::StorageChangeSet>(storage_key(2)).unwrap().unwrap();
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec![3]);
+        assert!(tx.get::<tables::StorageChangeSet>(storage_key(3)).unwrap().is_none());
+        tx.commit().unwrap();
+
+        validate_storage_consistency(&env).unwrap();
+    }
+}
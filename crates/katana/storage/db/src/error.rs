@@ -41,6 +41,9 @@ pub enum DatabaseError {
 
     #[error("failed to get db stats: {0}")]
     GetStats(libmdbx::Error),
+
+    #[error("no migration from db version {0}")]
+    UnsupportedMigration(u32),
 }
 
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
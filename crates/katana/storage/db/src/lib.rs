@@ -9,8 +9,10 @@ use anyhow::{anyhow, Context};
 
 pub mod abstraction;
 pub mod codecs;
+pub mod consistency;
 pub mod error;
 pub mod mdbx;
+mod migration;
 pub mod models;
 pub mod tables;
 pub mod utils;
@@ -18,12 +20,16 @@ pub mod version;
 
 use mdbx::{DbEnv, DbEnvKind};
 use utils::is_database_empty;
-use version::{check_db_version, create_db_version_file, DatabaseVersionError, CURRENT_DB_VERSION};
+use version::{
+    check_db_version, create_db_version_file, default_version_file_path, DatabaseVersionError,
+    CURRENT_DB_VERSION,
+};
 
 /// Initialize the database at the given path and returning a handle to the its
 /// environment.
 ///
-/// This will create the default tables, if necessary.
+/// This will create the default tables, if necessary. A database created by an older version is
+/// migrated to the current one.
 pub fn init_db<P: AsRef<Path>>(path: P) -> anyhow::Result<DbEnv> {
     if is_database_empty(path.as_ref()) {
         fs::create_dir_all(&path).with_context(|| {
@@ -43,6 +49,23 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> anyhow::Result<DbEnv> {
                     )
                 })?
             }
+            Err(DatabaseVersionError::MismatchVersion { found, .. })
+                if found < CURRENT_DB_VERSION =>
+            {
+                let env = open_db(&path)?;
+                env.create_tables()?;
+                migration::migrate_db(&env, found).with_context(|| {
+                    format!("Migrating database from version {found} to {CURRENT_DB_VERSION}")
+                })?;
+
+                // The version file is read-only, so it has to be replaced.
+                fs::remove_file(default_version_file_path(path.as_ref()))?;
+                create_db_version_file(&path, CURRENT_DB_VERSION).with_context(|| {
+                    format!("Updating database version file at path {}", path.as_ref().display())
+                })?;
+
+                return Ok(env);
+            }
             Err(err) => return Err(anyhow!(err)),
         }
     }
@@ -64,8 +87,17 @@ mod tests {
 
     use std::fs;
 
+    use katana_primitives::fee::TxFeeInfo;
+    use katana_primitives::receipt::Receipt;
+    use starknet::core::types::PriceUnit;
+
+    use crate::abstraction::{Database, DbTx, DbTxMut};
     use crate::init_db;
-    use crate::version::{default_version_file_path, get_db_version, CURRENT_DB_VERSION};
+    use crate::migration::v1;
+    use crate::tables::Receipts;
+    use crate::version::{
+        create_db_version_file, default_version_file_path, get_db_version, CURRENT_DB_VERSION,
+    };
 
     #[test]
     fn initialize_db_in_empty_dir() {
@@ -115,6 +147,65 @@ mod tests {
         assert!(err.to_string().contains("Database version mismatch"));
     }
 
+    #[test]
+    fn initialize_db_from_previous_version() {
+        let path = tempfile::tempdir().unwrap();
+        init_db(path.path()).unwrap();
+
+        fs::remove_file(default_version_file_path(path.path())).unwrap();
+        create_db_version_file(path.path(), 1).unwrap();
+
+        let env = init_db(path.path()).unwrap();
+        assert_eq!(get_db_version(path.path()).unwrap(), CURRENT_DB_VERSION);
+        assert_eq!(env.tx().unwrap().entries::<Receipts>().unwrap(), 0);
+    }
+
+    #[test]
+    fn initialize_db_migrates_the_content_of_a_previous_version() {
+        let path = tempfile::tempdir().unwrap();
+        let receipt = v1::L1HandlerTxReceipt {
+            fee: TxFeeInfo { gas_consumed: 1, gas_price: 2, overall_fee: 2, unit: PriceUnit::Wei },
+            events: Vec::new(),
+            message_hash: Default::default(),
+            messages_sent: Vec::new(),
+            revert_error: None,
+            execution_resources: Default::default(),
+        };
+
+        // Write a version 1 database, with the receipts encoded as in version 1.
+        {
+            let env = init_db(path.path()).unwrap();
+            let tx = env.tx_mut().unwrap();
+            tx.put::<v1::Receipts>(0, v1::Receipt::L1Handler(receipt)).unwrap();
+            tx.commit().unwrap();
+        }
+        fs::remove_file(default_version_file_path(path.path())).unwrap();
+        create_db_version_file(path.path(), 1).unwrap();
+
+        let env = init_db(path.path()).unwrap();
+        assert_eq!(get_db_version(path.path()).unwrap(), CURRENT_DB_VERSION);
+
+        let Some(Receipt::L1Handler(receipt)) = env.tx().unwrap().get::<Receipts>(0).unwrap()
+        else {
+            panic!("expected a migrated L1 handler receipt");
+        };
+        assert_eq!(receipt.paid_fee_on_l1, 0);
+        assert_eq!(receipt.fee.overall_fee, 2);
+    }
+
+    #[test]
+    fn initialize_db_without_migration_from_its_version() {
+        let path = tempfile::tempdir().unwrap();
+        init_db(path.path()).unwrap();
+
+        fs::remove_file(default_version_file_path(path.path())).unwrap();
+        create_db_version_file(path.path(), 0).unwrap();
+
+        let err = init_db(path.path()).unwrap_err();
+        assert!(format!("{err:#}").contains("no migration from db version 0"));
+        assert_eq!(get_db_version(path.path()).unwrap(), 0);
+    }
+
     #[test]
     fn initialize_db_with_missing_version_file() {
         let path = tempfile::tempdir().unwrap();
@@ -6,7 +6,7 @@ pub mod cursor;
 pub mod stats;
 pub mod tx;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 use dojo_metrics::metrics::gauge;
@@ -15,7 +15,7 @@ use libmdbx::{DatabaseFlags, EnvironmentFlags, Geometry, Mode, PageSize, SyncMod
 use metrics::{describe_gauge, Label};
 use tracing::error;
 
-use self::stats::{Stats, TableStat};
+use self::stats::{Stats, TableSize, TableStat};
 use self::tx::Tx;
 use crate::abstraction::Database;
 use crate::error::DatabaseError;
@@ -94,6 +94,13 @@ impl DbEnv {
         Ok(())
     }
 
+    /// Returns the number of entries and the approximate size of every table of the current
+    /// schema, keyed by table name.
+    pub fn table_sizes(&self) -> Result<BTreeMap<&'static str, TableSize>, DatabaseError> {
+        let stats = self.stats()?;
+        Ok(stats.table_stats().iter().map(|(name, stat)| (*name, TableSize::from(stat))).collect())
+    }
+
     fn with_metrics(self) -> Self {
         describe_gauge!("db.table_size", metrics::Unit::Bytes, "Total size of the table");
         describe_gauge!("db.table_pages", metrics::Unit::Count, "Number of pages in the table");
@@ -265,6 +272,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn db_table_sizes() {
+        let env = create_test_db(DbEnvKind::RW);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(1u64, Header::default()).expect(ERROR_PUT);
+        tx.put::<Headers>(2u64, Header::default()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let sizes = env.table_sizes().expect("Failed to retrieve table sizes");
+        assert_eq!(sizes.len(), NUM_TABLES);
+
+        let headers = sizes[Headers::NAME];
+        assert_eq!(headers.entries, 2);
+        assert!(headers.size > 0);
+
+        assert_eq!(sizes[BlockHashes::NAME].entries, 0);
+    }
+
     #[test]
     fn db_manual_put_get() {
         let env = create_test_db(DbEnvKind::RW);
@@ -57,6 +57,21 @@ impl TableStat {
     }
 }
 
+/// The number of entries of a table and the approximate size it takes on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableSize {
+    /// Number of data items.
+    pub entries: usize,
+    /// The total size (in bytes) of the pages used by the table.
+    pub size: usize,
+}
+
+impl From<&TableStat> for TableSize {
+    fn from(stat: &TableStat) -> Self {
+        Self { entries: stat.entries(), size: stat.total_size() }
+    }
+}
+
 /// Statistics for the entire MDBX environment.
 pub struct Stats {
     /// Statistics for individual tables in the environment
@@ -0,0 +1,260 @@
+//! Migrations of the database content between versions.
+
+use crate::abstraction::{Database, DbCursor, DbTx, DbTxMut};
+use crate::error::DatabaseError;
+use crate::mdbx::DbEnv;
+use crate::tables::{self, Table};
+use crate::version::CURRENT_DB_VERSION;
+
+/// Types as they were stored in version 1 of the database.
+pub(crate) mod v1 {
+    use alloy_primitives::B256;
+    use katana_primitives::fee::TxFeeInfo;
+    use katana_primitives::receipt::{
+        self, DeclareTxReceipt, DeployAccountTxReceipt, Event, InvokeTxReceipt, MessageToL1,
+    };
+    use katana_primitives::trace::{self, CallInfo, TxResources};
+    use katana_primitives::transaction::TxNumber;
+    use serde::{Deserialize, Serialize};
+
+    use crate::tables::{self, Table};
+
+    /// Receipt of a `L1Handler` transaction, before the fee paid on L1 was recorded.
+    #[derive(Debug, Serialize, Deserialize)]
+    pub(crate) struct L1HandlerTxReceipt {
+        pub(crate) fee: TxFeeInfo,
+        pub(crate) events: Vec<Event>,
+        pub(crate) message_hash: B256,
+        pub(crate) messages_sent: Vec<MessageToL1>,
+        pub(crate) revert_error: Option<String>,
+        pub(crate) execution_resources: TxResources,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub(crate) enum Receipt {
+        Invoke(InvokeTxReceipt),
+        Declare(DeclareTxReceipt),
+        L1Handler(L1HandlerTxReceipt),
+        DeployAccount(DeployAccountTxReceipt),
+    }
+
+    impl From<Receipt> for receipt::Receipt {
+        fn from(receipt: Receipt) -> Self {
+            match receipt {
+                Receipt::Invoke(receipt) => Self::Invoke(receipt),
+                Receipt::Declare(receipt) => Self::Declare(receipt),
+                Receipt::DeployAccount(receipt) => Self::DeployAccount(receipt),
+                Receipt::L1Handler(receipt) => Self::L1Handler(receipt::L1HandlerTxReceipt {
+                    fee: receipt.fee,
+                    events: receipt.events,
+                    message_hash: receipt.message_hash,
+                    // The fee paid on L1 wasn't recorded before.
+                    paid_fee_on_l1: 0,
+                    messages_sent: receipt.messages_sent,
+                    revert_error: receipt.revert_error,
+                    execution_resources: receipt.execution_resources,
+                }),
+            }
+        }
+    }
+
+    /// Execution info of a transaction, before the L1 gas usage and the fee unit were recorded.
+    #[derive(Debug, Serialize, Deserialize)]
+    pub(crate) struct TxExecInfo {
+        pub(crate) validate_call_info: Option<CallInfo>,
+        pub(crate) execute_call_info: Option<CallInfo>,
+        pub(crate) fee_transfer_call_info: Option<CallInfo>,
+        pub(crate) actual_fee: u128,
+        pub(crate) actual_resources: TxResources,
+        pub(crate) revert_error: Option<String>,
+    }
+
+    impl From<TxExecInfo> for trace::TxExecInfo {
+        fn from(info: TxExecInfo) -> Self {
+            Self {
+                validate_call_info: info.validate_call_info,
+                execute_call_info: info.execute_call_info,
+                fee_transfer_call_info: info.fee_transfer_call_info,
+                actual_fee: info.actual_fee,
+                actual_resources: info.actual_resources,
+                revert_error: info.revert_error,
+                // Neither the L1 gas usage nor the fee unit were recorded before.
+                l1_gas_consumed: 0,
+                l1_data_gas_consumed: 0,
+                fee_unit: None,
+            }
+        }
+    }
+
+    /// The [`tables::Receipts`] table with the values encoded as in version 1.
+    #[derive(Debug)]
+    pub(crate) struct Receipts;
+
+    impl Table for Receipts {
+        const NAME: &'static str = tables::Receipts::NAME;
+        type Key = TxNumber;
+        type Value = Receipt;
+    }
+
+    /// The [`tables::TxTraces`] table with the values encoded as in version 1.
+    #[derive(Debug)]
+    pub(crate) struct TxTraces;
+
+    impl Table for TxTraces {
+        const NAME: &'static str = tables::TxTraces::NAME;
+        type Key = TxNumber;
+        type Value = TxExecInfo;
+    }
+}
+
+/// Migrates the content of the database from the version `from` to [`CURRENT_DB_VERSION`], by
+/// running the migration from each intermediate version in sequence.
+///
+/// All the steps run in a single transaction, so the database is left untouched if any of them
+/// fails.
+pub(crate) fn migrate_db(env: &DbEnv, from: u32) -> Result<(), DatabaseError> {
+    let tx = env.tx_mut()?;
+
+    for version in from..CURRENT_DB_VERSION {
+        migrate_from(&tx, version)?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Migrates the content of the database from the version `version` to the next one.
+fn migrate_from(tx: &impl DbTxMut, version: u32) -> Result<(), DatabaseError> {
+    match version {
+        1 => migrate_from_v1_to_v2(tx),
+        _ => Err(DatabaseError::UnsupportedMigration(version)),
+    }
+}
+
+/// Re-encodes the receipts and the traces, which gained the fee paid on L1 and the L1 gas usage.
+fn migrate_from_v1_to_v2(tx: &impl DbTxMut) -> Result<(), DatabaseError> {
+    migrate_table::<v1::Receipts, tables::Receipts, _>(tx, Into::into)?;
+    migrate_table::<v1::TxTraces, tables::TxTraces, _>(tx, Into::into)
+}
+
+/// Re-encodes every value of the table `T` read with the legacy layout of `Old`.
+fn migrate_table<Old, T, F>(tx: &impl DbTxMut, convert: F) -> Result<(), DatabaseError>
+where
+    Old: Table<Key = T::Key>,
+    T: Table,
+    F: Fn(Old::Value) -> T::Value,
+{
+    let entries = {
+        let mut cursor = tx.cursor::<Old>()?;
+        cursor.walk(None)?.collect::<Result<Vec<_>, _>>()?
+    };
+
+    for (key, value) in entries {
+        tx.put::<T>(key, convert(value))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::fee::TxFeeInfo;
+    use katana_primitives::receipt::{DeclareTxReceipt, L1HandlerTxReceipt, MessageToL1, Receipt};
+    use katana_primitives::trace::{CallInfo, TxExecInfo};
+    use starknet::core::types::PriceUnit;
+    use starknet::macros::felt;
+
+    use super::{migrate_db, v1};
+    use crate::abstraction::{Database, DbTx, DbTxMut};
+    use crate::error::DatabaseError;
+    use crate::mdbx::test_utils::create_test_db;
+    use crate::mdbx::DbEnvKind;
+    use crate::tables;
+
+    #[test]
+    fn receipts_are_migrated_from_v1() {
+        let env = create_test_db(DbEnvKind::RW);
+
+        let fee =
+            TxFeeInfo { gas_consumed: 10, gas_price: 2, overall_fee: 20, unit: PriceUnit::Wei };
+        let message = MessageToL1 {
+            from_address: ContractAddress(felt!("0x1")),
+            to_address: felt!("0x2"),
+            payload: vec![felt!("0x3")],
+        };
+
+        let l1_handler = v1::L1HandlerTxReceipt {
+            fee: fee.clone(),
+            events: Vec::new(),
+            message_hash: B256::repeat_byte(1),
+            messages_sent: vec![message.clone()],
+            revert_error: None,
+            execution_resources: Default::default(),
+        };
+        let declare = DeclareTxReceipt {
+            fee: fee.clone(),
+            events: Vec::new(),
+            messages_sent: Vec::new(),
+            revert_error: Some("reverted".to_string()),
+            execution_resources: Default::default(),
+        };
+
+        let tx = env.tx_mut().unwrap();
+        tx.put::<v1::Receipts>(0, v1::Receipt::L1Handler(l1_handler)).unwrap();
+        tx.put::<v1::Receipts>(1, v1::Receipt::Declare(declare.clone())).unwrap();
+        tx.commit().unwrap();
+
+        migrate_db(&env, 1).unwrap();
+
+        let tx = env.tx().unwrap();
+        let expected = L1HandlerTxReceipt {
+            fee,
+            events: Vec::new(),
+            message_hash: B256::repeat_byte(1),
+            paid_fee_on_l1: 0,
+            messages_sent: vec![message],
+            revert_error: None,
+            execution_resources: Default::default(),
+        };
+        assert_eq!(tx.get::<tables::Receipts>(0).unwrap(), Some(Receipt::L1Handler(expected)));
+        assert_eq!(tx.get::<tables::Receipts>(1).unwrap(), Some(Receipt::Declare(declare)));
+    }
+
+    #[test]
+    fn traces_are_migrated_from_v1() {
+        let env = create_test_db(DbEnvKind::RW);
+
+        let call =
+            CallInfo { contract_address: ContractAddress(felt!("0x1")), ..Default::default() };
+        let info = v1::TxExecInfo {
+            validate_call_info: None,
+            execute_call_info: Some(call.clone()),
+            fee_transfer_call_info: None,
+            actual_fee: 10,
+            actual_resources: Default::default(),
+            revert_error: Some("reverted".to_string()),
+        };
+
+        let tx = env.tx_mut().unwrap();
+        tx.put::<v1::TxTraces>(0, info).unwrap();
+        tx.commit().unwrap();
+
+        migrate_db(&env, 1).unwrap();
+
+        let expected = TxExecInfo {
+            execute_call_info: Some(call),
+            actual_fee: 10,
+            revert_error: Some("reverted".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(env.tx().unwrap().get::<tables::TxTraces>(0).unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn versions_without_migration_are_rejected() {
+        let env = create_test_db(DbEnvKind::RW);
+        assert_eq!(migrate_db(&env, 0), Err(DatabaseError::UnsupportedMigration(0)));
+    }
+}
@@ -0,0 +1,150 @@
+//! [`Arbitrary`] implementations of the database models, to generate random test vectors.
+
+use katana_primitives::block::BlockNumber;
+use katana_primitives::FieldElement;
+use proptest::prelude::*;
+
+use super::contract::{ContractClassChange, ContractNonceChange};
+use super::storage::{ContractStorageEntry, ContractStorageKey};
+use crate::abstraction::{Database, DbCursor, DbTx, DbTxMut};
+use crate::codecs::{Compress, Decode, Decompress, Encode};
+use crate::mdbx::test_utils::create_test_db;
+use crate::mdbx::DbEnvKind;
+use crate::tables::{ClassChangeHistory, NonceChangeHistory, StorageChangeHistory};
+
+fn felt() -> impl Strategy<Value = FieldElement> {
+    any::<[u8; 32]>().prop_map(|bytes| FieldElement::from_bytes_be(&bytes))
+}
+
+impl Arbitrary for ContractStorageKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (felt(), felt())
+            .prop_map(|(address, key)| Self { contract_address: address.into(), key })
+            .boxed()
+    }
+}
+
+impl Arbitrary for ContractStorageEntry {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<ContractStorageKey>(), felt()).prop_map(|(key, value)| Self { key, value }).boxed()
+    }
+}
+
+impl Arbitrary for ContractNonceChange {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (felt(), felt())
+            .prop_map(|(address, nonce)| Self { contract_address: address.into(), nonce })
+            .boxed()
+    }
+}
+
+impl Arbitrary for ContractClassChange {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (felt(), felt())
+            .prop_map(|(address, class_hash)| Self { contract_address: address.into(), class_hash })
+            .boxed()
+    }
+}
+
+/// Removes the duplicated entries of `entries`, which a dupsort table stores only once.
+fn dedup<T: PartialEq>(entries: Vec<T>) -> Vec<T> {
+    let mut unique = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if !unique.contains(&entry) {
+            unique.push(entry);
+        }
+    }
+    unique
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn models_survive_codec_roundtrip(
+        key in any::<ContractStorageKey>(),
+        entry in any::<ContractStorageEntry>(),
+        nonce_change in any::<ContractNonceChange>(),
+        class_change in any::<ContractClassChange>(),
+    ) {
+        let encoded = key.clone().encode();
+        prop_assert_eq!(ContractStorageKey::decode(encoded).unwrap(), key);
+
+        let compressed = entry.clone().compress();
+        prop_assert_eq!(ContractStorageEntry::decompress(compressed).unwrap(), entry);
+
+        let compressed = nonce_change.clone().compress();
+        prop_assert_eq!(ContractNonceChange::decompress(compressed).unwrap(), nonce_change);
+
+        let compressed = class_change.clone().compress();
+        prop_assert_eq!(ContractClassChange::decompress(compressed).unwrap(), class_change);
+    }
+
+    #[test]
+    fn history_entries_survive_in_db(
+        storage in prop::collection::vec((0u64..8, any::<ContractStorageEntry>()), 1..32),
+        nonces in prop::collection::vec((0u64..8, any::<ContractNonceChange>()), 1..32),
+        classes in prop::collection::vec((0u64..8, any::<ContractClassChange>()), 1..32),
+    ) {
+        let env = create_test_db(DbEnvKind::RW);
+
+        let tx = env.tx_mut().unwrap();
+        for (block, entry) in &storage {
+            tx.put::<StorageChangeHistory>(*block, entry.clone()).unwrap();
+        }
+        for (block, change) in &nonces {
+            tx.put::<NonceChangeHistory>(*block, change.clone()).unwrap();
+        }
+        for (block, change) in &classes {
+            tx.put::<ClassChangeHistory>(*block, change.clone()).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = env.tx().unwrap();
+
+        let stored = tx
+            .cursor::<StorageChangeHistory>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<(BlockNumber, _)>, _>>()
+            .unwrap();
+        let storage = dedup(storage);
+        prop_assert_eq!(stored.len(), storage.len());
+        prop_assert!(storage.iter().all(|entry| stored.contains(entry)));
+
+        let stored = tx
+            .cursor::<NonceChangeHistory>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<(BlockNumber, _)>, _>>()
+            .unwrap();
+        let nonces = dedup(nonces);
+        prop_assert_eq!(stored.len(), nonces.len());
+        prop_assert!(nonces.iter().all(|change| stored.contains(change)));
+
+        let stored = tx
+            .cursor::<ClassChangeHistory>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<Vec<(BlockNumber, _)>, _>>()
+            .unwrap();
+        let classes = dedup(classes);
+        prop_assert_eq!(stored.len(), classes.len());
+        prop_assert!(classes.iter().all(|change| stored.contains(change)));
+    }
+}
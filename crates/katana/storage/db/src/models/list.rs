@@ -24,6 +24,16 @@ impl IntegerSet {
         self.0.insert(num);
     }
 
+    /// Removes a number from the set, returning whether it was present.
+    pub fn remove(&mut self, num: u64) -> bool {
+        self.0.remove(num)
+    }
+
+    /// Returns `true` if the set contains no numbers.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     /// Checks if the set contains the given number.
     pub fn contains(&self, num: u64) -> bool {
         self.0.contains(num)
@@ -38,6 +48,11 @@ impl IntegerSet {
     pub fn select(&self, n: u64) -> Option<u64> {
         self.0.select(n)
     }
+
+    /// Iterates over the numbers of the set in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.0.iter()
+    }
 }
 
 impl<const N: usize> From<[u64; N]> for IntegerSet {
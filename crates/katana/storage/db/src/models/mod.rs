@@ -1,3 +1,5 @@
+#[cfg(test)]
+mod arbitrary;
 pub mod block;
 pub mod class;
 pub mod contract;
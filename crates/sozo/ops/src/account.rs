@@ -1,6 +1,7 @@
 use core::panic;
 use std::io::Write;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use colored::Colorize;
@@ -277,7 +278,17 @@ pub async fn deploy(
             };
 
             let account_deployment = account_deployment.max_fee(max_fee.max_fee());
-            let txn_config = TxnConfig { fee_estimate_multiplier, wait, receipt, max_fee_raw };
+            let txn_config = TxnConfig {
+                fee_estimate_multiplier,
+                fee_estimate_pad: None,
+                wait,
+                receipt,
+                max_fee_raw,
+                report_dir: None,
+                max_concurrent_declarations: None,
+                max_retries: None,
+                retry_backoff_ms: None,
+            };
             do_account_deploy(
                 max_fee,
                 txn_config,
@@ -374,7 +385,7 @@ async fn do_account_deploy(
     );
     let receipt = TransactionWaiter::new(account_deployment_tx, &provider)
         .with_tx_status(TransactionFinalityStatus::AcceptedOnL2)
-        .with_interval(poll_interval)
+        .with_interval(Duration::from_millis(poll_interval))
         .await?;
 
     eprintln!(
@@ -1,11 +1,47 @@
-use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use cainome::cairo_serde::ContractAddress;
 use dojo_utils::TxnConfig;
-use dojo_world::contracts::WorldContract;
+use dojo_world::contracts::{WorldContract, WorldContractReader};
+use dojo_world::migration::world::WorldDiff;
+use dojo_world::migration::MigrationError;
 use scarb::core::Workspace;
-use starknet::accounts::ConnectedAccount;
+use scarb_ui::Ui;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{BlockId, BlockTag, Felt};
+
+use crate::auth::{
+    get_resource_selector, grant_owner, grant_writer, revoke_writer, ResourceOwner, ResourceType,
+    ResourceWriter,
+};
+use crate::migration::ui::MigrationUi;
+use crate::utils;
 
-use crate::auth::{grant_writer, revoke_writer, ResourceWriter};
+/// Outcome of [`auto_authorize`] for the writer grants.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AutoAuthOutput {
+    /// Number of writer permissions granted by this run.
+    pub granted: usize,
+    /// Number of writer permissions skipped because they were already granted on-chain.
+    pub already_granted: usize,
+}
 
+/// Grants and revokes the given permissions on the world.
+///
+/// Fails with [`MigrationError::NotWorldOwner`] before sending anything if the account isn't the
+/// owner of the world, since every grant would revert.
+#[tracing::instrument(
+    name = "authorize",
+    skip_all,
+    fields(
+        world_address = %format!("{:#x}", world.address),
+        grants = grant.len(),
+        revokes = revoke.len(),
+        owners = owners.len()
+    )
+)]
 pub async fn auto_authorize<A>(
     ws: &Workspace<'_>,
     world: &WorldContract<A>,
@@ -13,15 +49,193 @@ pub async fn auto_authorize<A>(
     default_namespace: &str,
     grant: &[ResourceWriter],
     revoke: &[ResourceWriter],
+    owners: &[ResourceOwner],
+) -> Result<AutoAuthOutput>
+where
+    A: ConnectedAccount + Sync + Send + 'static,
+    A::SignError: 'static,
+{
+    let ui = ws.config().ui();
+
+    if grant.is_empty() && revoke.is_empty() && owners.is_empty() {
+        return Ok(AutoAuthOutput::default());
+    }
+
+    ensure_world_owner(world).await?;
+
+    let missing = missing_grants(&ui, world, grant, default_namespace).await?;
+    let output =
+        AutoAuthOutput { granted: missing.len(), already_granted: grant.len() - missing.len() };
+
+    if output.already_granted > 0 {
+        ui.print_sub(format!(
+            "Skipping {} write permission(s) already granted",
+            output.already_granted
+        ));
+    }
+
+    grant_writer(&ui, world, &missing, txn_config.clone(), default_namespace).await?;
+    revoke_writer(&ui, world, revoke, txn_config.clone(), default_namespace).await?;
+
+    if !owners.is_empty() {
+        grant_owner(&ui, world, owners, txn_config.clone(), default_namespace).await?;
+    }
+
+    // The grants can only be checked once their transactions are accepted.
+    if txn_config.wait {
+        verify_grants(&ui, world, &missing, owners, default_namespace).await?;
+    }
+
+    Ok(output)
+}
+
+/// Ensures every writer of `writers` and every owner of `owners` has been granted its
+/// permission on-chain.
+async fn verify_grants<A>(
+    ui: &Ui,
+    world: &WorldContract<A>,
+    writers: &[ResourceWriter],
+    owners: &[ResourceOwner],
+    default_namespace: &str,
 ) -> Result<()>
+where
+    A: ConnectedAccount + Sync + Send + 'static,
+    A::SignError: 'static,
+{
+    for writer in writers {
+        let selector =
+            get_resource_selector(ui, world, &writer.resource, default_namespace).await?;
+        let contract_address = utils::get_contract_address(world, &writer.tag_or_address).await?;
+
+        if !world.is_writer(&selector, &ContractAddress(contract_address)).call().await? {
+            bail!(
+                "Write access to {:?} wasn't granted to {}.",
+                writer.resource,
+                writer.tag_or_address
+            );
+        }
+    }
+
+    for owner in owners {
+        let selector = get_resource_selector(ui, world, &owner.resource, default_namespace).await?;
+
+        if !world.is_owner(&selector, &ContractAddress(owner.owner)).call().await? {
+            bail!("Ownership of {:?} wasn't granted to {:#x}.", owner.resource, owner.owner);
+        }
+    }
+
+    Ok(())
+}
+
+/// Revokes the writer permissions held on-chain by the contracts of the world which are no
+/// longer declared in the `writes` of the local manifest, and returns the revoked permissions.
+///
+/// Only the contracts registered in the world are considered, including the ones removed from
+/// the manifest, which lose all their permissions. The permissions granted to other addresses are
+/// left untouched.
+#[tracing::instrument(
+    name = "revoke",
+    skip_all,
+    fields(world_address = %format!("{:#x}", world.address))
+)]
+pub async fn auto_revoke<A>(
+    ws: &Workspace<'_>,
+    world: &WorldContract<A>,
+    txn_config: &TxnConfig,
+    default_namespace: &str,
+    diff: &WorldDiff,
+) -> Result<Vec<ResourceWriter>>
 where
     A: ConnectedAccount + Sync + Send + 'static,
     A::SignError: 'static,
 {
     let ui = ws.config().ui();
 
-    grant_writer(&ui, world, grant, *txn_config, default_namespace).await?;
-    revoke_writer(&ui, world, revoke, *txn_config, default_namespace).await?;
+    let mut declared = HashSet::new();
+    for c in &diff.contracts {
+        let contract_address = utils::get_contract_address(world, &c.tag).await?;
+
+        for write in &c.local_writes {
+            let write = if write.contains(':') { write.to_string() } else { format!("m:{write}") };
+            let resource = ResourceType::from_str(&write)?;
+            let selector = get_resource_selector(&ui, world, &resource, default_namespace)
+                .await
+                .with_context(|| format!("Failed to get selector for {write}"))?;
+
+            declared.insert((selector, contract_address));
+        }
+    }
+
+    let world_reader = WorldContractReader::new(world.address, world.account.provider())
+        .with_block(BlockId::Tag(BlockTag::Pending));
+
+    let contracts = world_reader
+        .registered_contracts()
+        .await?
+        .into_iter()
+        .map(|c| (c.address, c.tag))
+        .collect::<HashMap<_, _>>();
+
+    let mut revoke = vec![];
+
+    for (resource, ContractAddress(contract_address)) in world_reader.writers().await? {
+        let Some(tag) = contracts.get(&contract_address) else { continue };
+
+        if !declared.contains(&(resource, contract_address)) {
+            ui.print_sub(format!("Revoking write access to {tag} for resource {resource:#x}"));
+            revoke.push(ResourceWriter {
+                resource: ResourceType::Selector(resource),
+                tag_or_address: format!("{contract_address:#x}"),
+            });
+        }
+    }
+
+    if !revoke.is_empty() {
+        ensure_world_owner(world).await?;
+        revoke_writer(&ui, world, &revoke, txn_config.clone(), default_namespace).await?;
+    }
+
+    Ok(revoke)
+}
+
+/// Ensures the account of `world` owns the world (the resource with selector `0`).
+async fn ensure_world_owner<A>(world: &WorldContract<A>) -> Result<()>
+where
+    A: ConnectedAccount + Sync + Send + 'static,
+    A::SignError: 'static,
+{
+    let account = world.account.address();
+
+    if !world.is_owner(&Felt::ZERO, &ContractAddress(account)).call().await? {
+        return Err(MigrationError::<A::SignError>::NotWorldOwner { account }.into());
+    }
 
     Ok(())
 }
+
+/// Returns the writers of `grant` which don't have write access to their resource yet.
+async fn missing_grants<A>(
+    ui: &Ui,
+    world: &WorldContract<A>,
+    grant: &[ResourceWriter],
+    default_namespace: &str,
+) -> Result<Vec<ResourceWriter>>
+where
+    A: ConnectedAccount + Sync + Send + 'static,
+    A::SignError: 'static,
+{
+    let mut missing = vec![];
+
+    for writer in grant {
+        let selector = get_resource_selector(ui, world, &writer.resource, default_namespace)
+            .await
+            .with_context(|| format!("Failed to get selector for {:?}", writer.resource))?;
+        let contract_address = utils::get_contract_address(world, &writer.tag_or_address).await?;
+
+        if !world.is_writer(&selector, &ContractAddress(contract_address)).call().await? {
+            missing.push(writer.clone());
+        }
+    }
+
+    Ok(missing)
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use camino::Utf8PathBuf;
+use dojo_utils::TxnConfig;
+use dojo_world::contracts::abi::world::Resource;
+use dojo_world::contracts::naming::compute_selector_from_tag;
+use dojo_world::contracts::WorldContractReader;
+use dojo_world::migration::class::ClassMigration;
+use dojo_world::migration::contract::ContractMigration;
+use dojo_world::migration::strategy::MigrationStrategy;
+use scarb::core::Workspace;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{BlockId, BlockTag, Felt, StarknetError};
+use starknet::providers::{Provider, ProviderError};
+
+use super::utils::write_atomically;
+
+/// Name of the file, in the target directory of the profile, holding the progress of the last
+/// migration.
+pub const MIGRATION_STATE_FILE: &str = "migration_state.json";
+
+/// The progress of a migration which has been cancelled before completion.
+///
+/// Applying [`MigrationCheckpoint::remaining`] to the original strategy gives the strategy
+/// that resumes the migration where it stopped.
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationCheckpoint {
+    #[serde_as(as = "UfeHex")]
+    pub world_address: Felt,
+    /// Whether the base class has been declared.
+    pub base_declared: bool,
+    /// Whether the world has been deployed or upgraded.
+    pub world_migrated: bool,
+    /// Tags of the registered models.
+    pub models: Vec<String>,
+    /// Selectors of the registered models, in the order of `models`.
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub model_selectors: Vec<Felt>,
+    /// Tags of the deployed or upgraded contracts.
+    pub contracts: Vec<String>,
+    /// Addresses of the deployed or upgraded contracts, in the order of `contracts`.
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub contract_addresses: Vec<Felt>,
+}
+
+impl MigrationCheckpoint {
+    /// Loads the checkpoint at `path`, `None` is returned if the file doesn't exist.
+    pub fn load(path: &Utf8PathBuf) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read migration state at {path}"))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Invalid migration state at {path}"))
+    }
+
+    pub fn save(&self, path: &Utf8PathBuf) -> Result<()> {
+        write_atomically(path, &serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write migration state at {path}"))
+    }
+
+    /// Records the registration of `models`.
+    pub(crate) fn record_models(&mut self, models: &[ClassMigration]) {
+        for m in models {
+            if !self.models.contains(&m.diff.tag) {
+                self.models.push(m.diff.tag.clone());
+                self.model_selectors.push(compute_selector_from_tag(&m.diff.tag));
+            }
+        }
+    }
+
+    /// Records the deployment or upgrade of `contracts`.
+    pub(crate) fn record_contracts(&mut self, contracts: &[ContractMigration]) {
+        for c in contracts {
+            if !self.contracts.contains(&c.diff.tag) {
+                self.contracts.push(c.diff.tag.clone());
+                self.contract_addresses.push(c.contract_address);
+            }
+        }
+    }
+
+    /// Returns the checkpoint without the steps which can't be found on-chain, so that they are
+    /// migrated again.
+    ///
+    /// If the world doesn't exist anymore, like after a restart of the chain, nothing is kept.
+    pub async fn confirmed<P>(self, world: &WorldContractReader<P>) -> Result<Self>
+    where
+        P: Provider + Sync + Send,
+    {
+        let world_address = self.world_address;
+        let provider = world.provider();
+
+        match provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), world_address).await {
+            Ok(_) => {}
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
+                return Ok(Self { world_address, ..Default::default() });
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        let mut confirmed = Self {
+            world_address,
+            base_declared: self.base_declared,
+            world_migrated: self.world_migrated,
+            ..Default::default()
+        };
+
+        for (tag, selector) in self.models.into_iter().zip(self.model_selectors) {
+            if !matches!(world.resource(&selector).call().await?, Resource::Unregistered) {
+                confirmed.models.push(tag);
+                confirmed.model_selectors.push(selector);
+            }
+        }
+
+        for (tag, address) in self.contracts.into_iter().zip(self.contract_addresses) {
+            match provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), address).await {
+                Ok(_) => {
+                    confirmed.contracts.push(tag);
+                    confirmed.contract_addresses.push(address);
+                }
+                Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(confirmed)
+    }
+
+    /// Returns `strategy` without the items already migrated.
+    pub fn remaining(&self, strategy: &MigrationStrategy) -> MigrationStrategy {
+        let mut strategy = strategy.clone();
+
+        if self.base_declared {
+            strategy.base = None;
+        }
+
+        if self.world_migrated {
+            strategy.world = None;
+        }
+
+        strategy.models.retain(|m| !self.models.contains(&m.diff.tag));
+        strategy.contracts.retain(|c| !self.contracts.contains(&c.diff.tag));
+
+        strategy
+    }
+}
+
+/// Returns the path of the migration state of the current profile, written to the report
+/// directory of `txn_config` if any.
+pub fn migration_state_path(ws: &Workspace<'_>, txn_config: &TxnConfig) -> Result<Utf8PathBuf> {
+    let dir = match &txn_config.report_dir {
+        Some(dir) => Utf8PathBuf::try_from(dir.clone())?,
+        None => ws.target_dir().path_existent()?.join(ws.config().profile().as_str()),
+    };
+
+    Ok(dir.join(MIGRATION_STATE_FILE))
+}
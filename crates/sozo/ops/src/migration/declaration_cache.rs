@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use camino::Utf8PathBuf;
+use dojo_world::migration::DeclareOutput;
+use scarb::core::Workspace;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{BlockId, BlockTag, Felt, StarknetError};
+use starknet::providers::{Provider, ProviderError};
+
+use super::utils::write_atomically;
+
+/// Name of the file, in the target directory of the profile, caching the declared classes.
+pub const DECLARATION_CACHE_FILE: &str = "declared_classes.json";
+
+/// A class declared by a previous migration.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclaredClass {
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub chain_id: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: Felt,
+}
+
+/// Local record of the classes declared by the migrations, keyed by class hash and chain id.
+///
+/// An entry is only a hint: a chain may have been restarted since, so the presence of a class is
+/// always confirmed on-chain with [`DeclarationCache::confirmed`] before skipping its declaration.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeclarationCache {
+    pub classes: Vec<DeclaredClass>,
+}
+
+impl DeclarationCache {
+    /// Loads the cache at `path`, an empty cache is returned if the file doesn't exist.
+    pub fn load(path: &Utf8PathBuf) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read declaration cache at {path}"))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid declaration cache at {path}"))
+    }
+
+    pub fn save(&self, path: &Utf8PathBuf) -> Result<()> {
+        write_atomically(path, &serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write declaration cache at {path}"))
+    }
+
+    pub fn get(&self, chain_id: Felt, class_hash: Felt) -> Option<&DeclaredClass> {
+        self.classes.iter().find(|c| c.chain_id == chain_id && c.class_hash == class_hash)
+    }
+
+    /// Records the declaration of `class_hash` on `chain_id`, replacing any previous entry.
+    pub fn record(&mut self, chain_id: Felt, class_hash: Felt, transaction_hash: Felt) {
+        self.classes.retain(|c| c.chain_id != chain_id || c.class_hash != class_hash);
+        self.classes.push(DeclaredClass { class_hash, chain_id, transaction_hash });
+    }
+
+    /// Returns the classes of `class_hashes` which are cached for `chain_id` and are still
+    /// declared on the chain.
+    pub async fn confirmed<P>(
+        &self,
+        provider: &P,
+        chain_id: Felt,
+        class_hashes: impl IntoIterator<Item = Felt>,
+    ) -> Result<HashSet<Felt>>
+    where
+        P: Provider + Sync,
+    {
+        let mut confirmed = HashSet::new();
+
+        for class_hash in class_hashes {
+            if self.get(chain_id, class_hash).is_none() || confirmed.contains(&class_hash) {
+                continue;
+            }
+
+            match provider.get_class(BlockId::Tag(BlockTag::Pending), class_hash).await {
+                Ok(_) => {
+                    confirmed.insert(class_hash);
+                }
+                Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(confirmed)
+    }
+}
+
+/// Classes known to be declared on the chain being migrated.
+#[derive(Debug)]
+pub(crate) struct DeclaredClasses {
+    pub(crate) chain_id: Felt,
+    pub(crate) cache: DeclarationCache,
+    /// Cached classes confirmed on-chain, their declaration is skipped.
+    pub(crate) confirmed: HashSet<Felt>,
+}
+
+impl DeclaredClasses {
+    pub(crate) fn contains(&self, class_hash: Felt) -> bool {
+        self.confirmed.contains(&class_hash)
+    }
+
+    pub(crate) fn record(&mut self, output: &DeclareOutput) {
+        self.cache.record(self.chain_id, output.class_hash, output.transaction_hash);
+    }
+}
+
+/// Returns the path of the declaration cache of the current profile.
+pub fn declaration_cache_path(ws: &Workspace<'_>) -> Result<Utf8PathBuf> {
+    let target_dir = ws.target_dir().path_existent()?;
+    Ok(target_dir.join(ws.config().profile().as_str()).join(DECLARATION_CACHE_FILE))
+}
@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use dojo_world::contracts::WorldContract;
+use dojo_world::migration::strategy::MigrationStrategy;
+use dojo_world::migration::{Declarable, Deployable, MigrationError, UDC_ADDRESS};
+use scarb::core::Workspace;
+use starknet::accounts::{Account, Call, ConnectedAccount};
+use starknet::core::types::Felt;
+use starknet::macros::selector;
+
+use super::migrate::{check_address_available, check_class_sizes, check_layout_versions};
+
+/// The kind of a step reported by [`execute_strategy_dry_run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DryRunAction {
+    Declare,
+    DeployWorld,
+    UpgradeWorld,
+    RegisterModel,
+    DeployContract,
+    UpgradeContract,
+}
+
+/// A transaction the migration would send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunStep {
+    /// The tag of the resource the step applies to.
+    pub tag: String,
+    pub action: DryRunAction,
+    /// The address of the world or contract deployed or upgraded by the step.
+    pub address: Option<Felt>,
+    /// The estimated fee of the step, `None` if it depends on a previous step which isn't
+    /// applied on-chain yet and can't be simulated.
+    pub estimated_fee: Option<Felt>,
+}
+
+/// What [`execute_strategy`](super::execute_strategy) would do, as computed by
+/// [`execute_strategy_dry_run`].
+#[derive(Debug, Default, Clone)]
+pub struct DryRunOutput {
+    pub world_address: Felt,
+    /// The steps of the migration, in the order they would be sent.
+    pub steps: Vec<DryRunStep>,
+}
+
+impl DryRunOutput {
+    /// Returns the sum of the fees estimated for the steps of the migration.
+    ///
+    /// Steps which couldn't be estimated aren't counted, see [`DryRunStep::estimated_fee`].
+    pub fn total_estimated_fee(&self) -> Felt {
+        self.steps.iter().filter_map(|s| s.estimated_fee).sum()
+    }
+
+    /// Returns the address the contract with the given tag would be deployed at.
+    pub fn contract_address(&self, tag: &str) -> Option<Felt> {
+        self.steps
+            .iter()
+            .find(|s| s.tag == tag && s.action == DryRunAction::DeployContract)
+            .and_then(|s| s.address)
+    }
+}
+
+/// Walks the migration strategy like [`execute_strategy`](super::execute_strategy), estimating
+/// the fee of each transaction instead of sending it.
+///
+/// The provider is only queried, for the class existence checks and the fee estimations, so
+/// the chain state is never modified. A step depending on a class or a world which doesn't
+/// exist on-chain yet can't be simulated, and is reported without a fee. Each invoke is
+/// estimated on its own, even if the migration would batch it with others, and the namespaces
+/// registration is not reported.
+pub async fn execute_strategy_dry_run<A>(
+    ws: &Workspace<'_>,
+    strategy: &MigrationStrategy,
+    migrator: A,
+) -> Result<DryRunOutput>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::Provider: Send,
+    A::SignError: 'static,
+{
+    let ui = ws.config().ui();
+    let world_address = strategy.world_address;
+
+    check_class_sizes(strategy, &ui)?;
+    check_layout_versions(strategy, &migrator, &ui).await?;
+
+    // Classes already declared on-chain, the invokes using them can be estimated.
+    let mut on_chain = HashSet::new();
+    // Classes with a declaration step, declared only once like in the migration.
+    let mut declaring = HashSet::new();
+
+    let mut steps = vec![];
+
+    if let Some(base) = &strategy.base {
+        let (tag, class_hash) = (&base.diff.tag, base.diff.local_class_hash);
+        steps.extend(
+            estimate_declaration(&migrator, base, tag, class_hash, &mut on_chain, &mut declaring)
+                .await?,
+        );
+    }
+
+    // If no world is migrated, the models and contracts target the deployed one.
+    let mut world_on_chain = true;
+
+    if let Some(world) = &strategy.world {
+        let tag = &world.diff.tag;
+        let class_hash = world.diff.local_class_hash;
+
+        steps.extend(
+            estimate_declaration(&migrator, world, tag, class_hash, &mut on_chain, &mut declaring)
+                .await?,
+        );
+
+        let (action, call) = if world.diff.remote_class_hash.is_some() {
+            let call = Call {
+                to: world.contract_address,
+                selector: selector!("upgrade"),
+                calldata: vec![class_hash],
+            };
+
+            (DryRunAction::UpgradeWorld, call)
+        } else {
+            // The address is derived from the seed when the strategy is prepared, this only
+            // ensures nothing is deployed there yet.
+            check_address_available(&migrator, world.contract_address).await?;
+            world_on_chain = false;
+
+            let base_class_hash = strategy.base.as_ref().unwrap().diff.local_class_hash;
+            let call = Call {
+                to: UDC_ADDRESS,
+                selector: selector!("deployContract"),
+                calldata: vec![class_hash, world.salt, Felt::ZERO, Felt::ONE, base_class_hash],
+            };
+
+            (DryRunAction::DeployWorld, call)
+        };
+
+        let estimated_fee = if on_chain.contains(&class_hash) {
+            Some(estimate_invoke(&migrator, call, tag).await?)
+        } else {
+            None
+        };
+
+        steps.push(DryRunStep {
+            tag: tag.clone(),
+            action,
+            address: Some(world.contract_address),
+            estimated_fee,
+        });
+    }
+
+    let world = WorldContract::new(world_address, &migrator);
+    let (models, contracts) = strategy.ordered_migrations();
+
+    for model in &models {
+        let (tag, class_hash) = (&model.diff.tag, model.diff.local_class_hash);
+        steps.extend(
+            estimate_declaration(&migrator, model, tag, class_hash, &mut on_chain, &mut declaring)
+                .await?,
+        );
+    }
+
+    for model in &models {
+        let class_hash = model.diff.local_class_hash;
+        let call = world.register_model_getcall(&class_hash.into());
+
+        let estimated_fee = if world_on_chain && on_chain.contains(&class_hash) {
+            Some(estimate_invoke(&migrator, call, &model.diff.tag).await?)
+        } else {
+            None
+        };
+
+        steps.push(DryRunStep {
+            tag: model.diff.tag.clone(),
+            action: DryRunAction::RegisterModel,
+            address: None,
+            estimated_fee,
+        });
+    }
+
+    for contract in &contracts {
+        let tag = &contract.diff.tag;
+        let class_hash = contract.diff.local_class_hash;
+
+        steps.extend(
+            estimate_declaration(
+                &migrator,
+                contract,
+                tag,
+                class_hash,
+                &mut on_chain,
+                &mut declaring,
+            )
+            .await?,
+        );
+
+        let (call, address, was_upgraded) = match contract
+            .deploy_dojo_contract_call(
+                world_address,
+                class_hash,
+                contract.diff.base_class_hash,
+                &migrator,
+                tag,
+            )
+            .await
+        {
+            Ok(res) => res,
+            Err(MigrationError::ContractAlreadyDeployed(_)) => continue,
+            Err(e) => return Err(anyhow!("Failed to prepare the deployment of `{tag}`: {e}")),
+        };
+
+        let estimated_fee = if world_on_chain && on_chain.contains(&class_hash) {
+            Some(estimate_invoke(&migrator, call, tag).await?)
+        } else {
+            None
+        };
+
+        let action =
+            if was_upgraded { DryRunAction::UpgradeContract } else { DryRunAction::DeployContract };
+
+        steps.push(DryRunStep { tag: tag.clone(), action, address: Some(address), estimated_fee });
+    }
+
+    Ok(DryRunOutput { world_address, steps })
+}
+
+/// Returns the declaration step of a class, or `None` if it's already declared on-chain or by a
+/// previous step.
+async fn estimate_declaration<A, C>(
+    migrator: &A,
+    class: &C,
+    tag: &str,
+    class_hash: Felt,
+    on_chain: &mut HashSet<Felt>,
+    declaring: &mut HashSet<Felt>,
+) -> Result<Option<DryRunStep>>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::Provider: Send,
+    A::SignError: 'static,
+    C: Declarable + Sync,
+{
+    if !declaring.insert(class_hash) {
+        return Ok(None);
+    }
+
+    match class.estimate_declare_fee(migrator).await {
+        Ok(fee) => Ok(Some(DryRunStep {
+            tag: tag.to_string(),
+            action: DryRunAction::Declare,
+            address: None,
+            estimated_fee: Some(fee.overall_fee),
+        })),
+        Err(MigrationError::ClassAlreadyDeclared) => {
+            on_chain.insert(class_hash);
+            Ok(None)
+        }
+        Err(e) => Err(anyhow!("Failed to estimate the declaration of `{tag}`: {e}")),
+    }
+}
+
+async fn estimate_invoke<A>(migrator: &A, call: Call, tag: &str) -> Result<Felt>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::SignError: 'static,
+{
+    let fee = migrator
+        .execute_v1(vec![call])
+        .estimate_fee()
+        .await
+        .map_err(|e| anyhow!("Failed to estimate the migration of `{tag}`: {e}"))?;
+
+    Ok(fee.overall_fee)
+}
@@ -1,63 +1,89 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::future::Future;
 use std::path::Path;
 use std::str::FromStr;
 
 use anyhow::{anyhow, bail, Context, Result};
-use cainome::cairo_serde::ByteArray;
+use cainome::cairo_serde::{ByteArray, ContractAddress};
 use camino::Utf8PathBuf;
 use dojo_utils::{TransactionExt, TransactionWaiter, TxnConfig};
+use dojo_world::config::MigrationConfig;
 use dojo_world::contracts::abi::world::{self, Resource};
-use dojo_world::contracts::naming::{
-    self, compute_selector_from_tag, get_name_from_tag, get_namespace_from_tag,
-};
-use dojo_world::contracts::{cairo_utils, WorldContract};
+use dojo_world::contracts::naming::{self, compute_selector_from_tag, get_namespace_from_tag};
+use dojo_world::contracts::{cairo_utils, WorldContract, WorldContractReader};
 use dojo_world::manifest::{
     AbiFormat, BaseManifest, Class, DeploymentManifest, DojoContract, DojoModel, Manifest,
     ManifestMethods, WorldContract as ManifestWorldContract, WorldMetadata, ABIS_DIR, BASE_DIR,
-    DEPLOYMENT_DIR, MANIFESTS_DIR,
+    DEPLOYMENT_DIR, MANIFESTS_DIR, WORLD_CONTRACT_TAG,
+};
+use dojo_world::metadata::{
+    dojo_metadata_from_workspace, MetadataStorage, ResourceMetadata, UploadConfig,
 };
-use dojo_world::metadata::{dojo_metadata_from_workspace, ResourceMetadata};
 use dojo_world::migration::class::ClassMigration;
 use dojo_world::migration::contract::ContractMigration;
-use dojo_world::migration::strategy::{generate_salt, prepare_for_migration, MigrationStrategy};
+use dojo_world::migration::strategy::{prepare_for_migration, MigrationStrategy, SaltStrategy};
 use dojo_world::migration::world::WorldDiff;
-use dojo_world::migration::{Declarable, Deployable, MigrationError, RegisterOutput, Upgradable};
+use dojo_world::migration::{
+    get_deployed_address_from_receipt, wait_for_declarations, Declarable, DeclareOutput,
+    Deployable, MigrationError, RegisterOutput, Upgradable,
+};
+use dojo_world::uri::Uri;
 use futures::future;
 use itertools::Itertools;
 use scarb::core::Workspace;
 use scarb_ui::Ui;
-use starknet::accounts::{Account, ConnectedAccount, SingleOwnerAccount};
+use starknet::accounts::{Account, Call, ConnectedAccount, SingleOwnerAccount};
 use starknet::core::types::{
     BlockId, BlockTag, Felt, FunctionCall, InvokeTransactionResult, StarknetError,
 };
 use starknet::core::utils::{
     cairo_short_string_to_felt, get_contract_address, get_selector_from_name,
 };
+use starknet::macros::selector;
 use starknet::providers::{AnyProvider, Provider, ProviderError};
 use starknet::signers::LocalWallet;
 use tokio::fs;
+use tokio_util::sync::CancellationToken;
+use tracing::{field, info_span, Instrument, Span};
 
+use super::checkpoint::migration_state_path;
+use super::declaration_cache::{declaration_cache_path, DeclarationCache, DeclaredClasses};
+use super::observer::MigrationObserver;
 use super::ui::{bold_message, italic_message, MigrationUi};
-use super::utils::generate_resource_map;
+use super::utils::{generate_resource_map, save_pinned_metadata};
 use super::{
-    ContractDeploymentOutput, ContractMigrationOutput, ContractUpgradeOutput, MigrationOutput,
+    execute_strategy_with_log, write_migration_reports, write_transaction_log,
+    ContractDeploymentOutput, ContractMigrationOutput, ContractUpgradeOutput, MigrationCheckpoint,
+    MigrationOutput, MigrationReport, TransactionLog,
 };
-use crate::auth::{get_resource_selector, ResourceType, ResourceWriter};
+use crate::auth::{get_resource_selector, ResourceOwner, ResourceType, ResourceWriter};
+use crate::utils;
 
 pub fn prepare_migration(
     target_dir: &Utf8PathBuf,
     diff: WorldDiff,
     name: &str,
     world_address: Option<Felt>,
+    config: Option<&MigrationConfig>,
     ui: &Ui,
 ) -> Result<MigrationStrategy> {
     ui.print_step(3, "📦", "Preparing for migration...");
 
     let name = cairo_short_string_to_felt(name).with_context(|| "Failed to parse World name.")?;
 
-    let migration = prepare_for_migration(world_address, name, target_dir, diff)
+    let mut migration = prepare_for_migration(world_address, name, target_dir, diff)
         .with_context(|| "Problem preparing for migration.")?;
 
+    if let Some(config) = config {
+        migration.strict_layout = config.strict_layout;
+        migration.strict_class_size = config.strict_class_size;
+
+        if let Some(max_class_size) = config.max_class_size {
+            migration.max_class_size = max_class_size;
+        }
+    }
+
     let info = migration.info();
 
     ui.print_sub(format!(
@@ -87,10 +113,29 @@ where
     ui.print_step(4, "🛠", "Migrating...");
     ui.print(" ");
 
-    let migration_output = execute_strategy(ws, strategy, account, txn_config, declarers)
-        .await
-        .map_err(|e| anyhow!(e))
-        .with_context(|| "Problem trying to migrate.")?;
+    let migration_output = match txn_config.report_dir.clone() {
+        Some(report_dir) => {
+            let (migration_output, log) =
+                execute_strategy_with_log(ws, strategy, &account, txn_config, declarers)
+                    .await
+                    .map_err(|e| anyhow!(e))
+                    .with_context(|| "Problem trying to migrate.")?;
+
+            match log {
+                Ok(log) => write_report(&ui, account.provider(), &report_dir, &log).await,
+                Err(e) => {
+                    tracing::warn!(error = ?e, "Failed to collect migration transactions.");
+                    ui.warn(format!("Failed to collect migration transactions: {e:#}"));
+                }
+            }
+
+            migration_output
+        }
+        None => execute_strategy(ws, strategy, &account, txn_config, declarers, None)
+            .await
+            .map_err(|e| anyhow!(e))
+            .with_context(|| "Problem trying to migrate.")?,
+    };
 
     if migration_output.full {
         if let Some(block_number) = migration_output.world_block_number {
@@ -115,13 +160,149 @@ where
     Ok(migration_output)
 }
 
+/// Writes the transaction log and the migration report of `log` to `report_dir`.
+///
+/// The migration is already done at this point, so a failure is only reported and doesn't
+/// fail the migration. If the report can't be built, the transaction log is still written.
+async fn write_report<P>(ui: &Ui, provider: &P, report_dir: &Path, log: &TransactionLog)
+where
+    P: Provider + Sync,
+{
+    let result = async {
+        let report = MigrationReport::from_transaction_log(provider, log).await?;
+        write_migration_reports(report_dir, &report, log)
+    }
+    .await;
+
+    match result {
+        Ok(()) => ui.print_sub(format!("Migration report written to {}", report_dir.display())),
+        Err(e) => {
+            tracing::warn!(error = ?e, "Failed to write migration report.");
+            ui.warn(format!("Failed to write migration report: {e:#}"));
+
+            if let Err(e) = write_transaction_log(report_dir, log) {
+                tracing::warn!(error = ?e, "Failed to write migration transaction log.");
+                ui.warn(format!("Failed to write migration transaction log: {e:#}"));
+            }
+        }
+    }
+}
+
 pub async fn execute_strategy<A>(
     ws: &Workspace<'_>,
     strategy: &MigrationStrategy,
     migrator: A,
     txn_config: TxnConfig,
     declarers: &[SingleOwnerAccount<AnyProvider, LocalWallet>],
+    observer: Option<&dyn MigrationObserver>,
 ) -> Result<MigrationOutput>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::Provider: Send,
+    A::SignError: 'static,
+{
+    let cancel = CancellationToken::new();
+    let (migration_output, _) = execute_strategy_cancellable(
+        ws, strategy, migrator, txn_config, declarers, &cancel, observer,
+    )
+    .await?;
+
+    Ok(migration_output)
+}
+
+/// Executes the migration strategy like [`execute_strategy`], stopping before the next step
+/// once `cancel` is triggered.
+///
+/// The step in progress is always waited for, so the world is never left in an unknown state.
+/// If the migration has been cancelled, the returned output is partial and a
+/// [`MigrationCheckpoint`] is returned to resume it later.
+pub async fn execute_strategy_cancellable<A>(
+    ws: &Workspace<'_>,
+    strategy: &MigrationStrategy,
+    migrator: A,
+    txn_config: TxnConfig,
+    declarers: &[SingleOwnerAccount<AnyProvider, LocalWallet>],
+    cancel: &CancellationToken,
+    observer: Option<&dyn MigrationObserver>,
+) -> Result<(MigrationOutput, Option<MigrationCheckpoint>)>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::Provider: Send,
+    A::SignError: 'static,
+{
+    let checkpoint =
+        MigrationCheckpoint { world_address: strategy.world_address, ..Default::default() };
+    execute_strategy_from(
+        ws, strategy, migrator, txn_config, declarers, cancel, checkpoint, observer,
+    )
+    .await
+}
+
+/// Resumes the last migration of `strategy` which didn't complete, from the migration state
+/// saved by [`execute_strategy`].
+///
+/// The steps recorded as completed are confirmed on-chain before being skipped, so the steps
+/// lost with a restart of the chain are migrated again. The `observer`, if any, is notified of
+/// the progress of the remaining steps only.
+pub async fn resume_strategy<A>(
+    ws: &Workspace<'_>,
+    strategy: &MigrationStrategy,
+    migrator: A,
+    txn_config: TxnConfig,
+    declarers: &[SingleOwnerAccount<AnyProvider, LocalWallet>],
+    observer: Option<&dyn MigrationObserver>,
+) -> Result<MigrationOutput>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::Provider: Send,
+    A::SignError: 'static,
+{
+    let state_path = migration_state_path(ws, &txn_config)?;
+    let Some(checkpoint) = MigrationCheckpoint::load(&state_path)? else {
+        bail!("No migration to resume, {state_path} doesn't exist.");
+    };
+
+    if checkpoint.world_address != strategy.world_address {
+        bail!(
+            "The migration state at {state_path} is for the world at {:#x}, not {:#x}.",
+            checkpoint.world_address,
+            strategy.world_address
+        );
+    }
+
+    let world = WorldContractReader::new(strategy.world_address, migrator.provider());
+    let checkpoint = checkpoint.confirmed(&world).await?;
+    let remaining = checkpoint.remaining(strategy);
+
+    let cancel = CancellationToken::new();
+    let (migration_output, _) = execute_strategy_from(
+        ws, &remaining, migrator, txn_config, declarers, &cancel, checkpoint, observer,
+    )
+    .await?;
+
+    Ok(migration_output)
+}
+
+/// Executes `strategy`, recording its progress to `checkpoint` which is saved to the migration
+/// state after each step.
+///
+/// Each step is traced in a span nested in the `migration` span, carrying the migrated resource
+/// and the resulting transaction hash.
+#[tracing::instrument(
+    name = "migration",
+    skip_all,
+    fields(world_address = %format!("{:#x}", strategy.world_address))
+)]
+async fn execute_strategy_from<A>(
+    ws: &Workspace<'_>,
+    strategy: &MigrationStrategy,
+    migrator: A,
+    txn_config: TxnConfig,
+    declarers: &[SingleOwnerAccount<AnyProvider, LocalWallet>],
+    cancel: &CancellationToken,
+    mut checkpoint: MigrationCheckpoint,
+    observer: Option<&dyn MigrationObserver>,
+) -> Result<(MigrationOutput, Option<MigrationCheckpoint>)>
 where
     A: ConnectedAccount + Sync + Send,
     A::Provider: Send,
@@ -130,13 +311,54 @@ where
     let ui = ws.config().ui();
     let mut world_tx_hash: Option<Felt> = None;
     let mut world_block_number: Option<u64> = None;
+    let mut transaction_hashes = vec![];
+
+    let world_address = strategy.world_address;
+    let state_path = migration_state_path(ws, &txn_config)?;
+
+    check_class_sizes(strategy, &ui)?;
+    check_layout_versions(strategy, &migrator, &ui).await?;
+
+    if let Some(world) = &strategy.world {
+        // A fresh deployment is expected, so nothing must already live at the world address.
+        if world.diff.remote_class_hash.is_none() {
+            check_address_available(&migrator, world.contract_address).await?;
+        }
+    }
+
+    for (class_hash, tags) in strategy.duplicate_class_hashes() {
+        ui.warn(format!(
+            "{} share the class hash {class_hash:#066x}, it will be declared only once.",
+            tags.join(", ")
+        ));
+    }
+
+    let cache_path = declaration_cache_path(ws)?;
+    let mut declared = load_declared_classes(strategy, &migrator, &cache_path).await?;
+
+    if cancel.is_cancelled() {
+        ui.print_sub("Migration cancelled.");
+        return Ok((MigrationOutput { world_address, ..Default::default() }, Some(checkpoint)));
+    }
 
     if let Some(base) = &strategy.base {
         ui.print_header("# Base Contract");
 
-        match base.declare(&migrator, &txn_config).await {
+        let result = if declared.contains(base.diff.local_class_hash) {
+            if let Some(observer) = observer {
+                observer.on_declare_start("base");
+                observer.on_declare_done("base", None);
+            }
+            Err(MigrationError::ClassAlreadyDeclared)
+        } else {
+            traced_declare("base", observer, base.declare(&migrator, &txn_config)).await
+        };
+
+        match result {
             Ok(res) => {
                 ui.print_sub(format!("Class Hash: {:#x}", res.class_hash));
+                transaction_hashes.push(res.transaction_hash);
+                declared.record(&res);
             }
             Err(MigrationError::ClassAlreadyDeclared) => {
                 ui.print_sub(format!("Already declared: {:#x}", base.diff.local_class_hash));
@@ -149,6 +371,16 @@ where
                 return Err(e.into());
             }
         };
+
+        declared.cache.save(&cache_path)?;
+    }
+    checkpoint.base_declared = true;
+    checkpoint.save(&state_path)?;
+
+    if cancel.is_cancelled() {
+        ui.print_sub("Migration cancelled.");
+        let output = MigrationOutput { world_address, transaction_hashes, ..Default::default() };
+        return Ok((output, Some(checkpoint)));
     }
 
     if let Some(world) = &strategy.world {
@@ -157,7 +389,7 @@ where
         // If a migration is pending for the world, we upgrade only if the remote world
         // already exists.
         if world.diff.remote_class_hash.is_some() {
-            let _deploy_result = upgrade_contract(
+            let ContractUpgradeOutput::Output(upgrade_result) = upgrade_contract(
                 world,
                 "world",
                 world.diff.original_class_hash,
@@ -169,9 +401,21 @@ where
             .await
             .map_err(|e| {
                 ui.verbose(format!("{e:?}"));
+                notify_error(observer, "world", &e);
                 anyhow!("Failed to upgrade world: {e}")
             })?;
 
+            transaction_hashes.extend(upgrade_result.declare.map(|d| d.transaction_hash));
+            transaction_hashes.push(upgrade_result.transaction_hash);
+
+            if let Some(observer) = observer {
+                observer.on_deploy(
+                    "world",
+                    world.contract_address,
+                    upgrade_result.transaction_hash,
+                );
+            }
+
             ui.print_sub(format!("Upgraded Contract at address: {:#x}", world.contract_address));
         } else {
             let calldata = vec![strategy.base.as_ref().unwrap().diff.local_class_hash];
@@ -180,21 +424,42 @@ where
                     .await
                     .map_err(|e| {
                         ui.verbose(format!("{e:?}"));
+                        notify_error(observer, "world", &e);
                         anyhow!("Failed to deploy world: {e}")
                     })?;
 
             (world_tx_hash, world_block_number) =
                 if let ContractDeploymentOutput::Output(deploy_result) = deploy_result {
+                    transaction_hashes.extend(deploy_result.declare.map(|d| d.transaction_hash));
+                    transaction_hashes.push(deploy_result.transaction_hash);
                     (Some(deploy_result.transaction_hash), deploy_result.block_number)
                 } else {
                     (None, None)
                 };
 
+            if let Some(transaction_hash) = world_tx_hash {
+                let deployed_address =
+                    get_deployed_address_from_receipt(migrator.provider(), transaction_hash)
+                        .await?;
+
+                if deployed_address != world.contract_address {
+                    bail!(
+                        "World deployed at {deployed_address:#x} but expected at {:#x}.",
+                        world.contract_address
+                    );
+                }
+
+                if let Some(observer) = observer {
+                    observer.on_deploy("world", deployed_address, transaction_hash);
+                }
+            }
+
             ui.print_sub(format!("Contract address: {:#x}", world.contract_address));
         }
     }
+    checkpoint.world_migrated = true;
+    checkpoint.save(&state_path)?;
 
-    let world_address = strategy.world_address;
     let mut migration_output = MigrationOutput {
         world_address,
         world_tx_hash,
@@ -202,8 +467,16 @@ where
         full: false,
         models: vec![],
         contracts: vec![],
+        unchanged_contracts: vec![],
+        transaction_hashes,
     };
 
+    let (models, contracts) = strategy.ordered_migrations();
+    let models = &models;
+    let (contracts, unchanged_contracts) =
+        partition_unchanged_contracts(contracts, world_address, &migrator, &ui).await?;
+    migration_output.unchanged_contracts = unchanged_contracts;
+
     // register namespaces
     let mut namespaces =
         strategy.models.iter().map(|m| get_namespace_from_tag(&m.diff.tag)).collect::<Vec<_>>();
@@ -212,136 +485,424 @@ where
     );
     namespaces = namespaces.into_iter().unique().collect::<Vec<_>>();
 
-    register_namespaces(&namespaces, world_address, &migrator, &ui, &txn_config).await?;
+    if cancel.is_cancelled() {
+        ui.print_sub("Migration cancelled.");
+        return Ok((migration_output, Some(checkpoint)));
+    }
+
+    register_namespaces(
+        &namespaces,
+        world_address,
+        &migrator,
+        &ui,
+        &txn_config,
+        &mut migration_output.transaction_hashes,
+    )
+    .await?;
+
+    if cancel.is_cancelled() {
+        ui.print_sub("Migration cancelled.");
+        return Ok((migration_output, Some(checkpoint)));
+    }
 
     // TODO: rework this part when more time.
     if declarers.is_empty() {
-        match register_dojo_models(&strategy.models, world_address, &migrator, &ui, &txn_config)
-            .await
-        {
+        let result = register_dojo_models(
+            models,
+            world_address,
+            &migrator,
+            &ui,
+            &txn_config,
+            &mut declared,
+            observer,
+        )
+        .await;
+        declared.cache.save(&cache_path)?;
+
+        match result {
             Ok(output) => {
+                migration_output.transaction_hashes.extend(output.transaction_hashes());
                 migration_output.models = output.registered_models;
             }
             Err(e) => {
                 ui.anyhow(&e);
-                return Ok(migration_output);
+                return Ok((migration_output, None));
             }
         };
+        checkpoint.record_models(models);
+        checkpoint.save(&state_path)?;
+
+        if cancel.is_cancelled() {
+            ui.print_sub("Migration cancelled.");
+            return Ok((migration_output, Some(checkpoint)));
+        }
 
-        match register_dojo_contracts(
-            &strategy.contracts,
+        let result = register_dojo_contracts(
+            &contracts,
             world_address,
             migrator,
             &ui,
             &txn_config,
+            &mut declared,
+            &mut migration_output.transaction_hashes,
+            observer,
         )
-        .await
-        {
+        .await;
+        declared.cache.save(&cache_path)?;
+
+        match result {
             Ok(output) => {
                 migration_output.contracts = output;
             }
             Err(e) => {
                 ui.anyhow(&e);
-                return Ok(migration_output);
+                return Ok((migration_output, None));
             }
         };
+        checkpoint.record_contracts(&contracts);
+        checkpoint.save(&state_path)?;
     } else {
-        match register_dojo_models_with_declarers(
-            &strategy.models,
+        let result = register_dojo_models_with_declarers(
+            models,
             world_address,
             &migrator,
             &ui,
             &txn_config,
             declarers,
+            &mut declared,
+            observer,
         )
-        .await
-        {
+        .await;
+        declared.cache.save(&cache_path)?;
+
+        match result {
             Ok(output) => {
+                migration_output.transaction_hashes.extend(output.transaction_hashes());
                 migration_output.models = output.registered_models;
             }
             Err(e) => {
                 ui.anyhow(&e);
-                return Ok(migration_output);
+                return Ok((migration_output, None));
             }
         };
+        checkpoint.record_models(models);
+        checkpoint.save(&state_path)?;
+
+        if cancel.is_cancelled() {
+            ui.print_sub("Migration cancelled.");
+            return Ok((migration_output, Some(checkpoint)));
+        }
 
-        match register_dojo_contracts_declarers(
-            &strategy.contracts,
+        let result = register_dojo_contracts_declarers(
+            &contracts,
             world_address,
             migrator,
             &ui,
             &txn_config,
             declarers,
+            &mut declared,
+            &mut migration_output.transaction_hashes,
+            observer,
         )
-        .await
-        {
+        .await;
+        declared.cache.save(&cache_path)?;
+
+        match result {
             Ok(output) => {
                 migration_output.contracts = output;
             }
             Err(e) => {
                 ui.anyhow(&e);
-                return Ok(migration_output);
+                return Ok((migration_output, None));
             }
         };
+        checkpoint.record_contracts(&contracts);
+        checkpoint.save(&state_path)?;
     }
 
     migration_output.full = true;
 
-    Ok(migration_output)
+    Ok((migration_output, None))
+}
+
+/// Splits `contracts` between the contracts to migrate and the tags of the contracts whose local
+/// class hash matches the class hash deployed on-chain, which are skipped.
+///
+/// The on-chain class hash is read at the address of the contract rather than taken from the
+/// remote manifest or the resource registry of the world, so contracts are neither skipped when
+/// they have been upgraded since, nor migrated again when the manifest is outdated.
+async fn partition_unchanged_contracts<A>(
+    contracts: Vec<ContractMigration>,
+    world_address: Felt,
+    migrator: &A,
+    ui: &Ui,
+) -> Result<(Vec<ContractMigration>, Vec<String>)>
+where
+    A: ConnectedAccount + Sync + Send,
+{
+    let world = WorldContractReader::new(world_address, migrator.provider())
+        .with_block(BlockId::Tag(BlockTag::Pending));
+
+    let mut to_migrate = vec![];
+    let mut unchanged = vec![];
+
+    for contract in contracts {
+        let tag = &contract.diff.tag;
+        let remote_class_hash = world
+            .contract_class_hash(tag)
+            .await
+            .with_context(|| format!("Failed to read the class hash of {tag}"))?;
+
+        if remote_class_hash == Some(contract.diff.local_class_hash) {
+            ui.print_sub(format!("{tag}: skipped (unchanged)"));
+            unchanged.push(tag.clone());
+        } else {
+            to_migrate.push(contract);
+        }
+    }
+
+    Ok((to_migrate, unchanged))
+}
+
+/// Loads the declaration cache at `cache_path` and confirms on-chain which classes of the
+/// strategy it records as declared.
+async fn load_declared_classes<A>(
+    strategy: &MigrationStrategy,
+    migrator: &A,
+    cache_path: &Utf8PathBuf,
+) -> Result<DeclaredClasses>
+where
+    A: ConnectedAccount + Sync + Send,
+{
+    let chain_id = migrator.provider().chain_id().await?;
+    // The cache is only a hint, an unreadable one is ignored and overwritten.
+    let cache = DeclarationCache::load(cache_path).unwrap_or_default();
+
+    let class_hashes = strategy
+        .base
+        .iter()
+        .chain(&strategy.models)
+        .map(|c| c.diff.local_class_hash)
+        .chain(strategy.contracts.iter().map(|c| c.diff.local_class_hash));
+    let confirmed = cache.confirmed(migrator.provider(), chain_id, class_hashes).await?;
+
+    Ok(DeclaredClasses { chain_id, cache, confirmed })
+}
+
+/// Builds the `init_contract` calls of the contracts deployed by the migration.
+///
+/// Dojo contracts are deployed by the world from the base class with an empty constructor, so
+/// that their address doesn't depend on their class. Their initialization parameters are instead
+/// given by the `init_calldata` of the manifest, and passed to `dojo_init` once the contract has
+/// been deployed and permissioned. Upgraded and unchanged contracts are not initialized again.
+pub fn init_contract_calls(
+    strategy: &MigrationStrategy,
+    migration_output: &MigrationOutput,
+) -> Result<Vec<Call>> {
+    let mut calls = vec![];
+
+    for c in &strategy.contracts {
+        let was_upgraded = migration_output
+            .contracts
+            .iter()
+            .flatten()
+            .find(|output| output.tag == c.diff.tag)
+            .map(|output| output.was_upgraded)
+            .unwrap_or(false);
+
+        if was_upgraded || migration_output.unchanged_contracts.contains(&c.diff.tag) {
+            continue;
+        }
+
+        let contract_selector = compute_selector_from_tag(&c.diff.tag);
+        let init_calldata: Vec<Felt> = c
+            .diff
+            .init_calldata
+            .iter()
+            .map(|s| Felt::from_str(s))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Invalid init_calldata for {}", c.diff.tag))?;
+
+        let mut calldata = vec![contract_selector, Felt::from(init_calldata.len())];
+        calldata.extend(init_calldata);
+
+        calls.push(Call {
+            calldata,
+            selector: selector!("init_contract"),
+            to: strategy.world_address,
+        });
+    }
+
+    Ok(calls)
+}
+
+/// Fails with [`MigrationError::AddressOccupied`] if a contract is already deployed at
+/// `address`, which happens when the world seed collides with a previous deployment.
+pub(super) async fn check_address_available<A>(migrator: &A, address: Felt) -> Result<()>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::SignError: 'static,
+{
+    match migrator.provider().get_class_hash_at(BlockId::Tag(BlockTag::Pending), address).await {
+        Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(()),
+        Ok(_) => Err(MigrationError::<A::SignError>::AddressOccupied { address }.into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Checks the size of the classes to declare against the maximum size of the strategy, before
+/// sending any transaction, as declaring an oversized class fails on-chain.
+pub(super) fn check_class_sizes(strategy: &MigrationStrategy, ui: &Ui) -> Result<()> {
+    for (tag, size) in strategy.class_sizes()? {
+        if size > strategy.max_class_size {
+            let message = format!(
+                "Class of `{tag}` is {size} bytes, which exceeds the maximum class size of {} \
+                 bytes.",
+                strategy.max_class_size
+            );
+
+            if strategy.strict_class_size {
+                bail!(message);
+            }
+
+            ui.warn(message);
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the storage layout version of the contracts about to be upgraded against the
+/// version expected by their new class.
+///
+/// A mismatch is reported as a warning, or as an error if the strategy is strict.
+pub(super) async fn check_layout_versions<A>(
+    strategy: &MigrationStrategy,
+    migrator: &A,
+    ui: &Ui,
+) -> Result<()>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::Provider: Send,
+{
+    let world = WorldContractReader::new(strategy.world_address, migrator.provider())
+        .with_block(BlockId::Tag(BlockTag::Pending));
+
+    for contract in &strategy.contracts {
+        let Some(expected) = contract.expected_layout_version else {
+            continue;
+        };
+
+        let contract_address = get_contract_address(
+            contract.salt,
+            contract.diff.base_class_hash,
+            &[],
+            strategy.world_address,
+        );
+
+        // Only contracts already deployed with another class are upgraded.
+        match migrator
+            .provider()
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), contract_address)
+            .await
+        {
+            Ok(class_hash) if class_hash != contract.diff.local_class_hash => {}
+            Ok(_) | Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => continue,
+            Err(e) => return Err(e.into()),
+        }
+
+        let version = world.contract_layout_version(contract_address).await?;
+
+        if version != expected {
+            let message = format!(
+                "Contract `{}` has storage layout version {version} but the new class expects \
+                 {expected}.",
+                contract.diff.tag
+            );
+
+            if strategy.strict_layout {
+                bail!(message);
+            }
+
+            ui.warn(message);
+        }
+    }
+
+    Ok(())
 }
 
-/// Upload a metadata as a IPFS artifact and then create a resource to register
-/// into the Dojo resource registry.
+/// Upload a metadata as a IPFS artifact.
 ///
 /// # Arguments
 /// * `ui` - The user interface object for displaying information
-/// * `resource_id` - The id of the resource to create
+/// * `tag` - The tag of the resource the metadata belongs to
 /// * `metadata` - The ResourceMetadata object containing the metadata to upload
+/// * `storage` - The storage the metadata is uploaded to
+/// * `config` - The options used to add the metadata to IPFS
 ///
 /// # Returns
-/// A [`world::ResourceMetadata`] object to register in the Dojo resource register
-/// on success, or an error if the upload fails.
-async fn upload_on_ipfs_and_create_resource(
+/// The `tag` along with the URI of the uploaded artifact on success, or an error if the
+/// upload fails.
+async fn upload_on_ipfs(
     ui: &Ui,
-    resource_id: Felt,
+    tag: String,
     metadata: ResourceMetadata,
-) -> Result<world::ResourceMetadata> {
-    match metadata.upload().await {
-        Ok(hash) => {
-            ui.print_sub(format!("{}: ipfs://{}", metadata.name, hash));
-            create_resource_metadata(resource_id, hash)
-        }
-        Err(_) => Err(anyhow!("Failed to upload IPFS resource.")),
-    }
+    storage: &dyn MetadataStorage,
+    config: UploadConfig,
+) -> (String, Result<Uri>) {
+    let uri = metadata.upload_to(storage, &config).await.map(|hash| {
+        let uri = storage.uri(&hash);
+        ui.print_sub(format!("{}: {uri}", metadata.name));
+        uri
+    });
+
+    (tag, uri)
 }
 
 /// Create a resource to register in the Dojo resource registry.
 ///
 /// # Arguments
 /// * `resource_id` - the ID of the resource
-/// * `hash` - the IPFS hash
+/// * `uri` - the URI of the pinned metadata
 ///
 /// # Returns
 /// A [`ResourceData`] object to register in the Dojo resource register
 /// on success.
-fn create_resource_metadata(resource_id: Felt, hash: String) -> Result<world::ResourceMetadata> {
-    let metadata_uri = cairo_utils::encode_uri(&format!("ipfs://{hash}"))?;
+fn create_resource_metadata(resource_id: Felt, uri: &Uri) -> Result<world::ResourceMetadata> {
+    let metadata_uri = cairo_utils::encode_uri(&uri.to_string())?;
     Ok(world::ResourceMetadata { resource_id, metadata_uri })
 }
 
 /// Upload metadata of the world/models/contracts as IPFS artifacts and then
 /// register them in the Dojo resource registry.
 ///
+/// The elements already present in `pinned_manifest` (element name to pinned URI, the world
+/// being named [`WORLD_CONTRACT_TAG`]) aren't uploaded again. Every successful upload is added to
+/// it, and saved at `pinned_path` if any, so that a failed upload can be resumed by calling this
+/// function again with the same manifest. An error is returned if any upload failed.
+///
+/// The metadata are uploaded to `storage`, with the add options (pinning and CID version) read
+/// from the `migration.upload` section of the profile configuration.
+///
 /// # Arguments
 ///
 /// * `ws` - the workspace
 /// * `migrator` - the account used to migrate
 /// * `migration_output` - the output after having applied the migration plan.
+/// * `storage` - the storage the metadata are uploaded to, [`IpfsStorage::default_node`] by
+///   default.
+/// * `pinned_manifest` - the metadata already pinned by a previous run.
+/// * `pinned_path` - the file the pinned metadata are saved to after each upload.
+///
+/// [`IpfsStorage::default_node`]: dojo_world::metadata::IpfsStorage::default_node
 pub async fn upload_metadata<A>(
     ws: &Workspace<'_>,
     migrator: A,
     migration_output: MigrationOutput,
     txn_config: TxnConfig,
+    storage: &dyn MetadataStorage,
+    pinned_manifest: &mut HashMap<String, Uri>,
+    pinned_path: Option<&Utf8PathBuf>,
 ) -> Result<()>
 where
     A: ConnectedAccount + Sync + Send,
@@ -354,60 +915,80 @@ where
     ui.print(" ");
 
     let dojo_metadata = dojo_metadata_from_workspace(ws)?;
+    let upload_config = dojo_metadata.migration.as_ref().map(|m| m.upload).unwrap_or_default();
+
+    // the elements to register in the resource registry, with their resource id
+    let mut elements = vec![];
     let mut ipfs = vec![];
-    let mut resources = vec![];
+    // the elements whose upload failed, the successful uploads are kept
+    let mut failed = vec![];
 
     // world
     if migration_output.world_tx_hash.is_some() {
-        match dojo_metadata.world.upload().await {
-            Ok(hash) => {
-                let resource = create_resource_metadata(Felt::ZERO, hash.clone())?;
-                ui.print_sub(format!("world: ipfs://{}", hash));
-                resources.push(resource);
-            }
-            Err(err) => {
-                ui.print_sub(format!("Failed to upload World metadata:\n{err}"));
+        elements.push((WORLD_CONTRACT_TAG.to_string(), Felt::ZERO));
+
+        if let Some(uri) = pinned_manifest.get(WORLD_CONTRACT_TAG) {
+            ui.print_sub(format!("world: {uri} (already pinned)"));
+        } else {
+            match dojo_metadata.world.upload_to(storage, &upload_config).await {
+                Ok(hash) => {
+                    let uri = storage.uri(&hash);
+                    ui.print_sub(format!("world: {uri}"));
+                    pinned_manifest.insert(WORLD_CONTRACT_TAG.to_string(), uri);
+                    if let Some(path) = pinned_path {
+                        save_pinned_metadata(path, pinned_manifest)?;
+                    }
+                }
+                Err(err) => {
+                    ui.print_sub(format!("Failed to upload World metadata:\n{err}"));
+                    failed.push(WORLD_CONTRACT_TAG.to_string());
+                }
             }
         }
     }
 
-    // models
-    if !migration_output.models.is_empty() {
-        for model_tag in migration_output.models {
-            if let Some(m) = dojo_metadata.resources_artifacts.get(&model_tag) {
-                ipfs.push(upload_on_ipfs_and_create_resource(
-                    &ui,
-                    compute_selector_from_tag(&model_tag),
-                    m.clone(),
-                ));
+    // models and contracts
+    let migrated_contracts = migration_output.contracts.into_iter().flatten().map(|c| c.tag);
+
+    for tag in migration_output.models.into_iter().chain(migrated_contracts) {
+        if let Some(m) = dojo_metadata.resources_artifacts.get(&tag) {
+            elements.push((tag.clone(), compute_selector_from_tag(&tag)));
+
+            if let Some(uri) = pinned_manifest.get(&tag) {
+                ui.print_sub(format!("{tag}: {uri} (already pinned)"));
+            } else {
+                ipfs.push(upload_on_ipfs(&ui, tag, m.clone(), storage, upload_config));
             }
         }
     }
 
-    // contracts
-    let migrated_contracts = migration_output.contracts.into_iter().flatten().collect::<Vec<_>>();
+    // upload IPFS, keeping the successful uploads even if some of them failed
+    for (tag, uri) in future::join_all(ipfs).await {
+        match uri {
+            Ok(uri) => {
+                pinned_manifest.insert(tag, uri);
 
-    if !migrated_contracts.is_empty() {
-        for contract in migrated_contracts {
-            if let Some(m) = dojo_metadata.resources_artifacts.get(&contract.tag) {
-                ipfs.push(upload_on_ipfs_and_create_resource(
-                    &ui,
-                    naming::compute_selector_from_tag(&contract.tag),
-                    m.clone(),
-                ));
+                if let Some(path) = pinned_path {
+                    save_pinned_metadata(path, pinned_manifest)?;
+                }
             }
+            Err(_) => failed.push(tag),
         }
     }
 
-    // upload IPFS
-    resources.extend(
-        future::try_join_all(ipfs)
-            .await
-            .map_err(|_| anyhow!("Unable to upload IPFS artifacts."))?,
-    );
+    if !failed.is_empty() {
+        bail!("Unable to upload the metadata of {}.", failed.join(", "));
+    }
 
     ui.print("> All IPFS artifacts have been successfully uploaded.".to_string());
 
+    let resources = elements
+        .iter()
+        .filter_map(|(name, resource_id)| {
+            pinned_manifest.get(name).map(|uri| create_resource_metadata(*resource_id, uri))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
     // update the resource registry
     let world = WorldContract::new(migration_output.world_address, &migrator);
 
@@ -432,12 +1013,18 @@ where
     Ok(())
 }
 
+#[tracing::instrument(
+    name = "register_namespaces",
+    skip_all,
+    fields(count = namespaces.len(), tx_hash = field::Empty)
+)]
 async fn register_namespaces<A>(
     namespaces: &[String],
     world_address: Felt,
     migrator: &A,
     ui: &Ui,
     txn_config: &TxnConfig,
+    transaction_hashes: &mut Vec<Felt>,
 ) -> Result<()>
 where
     A: ConnectedAccount + Send + Sync,
@@ -476,6 +1063,8 @@ where
             ui.verbose(format!("{e:?}"));
             anyhow!("Failed to register namespace to World: {e}")
         })?;
+    transaction_hashes.push(transaction_hash);
+    record_tx_hash(transaction_hash);
 
     TransactionWaiter::new(transaction_hash, migrator.provider()).await?;
 
@@ -484,12 +1073,19 @@ where
     Ok(())
 }
 
+#[tracing::instrument(
+    name = "register_models",
+    skip_all,
+    fields(count = models.len(), tx_hash = field::Empty)
+)]
 async fn register_dojo_models<A>(
     models: &[ClassMigration],
     world_address: Felt,
     migrator: &A,
     ui: &Ui,
     txn_config: &TxnConfig,
+    declared_classes: &mut DeclaredClasses,
+    observer: Option<&dyn MigrationObserver>,
 ) -> Result<RegisterOutput>
 where
     A: ConnectedAccount + Send + Sync,
@@ -507,43 +1103,47 @@ where
 
     let world = WorldContract::new(world_address, &migrator);
 
-    let mut declare_output = vec![];
     let mut models_to_register = vec![];
+    let mut to_declare = vec![];
+    let mut declared = declared_classes.confirmed.clone();
 
-    for (i, m) in models.iter().enumerate() {
+    for m in models {
         let tag = &m.diff.tag;
 
-        ui.print(italic_message(tag).to_string());
-
         if let Resource::Unregistered =
             world.resource(&compute_selector_from_tag(tag)).call().await?
         {
             models_to_register.push(tag.clone());
-        } else {
+
+            if declared.insert(m.diff.local_class_hash) {
+                to_declare.push((m.diff.tag.as_str(), m));
+            }
+        }
+    }
+
+    let outputs =
+        declare_classes(&to_declare, &migrator, ui, txn_config, declared_classes, observer).await?;
+    let mut outputs =
+        to_declare.iter().map(|(tag, _)| *tag).zip(outputs).collect::<HashMap<_, _>>();
+
+    let mut declare_output = vec![];
+
+    for m in models {
+        let tag = &m.diff.tag;
+        ui.print(italic_message(tag).to_string());
+
+        if !models_to_register.contains(tag) {
             ui.print_sub("Already registered");
             continue;
         }
 
-        match m.declare(&migrator, txn_config).await {
-            Ok(output) => {
-                ui.print_sub(format!("Selector: {:#066x}", compute_selector_from_tag(tag)));
-                ui.print_hidden_sub(format!("Class hash: {:#066x}", output.class_hash));
-                ui.print_hidden_sub(format!(
-                    "Declare transaction: {:#066x}",
-                    output.transaction_hash
-                ));
-                declare_output.push(output);
-            }
-            Err(MigrationError::ClassAlreadyDeclared) => {
-                ui.print_sub("Already declared");
-            }
-            Err(MigrationError::ArtifactError(e)) => {
-                return Err(handle_artifact_error(ui, models[i].artifact_path(), e));
-            }
-            Err(e) => {
-                ui.verbose(format!("{e:?}"));
-                bail!("Failed to declare model: {e}")
-            }
+        if let Some(output) = outputs.remove(tag.as_str()).flatten() {
+            ui.print_sub(format!("Selector: {:#066x}", compute_selector_from_tag(tag)));
+            ui.print_hidden_sub(format!("Class hash: {:#066x}", output.class_hash));
+            ui.print_hidden_sub(format!("Declare transaction: {:#066x}", output.transaction_hash));
+            declare_output.push(output);
+        } else {
+            ui.print_sub("Already declared");
         }
     }
 
@@ -556,17 +1156,209 @@ where
     let InvokeTransactionResult { transaction_hash } =
         world.account.execute_v1(calls).send_with_cfg(txn_config).await.map_err(|e| {
             ui.verbose(format!("{e:?}"));
-            anyhow!("Failed to register models to World: {e}")
+            let e = anyhow!("Failed to register models to World: {e}");
+            notify_errors(observer, &models_to_register, &e);
+            e
         })?;
+    record_tx_hash(transaction_hash);
 
-    TransactionWaiter::new(transaction_hash, migrator.provider()).await?;
+    TransactionWaiter::new(transaction_hash, migrator.provider())
+        .await
+        .inspect_err(|e| notify_errors(observer, &models_to_register, e))?;
+
+    if let Some(observer) = observer {
+        for tag in &models_to_register {
+            observer.on_register_model(tag, transaction_hash);
+        }
+    }
 
     ui.print(format!("All models are registered at: {transaction_hash:#x}\n"));
 
     Ok(RegisterOutput { transaction_hash, declare_output, registered_models: models_to_register })
 }
 
+/// Declares `classes` with `migrator`, in batches of `txn_config.max_concurrent_declarations`
+/// declarations sent at once and then waited for together.
+///
+/// The declarations don't depend on each other, only the nonce of the migrator is shared between
+/// them. It's fetched once and incremented for each declaration sent, so all the classes are read
+/// and checked first: a class skipped or failing once the following nonces are used would leave a
+/// gap that blocks the following declarations. The outputs are returned in the order of
+/// `classes`, `None` for the classes already declared, and the first failure stops the
+/// declarations not sent yet.
+async fn declare_classes<A, C>(
+    classes: &[(&str, &C)],
+    migrator: &A,
+    ui: &Ui,
+    txn_config: &TxnConfig,
+    declared_classes: &mut DeclaredClasses,
+    observer: Option<&dyn MigrationObserver>,
+) -> Result<Vec<Option<DeclareOutput>>>
+where
+    A: ConnectedAccount + Send + Sync,
+    <A as ConnectedAccount>::Provider: Send,
+    C: Declarable + Sync,
+{
+    if classes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let prepared =
+        future::join_all(classes.iter().map(|(_, class)| class.prepare_declaration(migrator)))
+            .await;
+
+    let mut declarations = vec![];
+
+    for (i, result) in prepared.into_iter().enumerate() {
+        let (tag, class) = classes[i];
+
+        match result {
+            Ok(declaration) => declarations.push((i, declaration)),
+            Err(MigrationError::ClassAlreadyDeclared) => {
+                if let Some(observer) = observer {
+                    observer.on_declare_start(tag);
+                    observer.on_declare_done(tag, None);
+                }
+            }
+            Err(MigrationError::ArtifactError(e)) => {
+                notify_error(observer, tag, &e);
+                return Err(handle_artifact_error(ui, class.artifact_path(), e));
+            }
+            Err(e) => {
+                notify_error(observer, tag, &e);
+                ui.verbose(format!("{e:?}"));
+                bail!("Failed to declare class: {e}")
+            }
+        }
+    }
+
+    let mut nonce = migrator.get_nonce().await?;
+    let batch_size = txn_config.max_concurrent_declarations.unwrap_or(1).max(1);
+    let mut declarations = declarations.into_iter().peekable();
+    let mut outputs = vec![None; classes.len()];
+
+    while declarations.peek().is_some() {
+        let mut indices = vec![];
+        let mut sent = vec![];
+
+        for (i, declaration) in declarations.by_ref().take(batch_size) {
+            let tag = classes[i].0;
+
+            if let Some(observer) = observer {
+                observer.on_declare_start(tag);
+            }
+
+            let span = info_span!(
+                "declare",
+                resource = tag,
+                class_hash = field::Empty,
+                tx_hash = field::Empty
+            );
+            let output = declaration
+                .send_with_nonce(migrator, txn_config, nonce)
+                .instrument(span.clone())
+                .await
+                .map_err(|e| {
+                    notify_error(observer, tag, &e);
+                    ui.verbose(format!("{e:?}"));
+                    anyhow!("Failed to declare class: {e}")
+                })?;
+
+            span.record("class_hash", format!("{:#x}", output.class_hash).as_str());
+            span.record("tx_hash", format!("{:#x}", output.transaction_hash).as_str());
+
+            nonce += Felt::ONE;
+            indices.push(i);
+            sent.push(output);
+        }
+
+        wait_for_declarations(migrator.provider(), &sent, txn_config).await.map_err(
+            |e: MigrationError<A::SignError>| {
+                let tags = indices.iter().map(|&i| classes[i].0).collect::<Vec<_>>();
+                notify_errors(observer, &tags, &e);
+                ui.verbose(format!("{e:?}"));
+                anyhow!("Failed to declare class: {e}")
+            },
+        )?;
+
+        for (i, output) in indices.into_iter().zip(sent) {
+            if let Some(observer) = observer {
+                observer.on_declare_done(classes[i].0, Some(output.transaction_hash));
+            }
+
+            declared_classes.record(&output);
+            outputs[i] = Some(output);
+        }
+    }
+
+    Ok(outputs)
+}
+
+/// Awaits the `declaration` of the class of `resource` in a `declare` span, recording the
+/// declared class hash and the declaration transaction hash.
+///
+/// The `observer` is notified when the declaration starts and once it's done or has failed.
+async fn traced_declare<F, E>(
+    resource: &str,
+    observer: Option<&dyn MigrationObserver>,
+    declaration: F,
+) -> Result<DeclareOutput, MigrationError<E>>
+where
+    F: Future<Output = Result<DeclareOutput, MigrationError<E>>>,
+    MigrationError<E>: Display,
+{
+    if let Some(observer) = observer {
+        observer.on_declare_start(resource);
+    }
+
+    let span = info_span!("declare", resource, class_hash = field::Empty, tx_hash = field::Empty);
+    let result = declaration.instrument(span.clone()).await;
+
+    if let Ok(output) = &result {
+        span.record("class_hash", format!("{:#x}", output.class_hash).as_str());
+        span.record("tx_hash", format!("{:#x}", output.transaction_hash).as_str());
+    }
+
+    if let Some(observer) = observer {
+        match &result {
+            Ok(output) => observer.on_declare_done(resource, Some(output.transaction_hash)),
+            Err(MigrationError::ClassAlreadyDeclared) => observer.on_declare_done(resource, None),
+            Err(e) => observer.on_error(resource, e),
+        }
+    }
+
+    result
+}
+
+/// Notifies `observer`, if any, that the migration of `element` failed with `error`.
+fn notify_error(observer: Option<&dyn MigrationObserver>, element: &str, error: &dyn Display) {
+    if let Some(observer) = observer {
+        observer.on_error(element, error);
+    }
+}
+
+/// Notifies `observer`, if any, that the migration of each of `elements` failed with `error`,
+/// as they are all sent in the transaction which failed.
+fn notify_errors<T>(observer: Option<&dyn MigrationObserver>, elements: &[T], error: &dyn Display)
+where
+    T: AsRef<str>,
+{
+    for element in elements {
+        notify_error(observer, element.as_ref(), error);
+    }
+}
+
+/// Records `transaction_hash` as the `tx_hash` of the current span.
+fn record_tx_hash(transaction_hash: Felt) {
+    Span::current().record("tx_hash", format!("{transaction_hash:#x}").as_str());
+}
+
 // For now duplicated because the migrator account is different from the declarers account type.
+#[tracing::instrument(
+    name = "register_models",
+    skip_all,
+    fields(count = models.len(), tx_hash = field::Empty)
+)]
 async fn register_dojo_models_with_declarers<A>(
     models: &[ClassMigration],
     world_address: Felt,
@@ -574,6 +1366,8 @@ async fn register_dojo_models_with_declarers<A>(
     ui: &Ui,
     txn_config: &TxnConfig,
     declarers: &[SingleOwnerAccount<AnyProvider, LocalWallet>],
+    declared_classes: &mut DeclaredClasses,
+    observer: Option<&dyn MigrationObserver>,
 ) -> Result<RegisterOutput>
 where
     A: ConnectedAccount + Send + Sync,
@@ -592,8 +1386,14 @@ where
     let mut declare_output = vec![];
     let mut models_to_register = vec![];
 
+    // A class shared by several models is declared only once, declaring it concurrently from
+    // several declarers would fail. The classes confirmed by the cache aren't declared at all.
+    let mut declared = declared_classes.confirmed.clone();
+    let (to_declare, shared): (Vec<_>, Vec<_>) =
+        models.iter().partition(|m| declared.insert(m.diff.local_class_hash));
+
     let mut declarers_tasks = HashMap::new();
-    for (i, m) in models.iter().enumerate() {
+    for (i, m) in to_declare.into_iter().enumerate() {
         let declarer_index = i % declarers.len();
         declarers_tasks
             .entry(declarer_index)
@@ -607,7 +1407,7 @@ where
         let future = async move {
             let mut results = Vec::new();
             for (tag, task) in d_tasks {
-                let result = task.await;
+                let result = traced_declare(&tag, observer, task).await;
                 results.push((declarer_index, tag, result));
             }
             results
@@ -641,6 +1441,7 @@ where
                         "Declare transaction: {:#066x}",
                         output.transaction_hash
                     ));
+                    declared_classes.record(&output);
                     declare_output.push(output);
                 }
                 Err(MigrationError::ClassAlreadyDeclared) => {
@@ -657,6 +1458,22 @@ where
         }
     }
 
+    // The models sharing a class declared above, or already declared, only have to be
+    // registered.
+    for m in shared {
+        let tag = &m.diff.tag;
+        ui.print(italic_message(tag).to_string());
+
+        if let Resource::Unregistered =
+            world.resource(&compute_selector_from_tag(tag)).call().await?
+        {
+            models_to_register.push(tag.clone());
+            ui.print_sub("Already declared");
+        } else {
+            ui.print_sub("Already registered");
+        }
+    }
+
     let calls = models
         .iter()
         .filter(|m| models_to_register.contains(&m.diff.tag))
@@ -666,22 +1483,41 @@ where
     let InvokeTransactionResult { transaction_hash } =
         world.account.execute_v1(calls).send_with_cfg(txn_config).await.map_err(|e| {
             ui.verbose(format!("{e:?}"));
-            anyhow!("Failed to register models to World: {e}")
+            let e = anyhow!("Failed to register models to World: {e}");
+            notify_errors(observer, &models_to_register, &e);
+            e
         })?;
+    record_tx_hash(transaction_hash);
 
-    TransactionWaiter::new(transaction_hash, migrator.provider()).await?;
+    TransactionWaiter::new(transaction_hash, migrator.provider())
+        .await
+        .inspect_err(|e| notify_errors(observer, &models_to_register, e))?;
+
+    if let Some(observer) = observer {
+        for tag in &models_to_register {
+            observer.on_register_model(tag, transaction_hash);
+        }
+    }
 
     ui.print(format!("All models are registered at: {transaction_hash:#x}\n"));
 
     Ok(RegisterOutput { transaction_hash, declare_output, registered_models: models_to_register })
 }
 
+#[tracing::instrument(
+    name = "deploy_contracts",
+    skip_all,
+    fields(count = contracts.len(), tx_hash = field::Empty)
+)]
 async fn register_dojo_contracts<A>(
     contracts: &Vec<ContractMigration>,
     world_address: Felt,
     migrator: A,
     ui: &Ui,
     txn_config: &TxnConfig,
+    declared_classes: &mut DeclaredClasses,
+    transaction_hashes: &mut Vec<Felt>,
+    observer: Option<&dyn MigrationObserver>,
 ) -> Result<Vec<Option<ContractMigrationOutput>>>
 where
     A: ConnectedAccount + Send + Sync,
@@ -693,32 +1529,29 @@ where
 
     ui.print_header(format!("# Contracts ({})", contracts.len()));
 
-    let mut declare_outputs = vec![];
+    let mut declared = declared_classes.confirmed.clone();
+    let to_declare = contracts
+        .iter()
+        .filter(|c| declared.insert(c.diff.local_class_hash))
+        .map(|c| (c.diff.tag.as_str(), c))
+        .collect::<Vec<_>>();
+
+    let outputs =
+        declare_classes(&to_declare, &migrator, ui, txn_config, declared_classes, observer).await?;
+    let mut outputs =
+        to_declare.iter().map(|(tag, _)| *tag).zip(outputs).collect::<HashMap<_, _>>();
 
-    for (i, c) in contracts.iter().enumerate() {
+    for c in contracts {
         let tag = &c.diff.tag;
         ui.print(italic_message(&tag).to_string());
 
-        match c.declare(&migrator, txn_config).await {
-            Ok(output) => {
-                ui.print_sub(format!("Selector: {:#066x}", compute_selector_from_tag(tag)));
-                ui.print_hidden_sub(format!("Class hash: {:#066x}", output.class_hash));
-                ui.print_hidden_sub(format!(
-                    "Declare transaction: {:#066x}",
-                    output.transaction_hash
-                ));
-                declare_outputs.push(output);
-            }
-            Err(MigrationError::ClassAlreadyDeclared) => {
-                ui.print_sub("Already declared");
-            }
-            Err(MigrationError::ArtifactError(e)) => {
-                return Err(handle_artifact_error(ui, contracts[i].artifact_path(), e));
-            }
-            Err(e) => {
-                ui.verbose(format!("{e:?}"));
-                bail!("Failed to declare model: {e}")
-            }
+        if let Some(output) = outputs.remove(tag.as_str()).flatten() {
+            ui.print_sub(format!("Selector: {:#066x}", compute_selector_from_tag(tag)));
+            ui.print_hidden_sub(format!("Class hash: {:#066x}", output.class_hash));
+            ui.print_hidden_sub(format!("Declare transaction: {:#066x}", output.transaction_hash));
+            transaction_hashes.push(output.transaction_hash);
+        } else {
+            ui.print_sub("Already declared");
         }
     }
 
@@ -752,6 +1585,7 @@ where
             deploy_outputs.push(Some(ContractMigrationOutput {
                 tag: tag.clone(),
                 contract_address,
+                class_hash: contract.diff.local_class_hash,
                 base_class_hash,
                 was_upgraded,
             }));
@@ -761,19 +1595,38 @@ where
         }
     }
 
+    let to_deploy = deploy_outputs.iter().flatten().map(|o| &o.tag).collect::<Vec<_>>();
+
     let InvokeTransactionResult { transaction_hash } =
         migrator.execute_v1(calls).send_with_cfg(txn_config).await.map_err(|e| {
             ui.verbose(format!("{e:?}"));
-            anyhow!("Failed to deploy contracts: {e}")
+            let e = anyhow!("Failed to deploy contracts: {e}");
+            notify_errors(observer, &to_deploy, &e);
+            e
         })?;
+    transaction_hashes.push(transaction_hash);
+    record_tx_hash(transaction_hash);
 
-    TransactionWaiter::new(transaction_hash, migrator.provider()).await?;
+    TransactionWaiter::new(transaction_hash, migrator.provider())
+        .await
+        .inspect_err(|e| notify_errors(observer, &to_deploy, e))?;
+
+    if let Some(observer) = observer {
+        for output in deploy_outputs.iter().flatten() {
+            observer.on_deploy(&output.tag, output.contract_address, transaction_hash);
+        }
+    }
 
     ui.print(format!("All contracts are deployed at: {transaction_hash:#x}\n"));
 
     Ok(deploy_outputs)
 }
 
+#[tracing::instrument(
+    name = "deploy_contracts",
+    skip_all,
+    fields(count = contracts.len(), tx_hash = field::Empty)
+)]
 async fn register_dojo_contracts_declarers<A>(
     contracts: &Vec<ContractMigration>,
     world_address: Felt,
@@ -781,6 +1634,9 @@ async fn register_dojo_contracts_declarers<A>(
     ui: &Ui,
     txn_config: &TxnConfig,
     declarers: &[SingleOwnerAccount<AnyProvider, LocalWallet>],
+    declared_classes: &mut DeclaredClasses,
+    transaction_hashes: &mut Vec<Felt>,
+    observer: Option<&dyn MigrationObserver>,
 ) -> Result<Vec<Option<ContractMigrationOutput>>>
 where
     A: ConnectedAccount + Send + Sync,
@@ -794,8 +1650,12 @@ where
 
     // Declare all and keep (tg, class_hash, tx_hash).
     // Then multicall the deploy matching the class hash.
+    // A class shared by several contracts is declared only once, declaring it concurrently from
+    // several declarers would fail. The classes confirmed by the cache aren't declared at all.
+    let mut declared = declared_classes.confirmed.clone();
     let mut declarers_tasks = HashMap::new();
-    for (i, c) in contracts.iter().enumerate() {
+    for (i, c) in contracts.iter().filter(|c| declared.insert(c.diff.local_class_hash)).enumerate()
+    {
         let declarer_index = i % declarers.len();
         declarers_tasks
             .entry(declarer_index)
@@ -809,7 +1669,7 @@ where
         let future = async move {
             let mut results = Vec::new();
             for (tag, task) in d_tasks {
-                let result = task.await;
+                let result = traced_declare(&tag, observer, task).await;
                 results.push((declarer_index, tag, result));
             }
             results
@@ -833,6 +1693,8 @@ where
                         "Declare transaction: {:#066x}",
                         output.transaction_hash
                     ));
+                    transaction_hashes.push(output.transaction_hash);
+                    declared_classes.record(&output);
                     declare_outputs.push(output);
                 }
                 Err(MigrationError::ClassAlreadyDeclared) => {
@@ -879,6 +1741,7 @@ where
             deploy_outputs.push(Some(ContractMigrationOutput {
                 tag: tag.clone(),
                 contract_address,
+                class_hash: contract.diff.local_class_hash,
                 base_class_hash,
                 was_upgraded,
             }));
@@ -888,19 +1751,42 @@ where
         }
     }
 
+    let to_deploy = deploy_outputs.iter().flatten().map(|o| &o.tag).collect::<Vec<_>>();
+
     let InvokeTransactionResult { transaction_hash } =
         migrator.execute_v1(calls).send_with_cfg(txn_config).await.map_err(|e| {
             ui.verbose(format!("{e:?}"));
-            anyhow!("Failed to deploy contracts: {e}")
+            let e = anyhow!("Failed to deploy contracts: {e}");
+            notify_errors(observer, &to_deploy, &e);
+            e
         })?;
+    transaction_hashes.push(transaction_hash);
+    record_tx_hash(transaction_hash);
 
-    TransactionWaiter::new(transaction_hash, migrator.provider()).await?;
+    TransactionWaiter::new(transaction_hash, migrator.provider())
+        .await
+        .inspect_err(|e| notify_errors(observer, &to_deploy, e))?;
+
+    if let Some(observer) = observer {
+        for output in deploy_outputs.iter().flatten() {
+            observer.on_deploy(&output.tag, output.contract_address, transaction_hash);
+        }
+    }
 
     ui.print(format!("All contracts are deployed at: {transaction_hash:#x}\n"));
 
     Ok(deploy_outputs)
 }
 
+#[tracing::instrument(
+    name = "deploy",
+    skip_all,
+    fields(
+        resource = contract_id,
+        address = %format!("{:#x}", contract.contract_address),
+        tx_hash = field::Empty
+    )
+)]
 async fn deploy_contract<A>(
     contract: &ContractMigration,
     contract_id: &str,
@@ -926,6 +1812,7 @@ where
             }
 
             ui.print_hidden_sub(format!("Deploy transaction: {:#x}", val.transaction_hash));
+            record_tx_hash(val.transaction_hash);
 
             val.tag = Some(contract.diff.tag.clone());
             Ok(ContractDeploymentOutput::Output(val))
@@ -943,6 +1830,15 @@ where
     }
 }
 
+#[tracing::instrument(
+    name = "upgrade",
+    skip_all,
+    fields(
+        resource = contract_id,
+        address = %format!("{:#x}", contract.contract_address),
+        tx_hash = field::Empty
+    )
+)]
 async fn upgrade_contract<A>(
     contract: &ContractMigration,
     contract_id: &str,
@@ -975,6 +1871,7 @@ where
             }
 
             ui.print_hidden_sub(format!("Upgrade transaction: {:#x}", val.transaction_hash));
+            record_tx_hash(val.transaction_hash);
 
             Ok(ContractUpgradeOutput::Output(val))
         }
@@ -1081,9 +1978,8 @@ pub async fn print_strategy<P>(
 
             ui.print(op_name);
             ui.print_sub(format!("Class hash: {:#x}", c.diff.local_class_hash));
-            let salt = generate_salt(&get_name_from_tag(&c.diff.tag));
             let contract_address =
-                get_contract_address(salt, c.diff.base_class_hash, &[], world_address);
+                get_contract_address(c.salt, c.diff.base_class_hash, &[], world_address);
             ui.print_sub(format!("Contract address: {:#x}", contract_address));
         }
     }
@@ -1101,6 +1997,7 @@ pub async fn update_manifests_and_abis(
     world_address: Felt,
     migration_output: Option<MigrationOutput>,
     salt: &str,
+    salt_strategy: &SaltStrategy,
 ) -> Result<()> {
     let ui = ws.config().ui();
     ui.print_step(5, "✨", "Updating manifests...");
@@ -1148,15 +2045,16 @@ pub async fn update_manifests_and_abis(
                     .expect("contract got migrated, means it should be present here");
 
                 local.inner.base_class_hash = output.base_class_hash;
+                local.inner.address = Some(output.contract_address);
             }
         });
     }
 
     // compute contract addresses and update them in the manifest for contracts
-    // that have a base class hash set.
+    // that have a base class hash set, and weren't deployed by this migration.
     local_manifest.contracts.iter_mut().for_each(|contract| {
-        if contract.inner.base_class_hash != Felt::ZERO {
-            let salt = generate_salt(&get_name_from_tag(&contract.inner.tag));
+        if contract.inner.base_class_hash != Felt::ZERO && contract.inner.address.is_none() {
+            let salt = salt_strategy.salt(&contract.inner.tag);
             contract.inner.address = Some(get_contract_address(
                 salt,
                 contract.inner.base_class_hash,
@@ -1205,6 +2103,7 @@ where
             .iter()
             .flatten()
             .map(|m| m.tag.clone())
+            .chain(migration_output.unchanged_contracts.iter().cloned())
             .collect::<HashSet<_>>()
     }
 
@@ -1309,6 +2208,72 @@ where
     Ok((grant, revoke))
 }
 
+/// Finds the resources each contract of the diff should own according to its `owners`,
+/// and which it's not already the owner of.
+pub async fn find_ownership_diff<A>(
+    ui: &Ui,
+    world: &WorldContract<A>,
+    diff: &WorldDiff,
+    migration_output: Option<&MigrationOutput>,
+    default_namespace: &str,
+) -> Result<Vec<ResourceOwner>>
+where
+    A: ConnectedAccount + Sync + Send,
+    <A as Account>::SignError: 'static,
+{
+    let mut grant = vec![];
+
+    let recently_migrated = migration_output
+        .map(|output| {
+            output
+                .contracts
+                .iter()
+                .flatten()
+                .map(|m| m.tag.clone())
+                .chain(output.unchanged_contracts.iter().cloned())
+                .collect()
+        })
+        .unwrap_or_else(HashSet::new);
+
+    for c in &diff.contracts {
+        if c.local_owners.is_empty()
+            || (c.remote_class_hash.is_none() && !recently_migrated.contains(&c.tag))
+        {
+            continue;
+        }
+
+        let contract_address = utils::get_contract_address(world, &c.tag).await?;
+
+        for owned in &c.local_owners {
+            let owned =
+                if owned.contains(':') { owned.to_string() } else { format!("m:{}", owned) };
+
+            let resource = ResourceType::from_str(&owned)?;
+            let selector = get_resource_selector(ui, world, &resource, default_namespace)
+                .await
+                .with_context(|| format!("Failed to get selector for {}", owned))?;
+
+            if world.is_owner(&selector, &ContractAddress(contract_address)).call().await? {
+                continue;
+            }
+
+            grant.push(ResourceOwner { resource, owner: contract_address });
+        }
+
+        let owned = grant
+            .iter()
+            .filter(|ro| ro.owner == contract_address)
+            .map(|ro| ro.resource.clone())
+            .collect::<Vec<_>>();
+
+        if !owned.is_empty() {
+            ui.print_sub(format!("Granting ownership to {} for resources: {:?}", c.tag, owned));
+        }
+    }
+
+    Ok(grant)
+}
+
 // copy abi files from `base/abi` to `deployment/abi` and update abi path in
 // local_manifest
 async fn update_manifest_abis(
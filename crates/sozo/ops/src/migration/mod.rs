@@ -1,19 +1,18 @@
-use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, bail, Result};
 use dojo_utils::{TransactionExt, TransactionWaiter, TxnConfig};
-use dojo_world::contracts::naming::compute_selector_from_tag;
 use dojo_world::contracts::WorldContract;
-use dojo_world::manifest::{BASE_DIR, MANIFESTS_DIR, OVERLAYS_DIR};
-use dojo_world::metadata::get_default_namespace_from_ws;
+use dojo_world::manifest::{BASE_DIR, DEPLOYMENT_DIR, MANIFESTS_DIR, OVERLAYS_DIR};
+use dojo_world::metadata::{dojo_metadata_from_workspace, get_default_namespace_from_ws};
 use dojo_world::migration::world::WorldDiff;
 use dojo_world::migration::{DeployOutput, UpgradeOutput};
+use num_traits::ToPrimitive;
 use scarb::core::Workspace;
-use starknet::accounts::{Call, ConnectedAccount, ExecutionEncoding, SingleOwnerAccount};
+use starknet::accounts::{ConnectedAccount, ExecutionEncoding, SingleOwnerAccount};
 use starknet::core::types::{BlockId, BlockTag, Felt, InvokeTransactionResult};
 use starknet::core::utils::{cairo_short_string_to_felt, get_contract_address};
-use starknet::macros::selector;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{AnyProvider, JsonRpcClient, Provider};
 use starknet::signers::{LocalWallet, SigningKey};
@@ -21,18 +20,54 @@ use starknet_crypto::poseidon_hash_single;
 use url::Url;
 
 mod auto_auth;
+mod checkpoint;
+mod declaration_cache;
+mod dry_run;
 mod migrate;
+mod observer;
+mod plan;
+mod profile_diff;
+mod report;
+mod rollback;
+mod summary;
+mod transaction_log;
 pub mod ui;
 mod utils;
 
-pub use self::auto_auth::auto_authorize;
+pub use self::auto_auth::{auto_authorize, auto_revoke, AutoAuthOutput};
+pub use self::checkpoint::{migration_state_path, MigrationCheckpoint, MIGRATION_STATE_FILE};
+pub use self::declaration_cache::{
+    declaration_cache_path, DeclarationCache, DeclaredClass, DECLARATION_CACHE_FILE,
+};
+pub use self::dry_run::{execute_strategy_dry_run, DryRunAction, DryRunOutput, DryRunStep};
 use self::migrate::update_manifests_and_abis;
 pub use self::migrate::{
-    apply_diff, execute_strategy, find_authorization_diff, prepare_migration, print_strategy,
+    apply_diff, execute_strategy, execute_strategy_cancellable, find_authorization_diff,
+    find_ownership_diff, init_contract_calls, prepare_migration, print_strategy, resume_strategy,
     upload_metadata,
 };
+pub use self::observer::MigrationObserver;
+pub use self::plan::{plan_migration_from_remote, MigrationPlan};
+pub use self::profile_diff::{
+    diff_profiles, ClassHashDifference, ProfileClassHash, ProfileDiff, ProfileWorld,
+};
+use self::report::fee_and_resources;
+pub use self::report::{
+    write_migration_reports, write_transaction_log, MigrationReport, MigrationReportEntry,
+    MIGRATION_REPORT_FILE, MIGRATION_REPORT_NDJSON_FILE, TRANSACTION_LOG_FILE,
+};
+pub use self::rollback::rollback_world_upgrade;
+pub use self::summary::{ContractSummary, MigrationSummary, ModelSummary, TransactionSummary};
+pub use self::transaction_log::{
+    execute_strategy_with_log, replay_transaction_log, LoggedTransaction, LoggedTransactionKind,
+    TransactionLog,
+};
 use self::ui::MigrationUi;
 
+/// Name of the file, in the deployment directory, holding the metadata pinned by an upload
+/// which didn't complete.
+pub const PINNED_METADATA_FILE: &str = "pinned_metadata.json";
+
 #[derive(Debug, Default, Clone)]
 pub struct MigrationOutput {
     pub world_address: Felt,
@@ -44,12 +79,44 @@ pub struct MigrationOutput {
 
     pub models: Vec<String>,
     pub contracts: Vec<Option<ContractMigrationOutput>>,
+    // Tags of the contracts skipped because their class hash already matches the one registered
+    // in the world, they are neither declared nor deployed again.
+    pub unchanged_contracts: Vec<String>,
+
+    // Hashes of the transactions sent during the migration, in order.
+    pub transaction_hashes: Vec<Felt>,
+}
+
+impl MigrationOutput {
+    /// Returns the sum of the actual fees paid by the transactions of the migration.
+    ///
+    /// The receipt of each transaction is awaited, so the total is exact even if the migration
+    /// didn't wait for the transactions to be accepted.
+    pub async fn total_fee_spent<P>(&self, provider: P) -> Result<u128>
+    where
+        P: Provider + Send,
+    {
+        let mut total = 0u128;
+
+        for hash in &self.transaction_hashes {
+            let receipt = TransactionWaiter::new(*hash, &provider)
+                .with_interval(Duration::from_millis(200))
+                .await?;
+            let (fee, _) = fee_and_resources(&receipt.receipt);
+
+            let fee = fee.to_u128().ok_or_else(|| anyhow!("Fee of {hash:#x} overflows u128."))?;
+            total = total.checked_add(fee).ok_or_else(|| anyhow!("Total fee overflows u128."))?;
+        }
+
+        Ok(total)
+    }
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct ContractMigrationOutput {
     pub tag: String,
     pub contract_address: Felt,
+    pub class_hash: Felt,
     pub base_class_hash: Felt,
     pub was_upgraded: bool,
 }
@@ -117,6 +184,11 @@ async fn get_declarers_accounts<A: ConnectedAccount>(
     Ok(declarers)
 }
 
+/// Migrates the workspace to the world at `world_address`, or to the world derived from the seed
+/// `name` if not given.
+///
+/// If `fresh_world` is set, the remote state is ignored and a new world is deployed at the
+/// address derived from the seed, which fails if this address is already occupied.
 #[allow(clippy::too_many_arguments)]
 pub async fn migrate<A>(
     ws: &Workspace<'_>,
@@ -127,6 +199,7 @@ pub async fn migrate<A>(
     dry_run: bool,
     txn_config: TxnConfig,
     skip_manifests: Option<Vec<String>>,
+    fresh_world: bool,
 ) -> Result<Option<MigrationOutput>>
 where
     A: ConnectedAccount + Sync + Send + 'static,
@@ -149,6 +222,14 @@ where
 
     let default_namespace = get_default_namespace_from_ws(ws)?;
 
+    // A fresh world ignores the remote state, so the world can't be upgraded in place.
+    let world_address = if fresh_world {
+        ui.print("\nIgnoring the remote World state, a fresh World will be deployed.");
+        None
+    } else {
+        world_address
+    };
+
     // Load local and remote World manifests.
     let (local_manifest, remote_manifest) = utils::load_world_manifests(
         &manifest_base_dir,
@@ -179,6 +260,10 @@ where
         }
     }
 
+    if fresh_world {
+        migrate::check_address_available(&account, generated_world_address).await?;
+    }
+
     // Calculate diff between local and remote World manifests.
     ui.print_step(2, "🧰", "Evaluating Worlds diff...");
     let diff =
@@ -191,7 +276,15 @@ where
         ui.print("\n✨ No diffs found. Remote World is already up to date!");
     }
 
-    let strategy = prepare_migration(&target_dir, diff.clone(), name, world_address, &ui)?;
+    let migration_config = dojo_metadata_from_workspace(ws)?.migration;
+    let strategy = prepare_migration(
+        &target_dir,
+        diff.clone(),
+        name,
+        world_address,
+        migration_config.as_ref(),
+        &ui,
+    )?;
     // TODO: dry run can also show the diffs for things apart from world state
     // what new authorizations would be granted, if ipfs data would change or not,
     // etc...
@@ -211,6 +304,7 @@ where
             strategy.world_address,
             None,
             name,
+            &strategy.salt_strategy,
         )
         .await?;
 
@@ -222,7 +316,7 @@ where
         ui.print_sub(format!("Declarers: {}", declarers_len));
 
         let migration_output = if total_diffs != 0 {
-            match apply_diff(ws, &account, txn_config, &strategy, &declarers).await {
+            match apply_diff(ws, &account, txn_config.clone(), &strategy, &declarers).await {
                 Ok(migration_output) => Some(migration_output),
                 Err(e) => {
                     update_manifests_and_abis(
@@ -234,6 +328,7 @@ where
                         strategy.world_address,
                         None,
                         name,
+                        &strategy.salt_strategy,
                     )
                     .await?;
                     return Err(e)?;
@@ -252,6 +347,7 @@ where
             strategy.world_address,
             migration_output.clone(),
             name,
+            &strategy.salt_strategy,
         )
         .await?;
 
@@ -268,10 +364,18 @@ where
             &default_namespace,
         )
         .await?;
-
-        match auto_authorize(ws, &world, &txn_config, &default_namespace, &grant, &revoke).await {
-            Ok(()) => {
-                ui.print_sub("Auto authorize completed successfully");
+        let owners =
+            find_ownership_diff(&ui, &world, &diff, migration_output.as_ref(), &default_namespace)
+                .await?;
+
+        match auto_authorize(ws, &world, &txn_config, &default_namespace, &grant, &revoke, &owners)
+            .await
+        {
+            Ok(output) => {
+                ui.print_sub(format!(
+                    "Auto authorize completed successfully ({} granted, {} already granted)",
+                    output.granted, output.already_granted
+                ));
             }
             Err(e) => {
                 ui.print_sub(format!("Failed to auto authorize with error: {e}"));
@@ -283,37 +387,7 @@ where
             ui.print_step(7, "🏗️", "Initializing contracts...");
 
             // Run dojo inits now that everything is actually deployed and permissioned.
-            let mut init_calls = vec![];
-            for c in strategy.contracts {
-                let was_upgraded = migration_output
-                    .contracts
-                    .iter()
-                    .flatten()
-                    .find(|output| output.tag == c.diff.tag)
-                    .map(|output| output.was_upgraded)
-                    .unwrap_or(false);
-
-                if was_upgraded {
-                    continue;
-                }
-
-                let contract_selector = compute_selector_from_tag(&c.diff.tag);
-                let init_calldata: Vec<Felt> = c
-                    .diff
-                    .init_calldata
-                    .iter()
-                    .map(|s| Felt::from_str(s))
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                let mut calldata = vec![contract_selector, Felt::from(init_calldata.len())];
-                calldata.extend(init_calldata);
-
-                init_calls.push(Call {
-                    calldata,
-                    selector: selector!("init_contract"),
-                    to: strategy.world_address,
-                });
-            }
+            let init_calls = init_contract_calls(&strategy, migration_output)?;
 
             if !init_calls.is_empty() {
                 let InvokeTransactionResult { transaction_hash } = account
@@ -334,7 +408,30 @@ where
 
         if let Some(migration_output) = &migration_output {
             if !ws.config().offline() {
-                upload_metadata(ws, &account, migration_output.clone(), txn_config).await?;
+                // The metadata pinned by a previous failed upload are not uploaded again.
+                let pinned_path = manifest_dir.join(DEPLOYMENT_DIR).join(PINNED_METADATA_FILE);
+                let mut pinned = utils::load_pinned_metadata(&pinned_path)?;
+                let storage = migration_config
+                    .as_ref()
+                    .map(|config| config.storage.clone())
+                    .unwrap_or_default()
+                    .storage()?;
+
+                upload_metadata(
+                    ws,
+                    &account,
+                    migration_output.clone(),
+                    txn_config,
+                    storage.as_ref(),
+                    &mut pinned,
+                    Some(&pinned_path),
+                )
+                .await?;
+
+                // The upload is complete, nothing is left to resume.
+                if pinned_path.exists() {
+                    std::fs::remove_file(&pinned_path)?;
+                }
             }
         }
 
@@ -365,7 +462,6 @@ enum ContractDeploymentOutput {
     Output(DeployOutput),
 }
 
-#[allow(dead_code)]
 enum ContractUpgradeOutput {
     Output(UpgradeOutput),
 }
@@ -0,0 +1,25 @@
+use std::fmt::Display;
+
+use starknet::core::types::Felt;
+
+/// Receives the progress of a migration as it is executed, to stream it to a UI or a log.
+///
+/// All the methods do nothing by default. The elements are identified by their tag, or by
+/// `base` and `world` for the core contracts, and the transaction hash is given when a
+/// transaction has been sent for the step.
+pub trait MigrationObserver: Send + Sync {
+    /// The class of `element` is about to be declared.
+    fn on_declare_start(&self, _element: &str) {}
+
+    /// The class of `element` is declared, `transaction_hash` is `None` if it was already.
+    fn on_declare_done(&self, _element: &str, _transaction_hash: Option<Felt>) {}
+
+    /// `element` has been deployed, or upgraded, at `address`.
+    fn on_deploy(&self, _element: &str, _address: Felt, _transaction_hash: Felt) {}
+
+    /// The model `element` has been registered to the world.
+    fn on_register_model(&self, _element: &str, _transaction_hash: Felt) {}
+
+    /// The migration of `element` failed with `error`.
+    fn on_error(&self, _element: &str, _error: &dyn Display) {}
+}
@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Context, Result};
+use dojo_world::manifest::{
+    AbstractManifestError, DeploymentManifest, BASE_DIR, MANIFESTS_DIR, OVERLAYS_DIR,
+};
+use dojo_world::metadata::{dojo_metadata_from_workspace, get_default_namespace_from_ws};
+use dojo_world::migration::strategy::{
+    prepare_for_migration_with_salt_strategy, MigrationStrategy, SaltStrategy,
+};
+use dojo_world::migration::world::WorldDiff;
+use dojo_world::migration::MigrationType;
+use scarb::core::Workspace;
+use starknet::core::types::Felt;
+use starknet::core::utils::{cairo_short_string_to_felt, get_contract_address};
+use starknet::providers::Provider;
+
+use super::utils::load_local_manifest;
+
+/// The changes a migration would apply to a remote world, by resource tag.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MigrationPlan {
+    pub world_address: Felt,
+    /// Whether the world itself would be deployed.
+    pub deploy_world: bool,
+    /// Whether the world itself would be upgraded.
+    pub upgrade_world: bool,
+    /// Classes to declare, including the world and base classes.
+    pub declare: Vec<String>,
+    /// Contracts to deploy.
+    pub deploy: Vec<String>,
+    /// The address each contract to deploy would get, in the same order as `deploy`.
+    pub deploy_addresses: Vec<Felt>,
+    /// Contracts to upgrade.
+    pub upgrade: Vec<String>,
+    /// Models to register.
+    pub register: Vec<String>,
+}
+
+impl MigrationPlan {
+    /// Returns `true` if the remote world is already up to date.
+    pub fn is_empty(&self) -> bool {
+        !self.deploy_world
+            && !self.upgrade_world
+            && self.declare.is_empty()
+            && self.deploy.is_empty()
+            && self.upgrade.is_empty()
+            && self.register.is_empty()
+    }
+}
+
+/// Computes what migrating the workspace against the world at `world_address` would do.
+///
+/// This is read-only: the remote manifest is loaded and diffed against the local one, and the
+/// migration strategy is prepared from this diff like for a migration, but no transaction is
+/// sent. The contract salts are derived with `salt_strategy`, which must be the one the
+/// migration uses for the planned addresses to match.
+pub async fn plan_migration_from_remote<P>(
+    ws: &Workspace<'_>,
+    provider: P,
+    world_address: Felt,
+    salt_strategy: SaltStrategy,
+) -> Result<MigrationPlan>
+where
+    P: Provider + Sync + Send,
+{
+    // its path to a file so `parent` should never return `None`
+    let root_dir = ws.manifest_path().parent().unwrap().to_path_buf();
+
+    let profile_name =
+        ws.current_profile().expect("Scarb profile expected to be defined.").to_string();
+    let manifest_base_dir = root_dir.join(MANIFESTS_DIR).join(&profile_name).join(BASE_DIR);
+    let overlay_dir = root_dir.join(OVERLAYS_DIR).join(&profile_name);
+
+    let default_namespace = get_default_namespace_from_ws(ws)?;
+
+    let local_manifest = load_local_manifest(&manifest_base_dir, &overlay_dir, None)?;
+
+    let remote_manifest = match DeploymentManifest::load_from_remote(provider, world_address).await
+    {
+        Ok(manifest) => Some(manifest),
+        Err(AbstractManifestError::RemoteWorldNotFound) => None,
+        Err(e) => return Err(anyhow!("Failed to build remote World state: {e}")),
+    };
+
+    let diff = WorldDiff::compute(local_manifest, remote_manifest, &default_namespace)?;
+
+    let target_dir = ws.target_dir().path_existent()?.join(ws.config().profile().as_str());
+    let seed = dojo_metadata_from_workspace(ws)?.world.seed;
+    let seed = cairo_short_string_to_felt(&seed).with_context(|| "Failed to parse World name.")?;
+
+    let strategy = prepare_for_migration_with_salt_strategy(
+        Some(world_address),
+        seed,
+        &target_dir,
+        diff,
+        salt_strategy,
+    )
+    .with_context(|| "Problem preparing for migration.")?;
+
+    Ok(plan_from_strategy(&strategy))
+}
+
+/// Lists the items of the migration strategy, so that the plan can't diverge from what a
+/// migration does.
+fn plan_from_strategy(strategy: &MigrationStrategy) -> MigrationPlan {
+    let mut plan = MigrationPlan { world_address: strategy.world_address, ..Default::default() };
+
+    if let Some(world) = &strategy.world {
+        match world.migration_type() {
+            MigrationType::New => plan.deploy_world = true,
+            MigrationType::Update => plan.upgrade_world = true,
+        }
+
+        plan.declare.push(world.diff.tag.clone());
+    }
+
+    if let Some(base) = &strategy.base {
+        plan.declare.push(base.diff.tag.clone());
+    }
+
+    for model in &strategy.models {
+        plan.declare.push(model.diff.tag.clone());
+        plan.register.push(model.diff.tag.clone());
+    }
+
+    for contract in &strategy.contracts {
+        match contract.migration_type() {
+            MigrationType::New => {
+                plan.deploy.push(contract.diff.tag.clone());
+                plan.deploy_addresses.push(get_contract_address(
+                    contract.salt,
+                    contract.diff.base_class_hash,
+                    &[],
+                    strategy.world_address,
+                ));
+            }
+            MigrationType::Update => plan.upgrade.push(contract.diff.tag.clone()),
+        }
+
+        plan.declare.push(contract.diff.tag.clone());
+    }
+
+    plan
+}
@@ -0,0 +1,267 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use dojo_world::config::ProfileConfig;
+use dojo_world::manifest::{
+    AbstractManifestError, DeploymentManifest, BASE_DIR, MANIFESTS_DIR, OVERLAYS_DIR,
+};
+use dojo_world::migration::world::WorldDiff;
+use scarb::compiler::Profile;
+use scarb::core::Workspace;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::Felt;
+use starknet::providers::Provider;
+
+use super::utils::load_local_manifest;
+
+/// A profile to compare, and the remote world it is migrated to.
+#[derive(Debug, Clone)]
+pub struct ProfileWorld<P> {
+    pub profile: String,
+    pub provider: P,
+    /// The address of the remote world, `None` if it isn't deployed yet.
+    pub world_address: Option<Felt>,
+}
+
+/// The class hashes of a resource in a profile.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileClassHash {
+    #[serde_as(as = "UfeHex")]
+    pub local: Felt,
+    /// The class hash on the remote world of the profile, `None` if the resource isn't there.
+    #[serde_as(as = "Option<UfeHex>")]
+    pub remote: Option<Felt>,
+}
+
+/// A resource of both profiles whose local class hash differs between them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassHashDifference {
+    pub tag: String,
+    pub left: ProfileClassHash,
+    pub right: ProfileClassHash,
+}
+
+/// Side by side comparison of the migrations of two profiles, like `dev` and `release`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileDiff {
+    pub left_profile: String,
+    pub right_profile: String,
+    /// The world, base, models and contracts of both profiles with different class hashes.
+    pub class_hashes: Vec<ClassHashDifference>,
+    /// Models of the right profile missing from the left one.
+    pub added_models: Vec<String>,
+    /// Models of the left profile missing from the right one.
+    pub removed_models: Vec<String>,
+    /// Contracts only present in the left profile.
+    pub contracts_only_in_left: Vec<String>,
+    /// Contracts only present in the right profile.
+    pub contracts_only_in_right: Vec<String>,
+}
+
+impl ProfileDiff {
+    /// Returns `true` if both profiles would migrate the same classes.
+    pub fn is_empty(&self) -> bool {
+        self.class_hashes.is_empty()
+            && self.added_models.is_empty()
+            && self.removed_models.is_empty()
+            && self.contracts_only_in_left.is_empty()
+            && self.contracts_only_in_right.is_empty()
+    }
+}
+
+/// Compares the migrations of two profiles of the workspace, each of them being diffed against
+/// its own remote world.
+///
+/// This is read-only: the manifests of both profiles are loaded and diffed, but no artifact is
+/// read and no transaction is sent.
+pub async fn diff_profiles<P>(
+    ws: &Workspace<'_>,
+    left: &ProfileWorld<P>,
+    right: &ProfileWorld<P>,
+) -> Result<ProfileDiff>
+where
+    P: Provider + Sync + Send,
+{
+    let left_diff = profile_world_diff(ws, left).await?;
+    let right_diff = profile_world_diff(ws, right).await?;
+
+    Ok(compare_world_diffs(&left.profile, &left_diff, &right.profile, &right_diff))
+}
+
+async fn profile_world_diff<P>(ws: &Workspace<'_>, world: &ProfileWorld<P>) -> Result<WorldDiff>
+where
+    P: Provider + Sync + Send,
+{
+    // its path to a file so `parent` should never return `None`
+    let root_dir = ws.manifest_path().parent().unwrap().to_path_buf();
+
+    let manifest_base_dir = root_dir.join(MANIFESTS_DIR).join(&world.profile).join(BASE_DIR);
+    let overlay_dir = root_dir.join(OVERLAYS_DIR).join(&world.profile);
+
+    // The namespace is read from the configuration of the compared profile, which may differ
+    // from the one of the current profile.
+    let profile = Profile::new(world.profile.as_str().into())?;
+    let default_namespace = ProfileConfig::new(&root_dir, profile)?.namespace.default;
+
+    let local_manifest = load_local_manifest(&manifest_base_dir, &overlay_dir, None)?;
+
+    let remote_manifest = match world.world_address {
+        Some(world_address) => {
+            match DeploymentManifest::load_from_remote(&world.provider, world_address).await {
+                Ok(manifest) => Some(manifest),
+                Err(AbstractManifestError::RemoteWorldNotFound) => None,
+                Err(e) => return Err(anyhow!("Failed to build remote World state: {e}")),
+            }
+        }
+        None => None,
+    };
+
+    WorldDiff::compute(local_manifest, remote_manifest, &default_namespace)
+}
+
+fn compare_world_diffs(
+    left_profile: &str,
+    left: &WorldDiff,
+    right_profile: &str,
+    right: &WorldDiff,
+) -> ProfileDiff {
+    let mut diff = ProfileDiff {
+        left_profile: left_profile.to_string(),
+        right_profile: right_profile.to_string(),
+        ..Default::default()
+    };
+
+    let left_models = class_hashes_of_models(left);
+    let right_models = class_hashes_of_models(right);
+    let left_contracts = class_hashes_of_contracts(left);
+    let right_contracts = class_hashes_of_contracts(right);
+
+    diff.added_models =
+        right_models.keys().filter(|tag| !left_models.contains_key(*tag)).cloned().collect();
+    diff.removed_models =
+        left_models.keys().filter(|tag| !right_models.contains_key(*tag)).cloned().collect();
+    diff.contracts_only_in_left =
+        left_contracts.keys().filter(|tag| !right_contracts.contains_key(*tag)).cloned().collect();
+    diff.contracts_only_in_right =
+        right_contracts.keys().filter(|tag| !left_contracts.contains_key(*tag)).cloned().collect();
+
+    let core = [
+        (
+            left.world.tag.clone(),
+            class_hash(left.world.local_class_hash, left.world.remote_class_hash),
+            class_hash(right.world.local_class_hash, right.world.remote_class_hash),
+        ),
+        (
+            left.base.tag.clone(),
+            class_hash(left.base.local_class_hash, left.base.remote_class_hash),
+            class_hash(right.base.local_class_hash, right.base.remote_class_hash),
+        ),
+    ];
+
+    let resources = [(&left_models, &right_models), (&left_contracts, &right_contracts)]
+        .into_iter()
+        .flat_map(|(left, right)| {
+            left.iter().filter_map(|(tag, left)| {
+                right.get(tag).map(|right| (tag.clone(), left.clone(), right.clone()))
+            })
+        });
+
+    for (tag, left, right) in core.into_iter().chain(resources) {
+        if left.local != right.local {
+            diff.class_hashes.push(ClassHashDifference { tag, left, right });
+        }
+    }
+
+    diff
+}
+
+fn class_hash(local: Felt, remote: Option<Felt>) -> ProfileClassHash {
+    ProfileClassHash { local, remote }
+}
+
+fn class_hashes_of_models(diff: &WorldDiff) -> BTreeMap<String, ProfileClassHash> {
+    diff.models
+        .iter()
+        .map(|m| (m.tag.clone(), class_hash(m.local_class_hash, m.remote_class_hash)))
+        .collect()
+}
+
+fn class_hashes_of_contracts(diff: &WorldDiff) -> BTreeMap<String, ProfileClassHash> {
+    diff.contracts
+        .iter()
+        .map(|c| (c.tag.clone(), class_hash(c.local_class_hash, c.remote_class_hash)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_world::migration::class::ClassDiff;
+    use dojo_world::migration::contract::ContractDiff;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    fn model(tag: &str, local: Felt) -> ClassDiff {
+        ClassDiff { tag: tag.to_string(), local_class_hash: local, ..Default::default() }
+    }
+
+    fn contract(tag: &str, local: Felt, remote: Option<Felt>) -> ContractDiff {
+        ContractDiff {
+            tag: tag.to_string(),
+            local_class_hash: local,
+            remote_class_hash: remote,
+            ..Default::default()
+        }
+    }
+
+    fn world_diff(models: Vec<ClassDiff>, contracts: Vec<ContractDiff>) -> WorldDiff {
+        WorldDiff {
+            world: contract("dojo-world", felt!("0x1"), None),
+            base: model("dojo-base", felt!("0x2")),
+            models,
+            contracts,
+        }
+    }
+
+    #[test]
+    fn profiles_are_compared_side_by_side() {
+        let dev = world_diff(
+            vec![model("ns-position", felt!("0x10")), model("ns-moves", felt!("0x11"))],
+            vec![
+                contract("ns-actions", felt!("0x20"), Some(felt!("0x20"))),
+                contract("ns-debug", felt!("0x21"), None),
+            ],
+        );
+        let release = world_diff(
+            vec![model("ns-position", felt!("0x10")), model("ns-inventory", felt!("0x12"))],
+            vec![
+                contract("ns-actions", felt!("0x30"), Some(felt!("0x20"))),
+                contract("ns-shop", felt!("0x31"), None),
+            ],
+        );
+
+        let diff = compare_world_diffs("dev", &dev, "release", &release);
+
+        assert_eq!(diff.added_models, vec!["ns-inventory".to_string()]);
+        assert_eq!(diff.removed_models, vec!["ns-moves".to_string()]);
+        assert_eq!(diff.contracts_only_in_left, vec!["ns-debug".to_string()]);
+        assert_eq!(diff.contracts_only_in_right, vec!["ns-shop".to_string()]);
+        assert_eq!(
+            diff.class_hashes,
+            vec![ClassHashDifference {
+                tag: "ns-actions".to_string(),
+                left: class_hash(felt!("0x20"), Some(felt!("0x20"))),
+                right: class_hash(felt!("0x30"), Some(felt!("0x20"))),
+            }]
+        );
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["class_hashes"][0]["right"]["local"], "0x30");
+        assert_eq!(json["class_hashes"][0]["left"]["remote"], "0x20");
+
+        assert!(compare_world_diffs("dev", &dev, "dev", &dev).is_empty());
+    }
+}
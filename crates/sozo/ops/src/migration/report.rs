@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{ExecutionResources, Felt, TransactionReceipt};
+use starknet::providers::Provider;
+
+use super::TransactionLog;
+
+/// File name of the consolidated [`MigrationReport`].
+pub const MIGRATION_REPORT_FILE: &str = "migration_report.json";
+/// File name of the [`MigrationReport`] entries, written as one JSON object per line.
+pub const MIGRATION_REPORT_NDJSON_FILE: &str = "migration_report.ndjson";
+/// File name of the [`TransactionLog`] of the migration.
+pub const TRANSACTION_LOG_FILE: &str = "migration_transactions.json";
+
+/// Fees and data availability gas spent by the transactions of a migration.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub entries: Vec<MigrationReportEntry>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationReportEntry {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: Felt,
+    /// The actual fee paid for the transaction.
+    #[serde_as(as = "UfeHex")]
+    pub fee: Felt,
+    /// The L1 gas and L1 data gas consumed by the data availability of the transaction only,
+    /// the receipts don't report the L1 gas consumed by its execution.
+    pub data_availability_gas: u64,
+}
+
+impl MigrationReport {
+    /// Builds the report of the transactions of a [`TransactionLog`] from their receipts.
+    pub async fn from_transaction_log<P>(provider: &P, log: &TransactionLog) -> Result<Self>
+    where
+        P: Provider + Sync,
+    {
+        let mut entries = vec![];
+
+        for tx in &log.transactions {
+            let receipt = provider.get_transaction_receipt(tx.transaction_hash).await?;
+            let (fee, resources) = fee_and_resources(&receipt.receipt);
+            let da = &resources.data_resources.data_availability;
+
+            entries.push(MigrationReportEntry {
+                transaction_hash: tx.transaction_hash,
+                fee,
+                data_availability_gas: da.l1_gas + da.l1_data_gas,
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Merges the reports of several migration stages into a single one.
+    ///
+    /// Entries are kept in order, and a transaction present in more than one report is
+    /// only counted once.
+    pub fn merge(self, others: impl IntoIterator<Item = MigrationReport>) -> MigrationReport {
+        let mut seen = HashSet::new();
+
+        let entries = std::iter::once(self)
+            .chain(others)
+            .flat_map(|report| report.entries)
+            .filter(|entry| seen.insert(entry.transaction_hash))
+            .collect();
+
+        MigrationReport { entries }
+    }
+
+    pub fn total_fee(&self) -> Felt {
+        self.entries.iter().map(|e| e.fee).sum()
+    }
+
+    pub fn total_data_availability_gas(&self) -> u64 {
+        self.entries.iter().map(|e| e.data_availability_gas).sum()
+    }
+
+    /// Serializes the entries as newline delimited JSON, one entry per line.
+    pub fn to_ndjson(&self) -> Result<String> {
+        let mut ndjson = String::new();
+
+        for entry in &self.entries {
+            ndjson.push_str(&serde_json::to_string(entry)?);
+            ndjson.push('\n');
+        }
+
+        Ok(ndjson)
+    }
+}
+
+/// Writes the report, its NDJSON entries and the transaction log of a migration into `dir`,
+/// which is created if it doesn't exist.
+pub fn write_migration_reports(
+    dir: &Path,
+    report: &MigrationReport,
+    log: &TransactionLog,
+) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create report directory {}", dir.display()))?;
+
+    fs::write(dir.join(MIGRATION_REPORT_FILE), serde_json::to_string_pretty(report)?)?;
+    fs::write(dir.join(MIGRATION_REPORT_NDJSON_FILE), report.to_ndjson()?)?;
+    write_transaction_log(dir, log)
+}
+
+/// Writes the transaction log of a migration into `dir`, which is created if it doesn't exist.
+pub fn write_transaction_log(dir: &Path, log: &TransactionLog) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create report directory {}", dir.display()))?;
+
+    fs::write(dir.join(TRANSACTION_LOG_FILE), log.to_json()?)?;
+
+    Ok(())
+}
+
+pub(super) fn fee_and_resources(receipt: &TransactionReceipt) -> (Felt, &ExecutionResources) {
+    match receipt {
+        TransactionReceipt::Invoke(r) => (r.actual_fee.amount, &r.execution_resources),
+        TransactionReceipt::Declare(r) => (r.actual_fee.amount, &r.execution_resources),
+        TransactionReceipt::Deploy(r) => (r.actual_fee.amount, &r.execution_resources),
+        TransactionReceipt::DeployAccount(r) => (r.actual_fee.amount, &r.execution_resources),
+        TransactionReceipt::L1Handler(r) => (r.actual_fee.amount, &r.execution_resources),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+
+    fn entry(transaction_hash: Felt, fee: Felt, gas: u64) -> MigrationReportEntry {
+        MigrationReportEntry { transaction_hash, fee, data_availability_gas: gas }
+    }
+
+    #[test]
+    fn merge_sums_totals_without_double_counting() {
+        let declare = MigrationReport {
+            entries: vec![
+                entry(felt!("0x1"), felt!("0x10"), 100),
+                entry(felt!("0x2"), felt!("0x20"), 200),
+            ],
+        };
+
+        let deploy = MigrationReport {
+            entries: vec![
+                entry(felt!("0x2"), felt!("0x20"), 200),
+                entry(felt!("0x3"), felt!("0x30"), 300),
+            ],
+        };
+
+        let merged = declare.merge([deploy]);
+
+        let hashes = merged.entries.iter().map(|e| e.transaction_hash).collect::<Vec<_>>();
+        assert_eq!(hashes, vec![felt!("0x1"), felt!("0x2"), felt!("0x3")]);
+
+        assert_eq!(merged.total_fee(), felt!("0x60"));
+        assert_eq!(merged.total_data_availability_gas(), 600);
+    }
+
+    #[test]
+    fn to_ndjson_writes_one_entry_per_line() {
+        let report = MigrationReport {
+            entries: vec![
+                entry(felt!("0x1"), felt!("0x10"), 100),
+                entry(felt!("0x2"), felt!("0x20"), 200),
+            ],
+        };
+
+        let ndjson = report.to_ndjson().unwrap();
+        let entries = ndjson
+            .lines()
+            .map(|line| serde_json::from_str::<MigrationReportEntry>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(entries, report.entries);
+    }
+}
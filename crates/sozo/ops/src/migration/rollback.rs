@@ -0,0 +1,44 @@
+use anyhow::{anyhow, bail, Result};
+use dojo_utils::{TransactionExt, TransactionWaiter, TxnConfig};
+use dojo_world::contracts::WorldContract;
+use starknet::accounts::ConnectedAccount;
+use starknet::core::types::{BlockId, BlockTag, Felt, InvokeTransactionResult, StarknetError};
+use starknet::providers::{Provider, ProviderError};
+
+/// Upgrades the world back to `to_class_hash`, usually the class hash it had before its last
+/// upgrade.
+///
+/// The class must already be declared: this is checked before sending anything, since
+/// `replace_class` would otherwise revert on-chain.
+pub async fn rollback_world_upgrade<A>(
+    world: &WorldContract<A>,
+    to_class_hash: Felt,
+    txn_config: &TxnConfig,
+) -> Result<InvokeTransactionResult>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::SignError: 'static,
+{
+    let provider = world.account.provider();
+
+    match provider.get_class(BlockId::Tag(BlockTag::Pending), to_class_hash).await {
+        Ok(_) => {}
+        Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => {
+            bail!("Cannot roll back the world: class {to_class_hash:#x} is not declared.");
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let res = world
+        .account
+        .execute_v1(vec![world.upgrade_getcall(&to_class_hash.into())])
+        .send_with_cfg(txn_config)
+        .await
+        .map_err(|e| anyhow!("Failed to roll back world to class {to_class_hash:#x}: {e}"))?;
+
+    if txn_config.wait {
+        TransactionWaiter::new(res.transaction_hash, provider).await?;
+    }
+
+    Ok(res)
+}
@@ -0,0 +1,161 @@
+use anyhow::Result;
+use dojo_world::contracts::naming::compute_selector_from_tag;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{Felt, ReceiptBlock};
+use starknet::providers::Provider;
+
+use super::MigrationOutput;
+
+/// Machine readable summary of a [`MigrationOutput`], for tools consuming the result of a
+/// migration.
+#[serde_as]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationSummary {
+    #[serde_as(as = "UfeHex")]
+    pub world_address: Felt,
+    /// Whether all the steps of the migration completed.
+    pub full: bool,
+    pub contracts: Vec<ContractSummary>,
+    /// Tags of the contracts skipped because they are unchanged on-chain.
+    pub unchanged_contracts: Vec<String>,
+    pub models: Vec<ModelSummary>,
+    /// The transactions sent during the migration, in order.
+    pub transactions: Vec<TransactionSummary>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractSummary {
+    pub tag: String,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub address: Felt,
+    pub was_upgraded: bool,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelSummary {
+    pub tag: String,
+    #[serde_as(as = "UfeHex")]
+    pub selector: Felt,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionSummary {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: Felt,
+    /// The block the transaction was accepted in, only known if the migration waited for its
+    /// transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+}
+
+impl MigrationSummary {
+    /// Builds the summary of `output`.
+    ///
+    /// If `wait` is set, the transactions are expected to be accepted and the block number of
+    /// each of them is read from its receipt.
+    pub async fn from_output<P>(provider: &P, output: &MigrationOutput, wait: bool) -> Result<Self>
+    where
+        P: Provider + Sync,
+    {
+        let contracts = output
+            .contracts
+            .iter()
+            .flatten()
+            .map(|c| ContractSummary {
+                tag: c.tag.clone(),
+                class_hash: c.class_hash,
+                address: c.contract_address,
+                was_upgraded: c.was_upgraded,
+            })
+            .collect();
+
+        let models = output
+            .models
+            .iter()
+            .map(|tag| ModelSummary { tag: tag.clone(), selector: compute_selector_from_tag(tag) })
+            .collect();
+
+        let mut transactions = vec![];
+
+        for transaction_hash in &output.transaction_hashes {
+            let block_number = if wait {
+                match provider.get_transaction_receipt(transaction_hash).await?.block {
+                    ReceiptBlock::Pending => None,
+                    ReceiptBlock::Block { block_number, .. } => Some(block_number),
+                }
+            } else {
+                None
+            };
+
+            transactions
+                .push(TransactionSummary { transaction_hash: *transaction_hash, block_number });
+        }
+
+        Ok(Self {
+            world_address: output.world_address,
+            full: output.full,
+            contracts,
+            unchanged_contracts: output.unchanged_contracts.clone(),
+            models,
+            transactions,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+    use starknet::providers::jsonrpc::HttpTransport;
+    use starknet::providers::JsonRpcClient;
+    use url::Url;
+
+    use super::*;
+    use crate::migration::ContractMigrationOutput;
+
+    #[tokio::test]
+    async fn summary_without_wait_has_no_block_numbers() {
+        let output = MigrationOutput {
+            world_address: felt!("0x1"),
+            full: true,
+            models: vec!["ns-position".to_string()],
+            contracts: vec![
+                None,
+                Some(ContractMigrationOutput {
+                    tag: "ns-actions".to_string(),
+                    contract_address: felt!("0x2"),
+                    class_hash: felt!("0x3"),
+                    base_class_hash: felt!("0x4"),
+                    was_upgraded: false,
+                }),
+            ],
+            transaction_hashes: vec![felt!("0xa"), felt!("0xb")],
+            ..Default::default()
+        };
+
+        // Nothing is fetched without `wait`, the provider is never reached.
+        let provider =
+            JsonRpcClient::new(HttpTransport::new(Url::parse("http://localhost:1").unwrap()));
+        let summary = MigrationSummary::from_output(&provider, &output, false).await.unwrap();
+
+        assert_eq!(summary.contracts.len(), 1);
+        assert_eq!(summary.models[0].selector, compute_selector_from_tag("ns-position"));
+        assert!(summary.transactions.iter().all(|tx| tx.block_number.is_none()));
+
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json().unwrap()).unwrap();
+        assert_eq!(json["world_address"], "0x1");
+        assert_eq!(json["contracts"][0]["class_hash"], "0x3");
+        assert_eq!(json["contracts"][0]["address"], "0x2");
+        assert_eq!(json["transactions"][1], serde_json::json!({ "transaction_hash": "0xb" }));
+    }
+}
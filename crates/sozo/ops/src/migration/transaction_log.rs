@@ -0,0 +1,273 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use dojo_utils::{TransactionExt, TransactionWaiter, TxnConfig};
+use dojo_world::migration::strategy::MigrationStrategy;
+use scarb::core::Workspace;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::accounts::{Account, Call, ConnectedAccount, SingleOwnerAccount};
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{
+    BlockId, BlockTag, ContractClass, DeclareTransaction, Felt, FlattenedSierraClass,
+    InvokeTransaction, MaybePendingBlockWithTxs, Transaction,
+};
+use starknet::providers::{AnyProvider, Provider};
+use starknet::signers::LocalWallet;
+
+use super::{execute_strategy, MigrationOutput};
+
+/// The ordered list of transactions sent while executing a migration strategy.
+///
+/// The log is serialized as JSON and can be replayed against another sequencer
+/// with [`replay_transaction_log`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionLog {
+    pub transactions: Vec<LoggedTransaction>,
+}
+
+/// A single transaction of a [`TransactionLog`].
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoggedTransaction {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub sender_address: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub nonce: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub max_fee: Felt,
+    #[serde(flatten)]
+    pub kind: LoggedTransactionKind,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LoggedTransactionKind {
+    Invoke {
+        #[serde_as(as = "Vec<UfeHex>")]
+        calldata: Vec<Felt>,
+    },
+    Declare {
+        #[serde_as(as = "UfeHex")]
+        class_hash: Felt,
+        #[serde_as(as = "UfeHex")]
+        compiled_class_hash: Felt,
+        contract_class: FlattenedSierraClass,
+    },
+}
+
+impl TransactionLog {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Executes the migration strategy like [`execute_strategy`] and records, in order,
+/// every transaction sent by the migrator and the declarers.
+///
+/// Transactions are collected from the blocks mined during the migration, so the
+/// sequencer should not be shared with other senders using the same accounts. Failing to
+/// collect them doesn't fail the migration, which has already been sent, so the log is
+/// returned as a separate result.
+pub async fn execute_strategy_with_log<A>(
+    ws: &Workspace<'_>,
+    strategy: &MigrationStrategy,
+    migrator: A,
+    txn_config: TxnConfig,
+    declarers: &[SingleOwnerAccount<AnyProvider, LocalWallet>],
+) -> Result<(MigrationOutput, Result<TransactionLog>)>
+where
+    A: ConnectedAccount + Sync + Send,
+    A::Provider: Send,
+    A::SignError: 'static,
+{
+    let mut senders = declarers.iter().map(|d| d.address()).collect::<HashSet<_>>();
+    senders.insert(migrator.address());
+
+    let from_block = migrator.provider().block_number().await;
+
+    let migration_output =
+        execute_strategy(ws, strategy, &migrator, txn_config, declarers, None).await?;
+
+    let log = match from_block {
+        Ok(block) => collect_transaction_log(migrator.provider(), block + 1, &senders).await,
+        Err(e) => Err(e.into()),
+    };
+
+    Ok((migration_output, log))
+}
+
+/// Re-sends all the transactions of a [`TransactionLog`] in order, using the given account.
+///
+/// Each transaction is waited for before sending the next one. The transaction hashes
+/// of the replayed transactions are returned in the same order as the log.
+pub async fn replay_transaction_log<A>(account: A, log: &TransactionLog) -> Result<Vec<Felt>>
+where
+    A: ConnectedAccount + Sync + Send,
+{
+    let mut hashes = vec![];
+
+    for tx in &log.transactions {
+        let txn_config = TxnConfig { max_fee_raw: Some(tx.max_fee), ..Default::default() };
+
+        let transaction_hash = match &tx.kind {
+            LoggedTransactionKind::Invoke { calldata } => {
+                let calls = decode_execute_calldata(calldata)?;
+
+                account
+                    .execute_v1(calls)
+                    .send_with_cfg(&txn_config)
+                    .await
+                    .map_err(|e| {
+                        anyhow!("Failed to replay transaction {:#x}: {e}", tx.transaction_hash)
+                    })?
+                    .transaction_hash
+            }
+            LoggedTransactionKind::Declare { compiled_class_hash, contract_class, .. } => {
+                account
+                    .declare_v2(Arc::new(contract_class.clone()), *compiled_class_hash)
+                    .send_with_cfg(&txn_config)
+                    .await
+                    .map_err(|e| {
+                        anyhow!("Failed to replay transaction {:#x}: {e}", tx.transaction_hash)
+                    })?
+                    .transaction_hash
+            }
+        };
+
+        TransactionWaiter::new(transaction_hash, account.provider()).await?;
+        hashes.push(transaction_hash);
+    }
+
+    Ok(hashes)
+}
+
+async fn collect_transaction_log<P>(
+    provider: &P,
+    from_block: u64,
+    senders: &HashSet<Felt>,
+) -> Result<TransactionLog>
+where
+    P: Provider + Sync,
+{
+    let latest = provider.block_number().await?;
+
+    let mut blocks = (from_block..=latest).map(BlockId::Number).collect::<Vec<_>>();
+    blocks.push(BlockId::Tag(BlockTag::Pending));
+
+    let mut transactions = vec![];
+
+    for block in blocks {
+        let txs = match provider.get_block_with_txs(block).await? {
+            MaybePendingBlockWithTxs::Block(block) => block.transactions,
+            MaybePendingBlockWithTxs::PendingBlock(block) => block.transactions,
+        };
+
+        for tx in txs {
+            let logged = match tx {
+                Transaction::Invoke(InvokeTransaction::V1(tx)) => LoggedTransaction {
+                    transaction_hash: tx.transaction_hash,
+                    sender_address: tx.sender_address,
+                    nonce: tx.nonce,
+                    max_fee: tx.max_fee,
+                    kind: LoggedTransactionKind::Invoke { calldata: tx.calldata },
+                },
+                Transaction::Declare(DeclareTransaction::V2(tx)) => {
+                    let contract_class = match provider.get_class(block, tx.class_hash).await? {
+                        ContractClass::Sierra(class) => class,
+                        ContractClass::Legacy(_) => {
+                            bail!("Unexpected legacy class {:#x} in migration.", tx.class_hash)
+                        }
+                    };
+
+                    LoggedTransaction {
+                        transaction_hash: tx.transaction_hash,
+                        sender_address: tx.sender_address,
+                        nonce: tx.nonce,
+                        max_fee: tx.max_fee,
+                        kind: LoggedTransactionKind::Declare {
+                            class_hash: tx.class_hash,
+                            compiled_class_hash: tx.compiled_class_hash,
+                            contract_class,
+                        },
+                    }
+                }
+                _ => continue,
+            };
+
+            if senders.contains(&logged.sender_address) {
+                transactions.push(logged);
+            }
+        }
+    }
+
+    Ok(TransactionLog { transactions })
+}
+
+/// Decodes the `__execute__` calldata of an account using the new execution encoding
+/// back into the list of calls.
+fn decode_execute_calldata(calldata: &[Felt]) -> Result<Vec<Call>> {
+    let mut iter = calldata.iter();
+    let mut next = || iter.next().copied().ok_or_else(|| anyhow!("Truncated execute calldata."));
+    let to_len = |felt: Felt| {
+        usize::try_from(felt).map_err(|_| anyhow!("Invalid length in execute calldata: {felt:#x}"))
+    };
+
+    let calls_len = to_len(next()?)?;
+    let mut calls = Vec::with_capacity(calls_len);
+
+    for _ in 0..calls_len {
+        let to = next()?;
+        let selector = next()?;
+        let len = to_len(next()?)?;
+        let calldata = (0..len).map(|_| next()).collect::<Result<Vec<_>>>()?;
+
+        calls.push(Call { to, selector, calldata });
+    }
+
+    Ok(calls)
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+
+    #[test]
+    fn decode_execute_calldata_works() {
+        let calldata = vec![
+            felt!("0x2"),
+            felt!("0x1"),
+            felt!("0xa"),
+            felt!("0x2"),
+            felt!("0x5"),
+            felt!("0x6"),
+            felt!("0x2"),
+            felt!("0xb"),
+            felt!("0x0"),
+        ];
+
+        let calls = decode_execute_calldata(&calldata).unwrap();
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, felt!("0x1"));
+        assert_eq!(calls[0].selector, felt!("0xa"));
+        assert_eq!(calls[0].calldata, vec![felt!("0x5"), felt!("0x6")]);
+        assert_eq!(calls[1].to, felt!("0x2"));
+        assert_eq!(calls[1].calldata, vec![]);
+    }
+
+    #[test]
+    fn decode_execute_calldata_fails_on_truncated_input() {
+        assert!(decode_execute_calldata(&[felt!("0x1"), felt!("0x1")]).is_err());
+    }
+}
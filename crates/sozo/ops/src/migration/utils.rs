@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use anyhow::{anyhow, Context, Result};
 use camino::Utf8PathBuf;
@@ -8,6 +10,7 @@ use dojo_world::manifest::{
     AbstractManifestError, BaseManifest, DeploymentManifest, OverlayManifest,
 };
 use dojo_world::migration::world::WorldDiff;
+use dojo_world::uri::Uri;
 use itertools::Itertools;
 use scarb_ui::Ui;
 use starknet::accounts::{Account, ConnectedAccount};
@@ -33,20 +36,7 @@ where
 {
     ui.print_step(1, "🌎", "Building World state...");
 
-    let mut local_manifest = BaseManifest::load_from_path(manifest_dir)
-        .map_err(|e| anyhow!("Fail to load local manifest file: {e}."))?;
-
-    if let Some(skip_manifests) = skip_migration {
-        local_manifest.remove_tags(skip_manifests);
-    }
-
-    if overlay_dir.exists() {
-        let overlay_manifest = OverlayManifest::load_from_path(overlay_dir, &local_manifest)
-            .map_err(|e| anyhow!("Fail to load overlay manifest file: {e}."))?;
-
-        // merge user defined changes to base manifest
-        local_manifest.merge(overlay_manifest);
-    }
+    let local_manifest = load_local_manifest(manifest_dir, overlay_dir, skip_migration)?;
 
     let remote_manifest = if let Some(address) = world_address {
         match DeploymentManifest::load_from_remote(account.provider(), address).await {
@@ -71,6 +61,68 @@ where
     Ok((local_manifest, remote_manifest))
 }
 
+/// Loads the `BaseManifest` from filesystem, with the overlays merged in and the skipped
+/// manifests removed.
+pub(super) fn load_local_manifest(
+    manifest_dir: &Utf8PathBuf,
+    overlay_dir: &Utf8PathBuf,
+    skip_migration: Option<Vec<String>>,
+) -> Result<BaseManifest> {
+    let mut local_manifest = BaseManifest::load_from_path(manifest_dir)
+        .map_err(|e| anyhow!("Fail to load local manifest file: {e}."))?;
+
+    if let Some(skip_manifests) = skip_migration {
+        local_manifest.remove_tags(skip_manifests);
+    }
+
+    if overlay_dir.exists() {
+        let overlay_manifest = OverlayManifest::load_from_path(overlay_dir, &local_manifest)
+            .map_err(|e| anyhow!("Fail to load overlay manifest file: {e}."))?;
+
+        // merge user defined changes to base manifest
+        local_manifest.merge(overlay_manifest);
+    }
+
+    Ok(local_manifest)
+}
+
+/// Loads the metadata pinned by a previous run of `upload_metadata` which didn't complete.
+/// Empty if there is no such run.
+pub(super) fn load_pinned_metadata(path: &Utf8PathBuf) -> Result<HashMap<String, Uri>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read pinned metadata at {path}"))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid pinned metadata at {path}"))
+}
+
+/// Saves the pinned metadata, so that a failed `upload_metadata` can be resumed.
+pub(super) fn save_pinned_metadata(
+    path: &Utf8PathBuf,
+    pinned: &HashMap<String, Uri>,
+) -> Result<()> {
+    write_atomically(path, &serde_json::to_string_pretty(pinned)?)
+        .with_context(|| format!("Failed to write pinned metadata at {path}"))
+}
+
+/// Writes `contents` to `path` through a temporary file renamed over it, so that a concurrent
+/// reader never sees a partially written file.
+pub(super) fn write_atomically(path: &Utf8PathBuf, contents: &str) -> std::io::Result<()> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let tmp_path = path.with_extension(format!("{}.{id}.tmp", std::process::id()));
+
+    fs::write(&tmp_path, contents)?;
+    fs::rename(&tmp_path, path)
+}
+
 pub async fn generate_resource_map<A>(
     ui: &Ui,
     world: &WorldContract<A>,
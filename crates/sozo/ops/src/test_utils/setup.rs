@@ -1,3 +1,5 @@
+use std::fs;
+
 use anyhow::Result;
 use dojo_test_utils::compiler::CompilerTestSetup;
 use dojo_test_utils::migration::prepare_migration_with_world_and_seed;
@@ -53,7 +55,16 @@ pub async fn get_declarers_from_sequencer(
 /// A [`Config`] object loaded from the spawn-and-moves Scarb.toml file.
 pub fn load_config() -> Config {
     let setup = CompilerTestSetup::from_examples("../../dojo-core", "../../../examples/");
-    setup.build_test_config("spawn-and-move", Profile::DEV)
+    let config = setup.build_test_config("spawn-and-move", Profile::DEV);
+
+    // The target directory is copied from the examples, the state left by a local migration is
+    // removed so that each test starts from an empty target directory of its own.
+    let profile_dir =
+        config.manifest_path().parent().unwrap().join("target").join(config.profile().as_str());
+    let _ = fs::remove_file(profile_dir.join(migration::DECLARATION_CACHE_FILE));
+    let _ = fs::remove_file(profile_dir.join(migration::MIGRATION_STATE_FILE));
+
+    config
 }
 
 /// Setups the workspace for the spawn-and-moves project.
@@ -138,6 +149,7 @@ pub async fn setup(
         &account,
         TxnConfig { wait: true, ..Default::default() },
         &accounts,
+        None,
     )
     .await?;
 
@@ -155,6 +167,7 @@ pub async fn setup(
         &default_namespace,
         &grant,
         &revoke,
+        &[],
     )
     .await?;
 
@@ -3,11 +3,12 @@ use std::str::FromStr;
 use dojo_test_utils::migration::copy_spawn_and_move_db;
 use dojo_utils::TxnConfig;
 use dojo_world::contracts::naming::compute_selector_from_tag;
-use dojo_world::contracts::world::WorldContract;
+use dojo_world::contracts::world::{WorldContract, WorldContractReader};
 use katana_runner::{KatanaRunner, KatanaRunnerConfig};
 use scarb_ui::{OutputFormat, Ui, Verbosity};
 use starknet::accounts::{Account, ConnectedAccount};
 use starknet::core::types::Felt;
+use starknet::macros::felt;
 
 use crate::auth::{self, ResourceOwner, ResourceType, ResourceWriter};
 use crate::execute;
@@ -181,6 +182,40 @@ async fn auth_revoke_owner_ok() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn contracts_writing_lists_granted_writers() {
+    let move_model_selector = compute_selector_from_tag(MOVE_MODEL_TAG);
+
+    let config = KatanaRunnerConfig { n_accounts: 10, ..Default::default() }
+        .with_db_dir(copy_spawn_and_move_db().as_str());
+
+    let sequencer = KatanaRunner::new_with_config(config).expect("Failed to start runner.");
+
+    let world = setup::setup_with_world(&sequencer).await.unwrap();
+
+    let writers = [felt!("0x1111"), felt!("0x2222")];
+
+    auth::grant_writer(
+        &Ui::new(Verbosity::Normal, OutputFormat::Text),
+        &world,
+        &writers.map(|writer| ResourceWriter {
+            resource: ResourceType::from_str(&format!("model:{MOVE_MODEL_TAG}")).unwrap(),
+            tag_or_address: format!("{writer:#x}"),
+        }),
+        TxnConfig { wait: true, ..Default::default() },
+        DEFAULT_NAMESPACE,
+    )
+    .await
+    .unwrap();
+
+    let world_reader = WorldContractReader::new(world.address, world.account.provider());
+    let contracts = world_reader.contracts_writing(move_model_selector).await.unwrap();
+
+    for writer in writers {
+        assert!(contracts.contains(&writer.into()), "{writer:#x} should be a writer");
+    }
+}
+
 /// Executes the `spawn` system on `actions` contract.
 ///
 /// # Returns
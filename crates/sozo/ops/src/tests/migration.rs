@@ -1,38 +1,105 @@
 #![allow(dead_code)]
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::fs;
+use std::path::PathBuf;
 use std::str::{self, FromStr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use assert_fs::TempDir;
 use cainome::cairo_serde::ContractAddress;
-use camino::Utf8Path;
+use camino::{Utf8Path, Utf8PathBuf};
 use dojo_test_utils::migration::prepare_migration_with_world_and_seed;
-use dojo_utils::TxnConfig;
-use dojo_world::contracts::naming::{compute_bytearray_hash, compute_selector_from_tag};
+use dojo_utils::{TransactionExt, TransactionWaiter, TxnConfig};
+use dojo_world::contracts::abi::world::Resource;
+use dojo_world::contracts::naming::{
+    compute_bytearray_hash, compute_selector_from_tag, get_name_from_tag,
+};
+use dojo_world::contracts::world::DEFAULT_LAYOUT_VERSION;
 use dojo_world::contracts::{WorldContract, WorldContractReader};
 use dojo_world::manifest::{
     BaseManifest, DeploymentManifest, OverlayManifest, BASE_DIR, MANIFESTS_DIR, OVERLAYS_DIR,
     WORLD_CONTRACT_TAG,
 };
 use dojo_world::metadata::{
-    dojo_metadata_from_workspace, get_default_namespace_from_ws, ArtifactMetadata, DojoMetadata,
-    WorldMetadata, IPFS_CLIENT_URL, IPFS_PASSWORD, IPFS_USERNAME,
+    decode_artifact, dojo_metadata_from_workspace, get_default_namespace_from_ws, ArtifactMetadata,
+    CidVersion, ContentEncoding, DojoMetadata, IpfsStorage, MetadataStorage, ResourceMetadata,
+    UploadConfig, WorldMetadata, ARWEAVE_GATEWAY_URL, IPFS_CLIENT_URL, IPFS_PASSWORD,
+    IPFS_USERNAME,
+};
+use dojo_world::migration::strategy::{
+    check_artifact_freshness, generate_salt, prepare_for_migration,
+    prepare_for_migration_with_salt_strategy, MigrationMetadata, MigrationStrategy, SaltStrategy,
 };
-use dojo_world::migration::strategy::{prepare_for_migration, MigrationMetadata};
 use dojo_world::migration::world::WorldDiff;
+use dojo_world::migration::{
+    get_compiled_class_hash, get_deployed_address_from_receipt, read_class,
+    wait_for_class_declared, wait_for_declarations, Declarable, MigrationError,
+};
 use dojo_world::uri::Uri;
 use futures::TryStreamExt;
 use ipfs_api_backend_hyper::{HyperBackend, IpfsApi, IpfsClient, TryFromUri};
 use katana_runner::{KatanaRunner, KatanaRunnerConfig};
-use starknet::core::types::{BlockId, BlockTag, Felt};
+use scarb::core::{Config, Workspace};
+use starknet::accounts::{Account, ConnectedAccount, SingleOwnerAccount};
+use starknet::core::types::{BlockId, BlockTag, Felt, TransactionReceipt};
+use starknet::core::utils::{cairo_short_string_to_felt, get_contract_address};
 use starknet::macros::felt;
 use starknet::providers::jsonrpc::HttpTransport;
-use starknet::providers::JsonRpcClient;
+use starknet::providers::{AnyProvider, JsonRpcClient, Provider};
+use starknet::signers::LocalWallet;
+use tokio_util::sync::CancellationToken;
+use tracing::field::{Field, Visit};
+use tracing::instrument::WithSubscriber;
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
 
 use crate::auth::ResourceType;
 use crate::migration::{
-    auto_authorize, execute_strategy, find_authorization_diff, upload_metadata,
+    apply_diff, auto_authorize, auto_revoke, declaration_cache_path, execute_strategy,
+    execute_strategy_cancellable, execute_strategy_dry_run, execute_strategy_with_log,
+    find_authorization_diff, find_ownership_diff, init_contract_calls, migration_state_path,
+    plan_migration_from_remote, replay_transaction_log, resume_strategy, rollback_world_upgrade,
+    upload_metadata, ContractMigrationOutput, DeclarationCache, DryRunAction, MigrationCheckpoint,
+    MigrationObserver, MigrationOutput, MigrationReport, MigrationReportEntry, TransactionLog,
+    MIGRATION_REPORT_FILE, MIGRATION_REPORT_NDJSON_FILE, TRANSACTION_LOG_FILE,
 };
 use crate::test_utils::setup;
 use crate::utils::get_contract_address_from_reader;
 
+/// A Katana instance with the spawn-and-move migration ready to be executed against it.
+struct MigrationTest<'a> {
+    sequencer: KatanaRunner,
+    account: SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
+    migration: MigrationStrategy,
+    ws: Workspace<'a>,
+    declarers: Vec<SingleOwnerAccount<AnyProvider, LocalWallet>>,
+    diff: WorldDiff,
+}
+
+impl<'a> MigrationTest<'a> {
+    async fn setup(config: &'a Config) -> Self {
+        let ws = setup::setup_ws(config);
+        let (migration, diff) = setup::setup_migration(config, "dojo_examples").unwrap();
+
+        let sequencer = KatanaRunner::new_with_config(KatanaRunnerConfig {
+            n_accounts: 10,
+            ..Default::default()
+        })
+        .expect("Failed to start runner.");
+
+        let mut account = sequencer.account(0);
+        account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+        let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+
+        Self { sequencer, account, migration, ws, declarers, diff }
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn default_migrate_no_dry_run() {
     let config = setup::load_config();
@@ -57,6 +124,7 @@ async fn default_migrate_no_dry_run() {
         false,
         TxnConfig::init_wait(),
         None,
+        false,
     )
     .await
     .is_ok();
@@ -65,20 +133,12 @@ async fn default_migrate_no_dry_run() {
 #[tokio::test(flavor = "multi_thread")]
 async fn migrate_with_auto_mine() {
     let config = setup::load_config();
-    let ws = setup::setup_ws(&config);
-
-    let (migration, _) = setup::setup_migration(&config, "dojo_examples").unwrap();
+    let MigrationTest { sequencer: _sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
 
-    let sequencer =
-        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
-            .expect("Fail to start runner");
-
-    let mut account = sequencer.account(0);
-    account.set_block_id(BlockId::Tag(BlockTag::Pending));
-
-    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
-
-    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers).await.unwrap();
+    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+        .await
+        .unwrap();
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -100,349 +160,2340 @@ async fn migrate_with_block_time() {
 
     let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
 
-    execute_strategy(&ws, &migration, &account, TxnConfig::default(), &declarers).await.unwrap();
+    execute_strategy(&ws, &migration, &account, TxnConfig::default(), &declarers, None)
+        .await
+        .unwrap();
 }
 
-#[tokio::test]
-async fn metadata_calculated_properly() {
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_declarations_match_sequential_migration() {
     let config = setup::load_config();
     let ws = setup::setup_ws(&config);
 
-    let base = config.manifest_path().parent().unwrap();
-    let target_dir = format!("{}/target/dev", base);
+    let (migration, _) = setup::setup_migration(&config, "dojo_examples").unwrap();
 
-    let profile_name = ws.current_profile().unwrap().to_string();
+    let mut outputs = vec![];
+
+    for max_concurrent_declarations in [None, Some(4)] {
+        let sequencer = KatanaRunner::new_with_config(KatanaRunnerConfig {
+            n_accounts: 10,
+            ..Default::default()
+        })
+        .expect("Failed to start runner.");
+
+        let mut account = sequencer.account(0);
+        account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+        let txn_config = TxnConfig { max_concurrent_declarations, ..TxnConfig::init_wait() };
+        let output =
+            execute_strategy(&ws, &migration, &account, txn_config, &[], None).await.unwrap();
+        assert!(output.full);
+
+        let classes = migration
+            .models
+            .iter()
+            .map(|m| (&m.diff.tag, m.diff.local_class_hash))
+            .chain(migration.contracts.iter().map(|c| (&c.diff.tag, c.diff.local_class_hash)));
+
+        for (tag, class_hash) in classes {
+            sequencer
+                .provider()
+                .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
+                .await
+                .unwrap_or_else(|_| panic!("{tag} is not declared"));
+        }
 
-    let mut manifest = BaseManifest::load_from_path(
-        &base.to_path_buf().join(MANIFESTS_DIR).join(&profile_name).join(BASE_DIR),
+        outputs.push(output);
+    }
+
+    // The order of the outputs doesn't depend on the order the declarations completed in.
+    assert_eq!(outputs[0].models, outputs[1].models);
+    assert_eq!(
+        outputs[0]
+            .contracts
+            .iter()
+            .flatten()
+            .map(|c| (&c.tag, c.contract_address))
+            .collect::<Vec<_>>(),
+        outputs[1]
+            .contracts
+            .iter()
+            .flatten()
+            .map(|c| (&c.tag, c.contract_address))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failing_declaration_mid_batch_sends_nothing() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, .. } =
+        MigrationTest::setup(&config).await;
+    assert!(migration.models.len() > 2);
+
+    // An artifact in the middle of the models to declare which can be read, but whose Sierra
+    // program can't be compiled when preparing its declaration.
+    let mut artifact: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&migration.models[1].artifact_path).unwrap())
+            .unwrap();
+    artifact["sierra_program"] = serde_json::json!(["0x1"]);
+
+    let artifacts = TempDir::new().unwrap();
+    let broken = artifacts.path().join("broken.json");
+    fs::write(&broken, artifact.to_string()).unwrap();
+
+    let mut broken_migration = migration.clone();
+    broken_migration.models[1].artifact_path = broken;
+
+    let txn_config = TxnConfig { max_concurrent_declarations: Some(4), ..TxnConfig::init_wait() };
+    let result =
+        execute_strategy(&ws, &broken_migration, &account, txn_config.clone(), &[], None).await;
+    assert!(result.is_err());
+
+    // The classes are all checked before any nonce is assigned, so none of them was sent.
+    for model in &migration.models {
+        let class = sequencer
+            .provider()
+            .get_class(BlockId::Tag(BlockTag::Pending), model.diff.local_class_hash)
+            .await;
+        assert!(class.is_err(), "{} was declared", model.diff.tag);
+    }
+
+    // No nonce was skipped, so the same account can go on with the migration.
+    let output = execute_strategy(&ws, &migration, &account, txn_config, &[], None).await.unwrap();
+    assert!(output.full);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migration_reports_are_written_to_report_dir() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
+
+    let report_dir = TempDir::new().unwrap();
+    let txn_config = TxnConfig {
+        wait: true,
+        report_dir: Some(report_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+
+    let output = apply_diff(&ws, &account, txn_config, &migration, &declarers).await.unwrap();
+    let read = |file: &str| fs::read_to_string(report_dir.path().join(file)).unwrap();
+
+    // The log holds every transaction sent by the migration.
+    let log = TransactionLog::from_json(&read(TRANSACTION_LOG_FILE)).unwrap();
+    let logged = log.transactions.iter().map(|tx| tx.transaction_hash).collect::<HashSet<_>>();
+    assert_eq!(logged, output.transaction_hashes.iter().copied().collect::<HashSet<_>>());
+
+    // The report has one entry per logged transaction, in the same order.
+    let report: MigrationReport = serde_json::from_str(&read(MIGRATION_REPORT_FILE)).unwrap();
+    let reported = report.entries.iter().map(|e| e.transaction_hash).collect::<Vec<_>>();
+    let expected = log.transactions.iter().map(|tx| tx.transaction_hash).collect::<Vec<_>>();
+    assert_eq!(reported, expected);
+
+    for entry in &report.entries {
+        let receipt =
+            sequencer.provider().get_transaction_receipt(entry.transaction_hash).await.unwrap();
+        let fee = match receipt.receipt {
+            TransactionReceipt::Invoke(r) => r.actual_fee.amount,
+            TransactionReceipt::Declare(r) => r.actual_fee.amount,
+            TransactionReceipt::Deploy(r) => r.actual_fee.amount,
+            TransactionReceipt::DeployAccount(r) => r.actual_fee.amount,
+            TransactionReceipt::L1Handler(r) => r.actual_fee.amount,
+        };
+        assert_eq!(entry.fee, fee);
+        assert_ne!(entry.fee, Felt::ZERO);
+    }
+
+    // The NDJSON file holds the same entries, one per line.
+    let ndjson = read(MIGRATION_REPORT_NDJSON_FILE)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect::<Vec<MigrationReportEntry>>();
+    assert_eq!(ndjson, report.entries);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn plan_from_remote_matches_executed_migration() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
+
+    let plan = plan_migration_from_remote(
+        &ws,
+        sequencer.provider(),
+        migration.world_address,
+        SaltStrategy::default(),
     )
+    .await
     .unwrap();
 
-    let overlay_dir = base.join(OVERLAYS_DIR).join(&profile_name);
-    if overlay_dir.exists() {
-        let overlay_manifest = OverlayManifest::load_from_path(&overlay_dir, &manifest).unwrap();
-        manifest.merge(overlay_manifest);
-    }
+    assert!(plan.deploy_world);
+    assert!(!plan.upgrade_world);
 
-    let world = WorldDiff::compute(manifest, None, "dojo-test").unwrap();
+    let output =
+        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+            .await
+            .unwrap();
 
-    let migration = prepare_for_migration(
+    // the strategy may reorder items to satisfy dependencies, only the sets are compared.
+    let mut planned = plan.register.clone();
+    let mut registered = output.models.clone();
+    planned.sort();
+    registered.sort();
+    assert_eq!(planned, registered);
+
+    let mut planned = plan.deploy.clone();
+    let mut deployed = output.contracts.iter().flatten().map(|c| c.tag.clone()).collect::<Vec<_>>();
+    planned.sort();
+    deployed.sort();
+    assert_eq!(planned, deployed);
+    assert!(plan.upgrade.is_empty());
+
+    // once migrated, there is nothing left to do.
+    let plan = plan_migration_from_remote(
+        &ws,
+        sequencer.provider(),
+        migration.world_address,
+        SaltStrategy::default(),
+    )
+    .await
+    .unwrap();
+    assert!(plan.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn plan_from_remote_uses_salt_strategy() {
+    let config = setup::load_config();
+    let manifest_path = config.manifest_path();
+    let target_dir = Utf8PathBuf::from(format!("{}/target/dev", manifest_path.parent().unwrap()));
+
+    let MigrationTest { sequencer, account, ws, diff, .. } = MigrationTest::setup(&config).await;
+    let migration = prepare_for_migration_with_salt_strategy(
         None,
-        felt!("0x12345"),
-        &Utf8Path::new(&target_dir).to_path_buf(),
-        world,
+        cairo_short_string_to_felt("dojo_examples_ns").unwrap(),
+        &target_dir,
+        diff,
+        SaltStrategy::FromNamespacedName,
     )
     .unwrap();
 
-    // verifies that key name and actual item name are same
-    for (key, value) in migration.metadata.iter() {
-        match value {
-            MigrationMetadata::Contract(c) => {
-                assert_eq!(key, &c.tag);
-            }
+    let plan = plan_migration_from_remote(
+        &ws,
+        sequencer.provider(),
+        migration.world_address,
+        SaltStrategy::FromNamespacedName,
+    )
+    .await
+    .unwrap();
+    let default_plan = plan_migration_from_remote(
+        &ws,
+        sequencer.provider(),
+        migration.world_address,
+        SaltStrategy::default(),
+    )
+    .await
+    .unwrap();
+
+    let output = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None)
+        .await
+        .unwrap();
+
+    assert_eq!(plan.deploy, default_plan.deploy);
+
+    for (tag, address) in plan.deploy.iter().zip(&plan.deploy_addresses) {
+        let contract = output.contracts.iter().flatten().find(|c| &c.tag == tag).unwrap();
+        assert_eq!(contract.contract_address, *address, "{tag} deployed at another address");
+    }
+
+    assert_ne!(plan.deploy_addresses, default_plan.deploy_addresses);
+}
+
+/// Cancels the migration once the given element is deployed.
+struct CancelOnDeploy {
+    element: &'static str,
+    cancel: CancellationToken,
+}
+
+impl MigrationObserver for CancelOnDeploy {
+    fn on_deploy(&self, element: &str, _address: Felt, _transaction_hash: Felt) {
+        if element == self.element {
+            self.cancel.cancel();
         }
     }
 }
 
-#[tokio::test]
-async fn migration_with_correct_calldata_second_time_work_as_expected() {
+#[tokio::test(flavor = "multi_thread")]
+async fn cancelled_migration_returns_resumable_checkpoint() {
     let config = setup::load_config();
-    let ws = setup::setup_ws(&config);
+    let MigrationTest { sequencer: _sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
 
-    let base = config.manifest_path().parent().unwrap();
-    let target_dir = format!("{}/target/dev", base);
+    // cancel as soon as the world is deployed, so the models and contracts are not migrated yet.
+    let cancel = CancellationToken::new();
+    let observer = CancelOnDeploy { element: "world", cancel: cancel.clone() };
 
-    let sequencer =
-        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
-            .expect("Failed to start runner.");
+    let (output, checkpoint) = execute_strategy_cancellable(
+        &ws,
+        &migration,
+        &account,
+        TxnConfig::init_wait(),
+        &declarers,
+        &cancel,
+        Some(&observer),
+    )
+    .await
+    .unwrap();
 
-    let account = sequencer.account(0);
+    assert!(!output.full);
+    assert!(output.world_tx_hash.is_some());
+    assert!(output.models.is_empty());
+    assert!(output.contracts.is_empty());
+
+    let checkpoint = checkpoint.expect("cancelled migration should return a checkpoint");
+    assert_eq!(checkpoint.world_address, migration.world_address);
+    assert!(checkpoint.base_declared);
+    assert!(checkpoint.world_migrated);
+    assert!(checkpoint.contracts.is_empty());
+
+    // resuming the migration only migrates what is left.
+    let remaining = checkpoint.remaining(&migration);
+    assert!(remaining.base.is_none());
+    assert!(remaining.world.is_none());
+    assert_eq!(remaining.contracts.len(), migration.contracts.len());
+
+    let output =
+        execute_strategy(&ws, &remaining, &account, TxnConfig::init_wait(), &declarers, None)
+            .await
+            .unwrap();
 
-    let profile_name = ws.current_profile().unwrap().to_string();
+    assert!(output.full);
+    assert_eq!(output.contracts.len(), migration.contracts.len());
+}
 
-    let mut manifest = BaseManifest::load_from_path(
-        &base.to_path_buf().join(MANIFESTS_DIR).join(&profile_name).join(BASE_DIR),
+#[tokio::test(flavor = "multi_thread")]
+async fn interrupted_migration_is_resumed_from_saved_state() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer: _sequencer, account, migration, ws, .. } =
+        MigrationTest::setup(&config).await;
+
+    let state_dir = TempDir::new().unwrap();
+    let txn_config =
+        TxnConfig { report_dir: Some(state_dir.path().to_path_buf()), ..TxnConfig::init_wait() };
+    let state_path = migration_state_path(&ws, &txn_config).unwrap();
+
+    let err = resume_strategy(&ws, &migration, &account, txn_config.clone(), &[], None)
+        .await
+        .expect_err("Nothing should be resumed without a saved state");
+    assert!(err.to_string().contains("No migration to resume"));
+
+    // Interrupt the migration once the world is deployed.
+    let cancel = CancellationToken::new();
+    let observer = CancelOnDeploy { element: "world", cancel: cancel.clone() };
+
+    execute_strategy_cancellable(
+        &ws,
+        &migration,
+        &account,
+        txn_config.clone(),
+        &[],
+        &cancel,
+        Some(&observer),
     )
+    .await
     .unwrap();
 
-    let world = WorldDiff::compute(manifest.clone(), None, "dojo-test").unwrap();
+    let state = MigrationCheckpoint::load(&state_path).unwrap().unwrap();
+    assert_eq!(state.world_address, migration.world_address);
+    assert!(state.world_migrated);
+    assert!(state.contracts.is_empty());
 
-    let migration = prepare_for_migration(
+    let observer = RecordingObserver::default();
+    let output =
+        resume_strategy(&ws, &migration, &account, txn_config.clone(), &[], Some(&observer))
+            .await
+            .unwrap();
+    assert!(output.full);
+    assert!(output.world_tx_hash.is_none());
+    assert_eq!(output.contracts.len(), migration.contracts.len());
+
+    // Only the remaining steps are reported.
+    let deployed = observer.events("deploy");
+    assert!(deployed.iter().all(|(element, _)| element != "world"));
+    for contract in output.contracts.iter().flatten() {
+        assert!(deployed.iter().any(|(tag, _)| tag == &contract.tag));
+    }
+    assert!(observer.events("error").is_empty());
+
+    let state = MigrationCheckpoint::load(&state_path).unwrap().unwrap();
+    assert_eq!(state.models.len(), migration.models.len());
+    for (tag, selector) in state.models.iter().zip(&state.model_selectors) {
+        assert_eq!(*selector, compute_selector_from_tag(tag));
+    }
+    for contract in output.contracts.iter().flatten() {
+        let i = state.contracts.iter().position(|t| *t == contract.tag).unwrap();
+        assert_eq!(state.contract_addresses[i], contract.contract_address);
+    }
+
+    // Everything is confirmed on-chain, resuming again sends nothing.
+    let output = resume_strategy(&ws, &migration, &account, txn_config, &[], None).await.unwrap();
+    assert!(output.full);
+    assert!(output.transaction_hashes.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migration_steps_are_traced_in_nested_spans() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer: _sequencer, account, migration, ws, .. } =
+        MigrationTest::setup(&config).await;
+
+    let recorder = SpanRecorder::default();
+    let subscriber = tracing_subscriber::registry().with(recorder.clone());
+
+    let output = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None)
+        .with_subscriber(subscriber)
+        .await
+        .unwrap();
+    assert!(output.full);
+
+    let spans = recorder.spans.lock().unwrap();
+    let find = |name: &str, resource: Option<&str>| {
+        spans
+            .iter()
+            .map(|(_, span)| span)
+            .find(|s| {
+                s.name == name && resource.map_or(true, |r| s.fields.get("resource").unwrap() == r)
+            })
+            .unwrap_or_else(|| panic!("No `{name}` span for {resource:?}"))
+    };
+
+    let root = find("migration", None);
+    assert_eq!(root.parent, None);
+    assert_eq!(root.fields["world_address"], format!("{:#x}", migration.world_address));
+
+    let world = find("deploy", Some("world"));
+    assert_eq!(world.parent, Some("migration"));
+    assert_eq!(world.fields["tx_hash"], format!("{:#x}", output.world_tx_hash.unwrap()));
+
+    for (step, count) in [
+        ("register_models", migration.models.len()),
+        ("deploy_contracts", migration.contracts.len()),
+    ] {
+        let span = find(step, None);
+        assert_eq!(span.parent, Some("migration"));
+        assert_eq!(span.fields["count"], count.to_string());
+        assert!(output
+            .transaction_hashes
+            .iter()
+            .any(|h| span.fields["tx_hash"] == format!("{h:#x}")));
+    }
+
+    let base = find("declare", Some("base"));
+    assert_eq!(base.parent, Some("migration"));
+    assert_eq!(
+        base.fields["class_hash"],
+        format!("{:#x}", migration.base.as_ref().unwrap().diff.local_class_hash)
+    );
+
+    for (tag, parent) in
+        [("dojo_examples-moves", "register_models"), ("dojo_examples-actions", "deploy_contracts")]
+    {
+        let declare = find("declare", Some(tag));
+        assert_eq!(declare.parent, Some(parent));
+        assert!(declare.fields.contains_key("class_hash"));
+        assert!(declare.fields.contains_key("tx_hash"));
+    }
+}
+
+/// A span recorded by [`SpanRecorder`].
+#[derive(Debug)]
+struct RecordedSpan {
+    name: &'static str,
+    parent: Option<&'static str>,
+    fields: HashMap<String, String>,
+}
+
+impl Visit for RecordedSpan {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.fields.insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+/// Layer recording the spans created and their fields, including the fields recorded later.
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    spans: Arc<Mutex<Vec<(Id, RecordedSpan)>>>,
+}
+
+impl<S> Layer<S> for SpanRecorder
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let parent = ctx.span(id).and_then(|s| s.parent()).map(|p| p.name());
+        let mut span =
+            RecordedSpan { name: attrs.metadata().name(), parent, fields: HashMap::new() };
+        attrs.record(&mut span);
+        self.spans.lock().unwrap().push((id.clone(), span));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        // Ids are reused once a span is closed, the last span with the id is the one recorded.
+        if let Some((_, span)) = self.spans.lock().unwrap().iter_mut().rev().find(|(i, _)| i == id)
+        {
+            values.record(span);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dry_run_predicts_addresses_without_sending_transactions() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, .. } =
+        MigrationTest::setup(&config).await;
+
+    let provider = sequencer.provider();
+    let nonce =
+        provider.get_nonce(BlockId::Tag(BlockTag::Pending), account.address()).await.unwrap();
+
+    let dry_run = execute_strategy_dry_run(&ws, &migration, &account).await.unwrap();
+
+    // Nothing has been sent, the world isn't deployed yet.
+    assert_eq!(
+        provider.get_nonce(BlockId::Tag(BlockTag::Pending), account.address()).await.unwrap(),
+        nonce
+    );
+    assert!(provider
+        .get_class_hash_at(BlockId::Tag(BlockTag::Pending), migration.world_address)
+        .await
+        .is_err());
+
+    let world_step = dry_run.steps.iter().find(|s| s.action == DryRunAction::DeployWorld).unwrap();
+    assert_eq!(dry_run.world_address, migration.world_address);
+    assert_eq!(world_step.address, Some(migration.world_address));
+    assert!(dry_run.total_estimated_fee() > Felt::ZERO);
+
+    let output = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None)
+        .await
+        .unwrap();
+
+    for contract in output.contracts.iter().flatten() {
+        assert_eq!(dry_run.contract_address(&contract.tag), Some(contract.contract_address));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn salt_strategy_determines_contract_addresses() {
+    let config = setup::load_config();
+    let manifest_path = config.manifest_path();
+    let target_dir = Utf8PathBuf::from(format!("{}/target/dev", manifest_path.parent().unwrap()));
+
+    let MigrationTest { sequencer: _sequencer, account, migration: from_name, ws, diff, .. } =
+        MigrationTest::setup(&config).await;
+    let namespaced = prepare_for_migration_with_salt_strategy(
         None,
-        felt!("0x12345"),
-        &Utf8Path::new(&target_dir).to_path_buf(),
-        world,
+        cairo_short_string_to_felt("dojo_examples_ns").unwrap(),
+        &target_dir,
+        diff,
+        SaltStrategy::FromNamespacedName,
     )
     .unwrap();
 
-    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+    let tag = "dojo_examples-actions";
 
-    let migration_output =
-        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers)
+    // The default strategy keeps deriving the salt from the name only.
+    assert_eq!(from_name.salt_strategy, SaltStrategy::FromName);
+    let contract = from_name.contracts.iter().find(|c| c.diff.tag == tag).unwrap();
+    assert_eq!(contract.salt, generate_salt("actions"));
+
+    let contract = namespaced.contracts.iter().find(|c| c.diff.tag == tag).unwrap();
+    assert_eq!(contract.salt, generate_salt(tag));
+
+    for (strategy, salt) in
+        [(&from_name, generate_salt("actions")), (&namespaced, generate_salt(tag))]
+    {
+        let output = execute_strategy(&ws, strategy, &account, TxnConfig::init_wait(), &[], None)
             .await
             .unwrap();
 
-    let world_address = migration_output.world_address;
+        let contract = output.contracts.iter().flatten().find(|c| c.tag == tag).unwrap();
+        let base_class_hash = strategy.base.as_ref().unwrap().diff.local_class_hash;
 
-    let remote_manifest = DeploymentManifest::load_from_remote(sequencer.provider(), world_address)
-        .await
-        .expect("Failed to load remote manifest");
+        assert_eq!(
+            contract.contract_address,
+            get_contract_address(salt, base_class_hash, &[], strategy.world_address)
+        );
+    }
+}
 
-    let overlay_dir = base.join(OVERLAYS_DIR).join(profile_name);
-    if overlay_dir.exists() {
-        let overlay = OverlayManifest::load_from_path(&overlay_dir, &manifest)
-            .expect("Failed to load overlay");
+#[tokio::test(flavor = "multi_thread")]
+async fn total_fee_spent_matches_receipts() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
 
-        // adding correct calldata
-        manifest.merge(overlay);
+    let output =
+        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+            .await
+            .unwrap();
+    assert!(!output.transaction_hashes.is_empty());
+
+    let mut expected = Felt::ZERO;
+    for hash in &output.transaction_hashes {
+        let receipt = sequencer.provider().get_transaction_receipt(hash).await.unwrap();
+        expected += match receipt.receipt {
+            TransactionReceipt::Invoke(r) => r.actual_fee.amount,
+            TransactionReceipt::Declare(r) => r.actual_fee.amount,
+            TransactionReceipt::Deploy(r) => r.actual_fee.amount,
+            TransactionReceipt::DeployAccount(r) => r.actual_fee.amount,
+            TransactionReceipt::L1Handler(r) => r.actual_fee.amount,
+        };
     }
-    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
 
-    let world = WorldDiff::compute(manifest, Some(remote_manifest), &default_namespace)
-        .expect("failed to update order");
+    let total = output.total_fee_spent(sequencer.provider()).await.unwrap();
+    assert_eq!(Felt::from(total), expected);
+    assert!(total > 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fresh_deploy_to_occupied_world_address_fails() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer: _sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
+
+    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+        .await
+        .unwrap();
+
+    // Same seed, and no remote world expected: the world address is already taken.
+    let err = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+        .await
+        .expect_err("Deploying over an existing world should fail");
+
+    type SignError =
+        <SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet> as Account>::SignError;
+    let err = err.downcast_ref::<MigrationError<SignError>>().expect("Expected a migration error");
+    assert!(
+        matches!(err, MigrationError::AddressOccupied { address } if *address == migration.world_address)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn declared_classes_are_looked_up_until_found() {
+    let config = setup::load_config();
+
+    let (migration, _) = setup::setup_migration(&config, "dojo_examples").unwrap();
+
+    let sequencer = KatanaRunner::new().expect("Failed to start runner.");
+
+    let mut account = sequencer.account(0);
+    account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+    let txn_config = TxnConfig::init_wait();
+
+    // Once declared and waited for, the classes can be queried right away.
+    let base = migration.base.as_ref().unwrap();
+    let output = base.declare(&account, &txn_config).await.unwrap();
+    assert_eq!(output.class_hash, base.diff.local_class_hash);
+    let block_id = BlockId::Tag(BlockTag::Pending);
+    sequencer.provider().get_class(block_id, output.class_hash).await.unwrap();
+
+    let model = &migration.models[0];
+    let nonce = account.get_nonce().await.unwrap();
+    let declaration = model.prepare_declaration(&account).await.unwrap();
+    let output = declaration.send_with_nonce(&account, &txn_config, nonce).await.unwrap();
+    wait_for_declarations::<_, Infallible>(sequencer.provider(), &[output.clone()], &txn_config)
+        .await
+        .unwrap();
+    assert_eq!(output.class_hash, model.diff.local_class_hash);
+    sequencer.provider().get_class(block_id, output.class_hash).await.unwrap();
+
+    let interval = Duration::from_millis(10);
+    let found = wait_for_class_declared(sequencer.provider(), output.class_hash, 3, interval)
+        .await
+        .unwrap();
+    assert!(found);
+
+    let found =
+        wait_for_class_declared(sequencer.provider(), felt!("0xdead"), 3, interval).await.unwrap();
+    assert!(!found);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fresh_world_migration_ignores_remote_state() {
+    let config = setup::load_config();
+    let ws = setup::setup_ws(&config);
+
+    let sequencer =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
+
+    let mut account = sequencer.account(0);
+    account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+    let migrate = |fresh_world| {
+        crate::migration::migrate(
+            &ws,
+            None,
+            sequencer.url().to_string(),
+            account.clone(),
+            "dojo_examples",
+            false,
+            TxnConfig::init_wait(),
+            None,
+            fresh_world,
+        )
+    };
+
+    let output = migrate(true).await.unwrap().expect("A fresh world should be migrated");
+    assert!(output.full);
+
+    // The world now exists at the address derived from the seed, a fresh world can't be deployed
+    // there anymore and it isn't upgraded in place instead.
+    let err = migrate(true).await.expect_err("Deploying over an existing world should fail");
+
+    type SignError =
+        <SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet> as Account>::SignError;
+    let err = err.downcast_ref::<MigrationError<SignError>>().expect("Expected a migration error");
+    assert!(
+        matches!(err, MigrationError::AddressOccupied { address } if *address == output.world_address)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn exported_genesis_boots_with_migrated_world() {
+    let config = setup::load_config();
+    let ws = setup::setup_ws(&config);
+
+    let (migration, _) = setup::setup_migration(&config, "dojo_examples").unwrap();
+
+    let sequencer = KatanaRunner::new_with_config(KatanaRunnerConfig {
+        n_accounts: 10,
+        dev: true,
+        ..Default::default()
+    })
+    .expect("Failed to start runner.");
+
+    let account = sequencer.account(0);
+    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+
+    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+        .await
+        .unwrap();
+
+    let world_class_hash = sequencer
+        .provider()
+        .get_class_hash_at(BlockId::Tag(BlockTag::Pending), migration.world_address)
+        .await
+        .unwrap();
+
+    let genesis_dir = TempDir::new().unwrap();
+    let genesis_path = genesis_dir.path().join("genesis.json");
+    sequencer.export_genesis(&genesis_path).await.unwrap();
+
+    let snapshot = KatanaRunner::new_with_config(KatanaRunnerConfig {
+        genesis: Some(genesis_path),
+        ..Default::default()
+    })
+    .expect("Failed to start runner from the exported genesis.");
+
+    let snapshot_class_hash = snapshot
+        .provider()
+        .get_class_hash_at(BlockId::Tag(BlockTag::Latest), migration.world_address)
+        .await
+        .unwrap();
+
+    assert_eq!(snapshot_class_hash, world_class_hash);
+}
+
+#[tokio::test]
+async fn metadata_calculated_properly() {
+    let config = setup::load_config();
+    let ws = setup::setup_ws(&config);
+
+    let base = config.manifest_path().parent().unwrap();
+    let target_dir = format!("{}/target/dev", base);
+
+    let profile_name = ws.current_profile().unwrap().to_string();
+
+    let mut manifest = BaseManifest::load_from_path(
+        &base.to_path_buf().join(MANIFESTS_DIR).join(&profile_name).join(BASE_DIR),
+    )
+    .unwrap();
+
+    let overlay_dir = base.join(OVERLAYS_DIR).join(&profile_name);
+    if overlay_dir.exists() {
+        let overlay_manifest = OverlayManifest::load_from_path(&overlay_dir, &manifest).unwrap();
+        manifest.merge(overlay_manifest);
+    }
+
+    let world = WorldDiff::compute(manifest, None, "dojo-test").unwrap();
+
+    let migration = prepare_for_migration(
+        None,
+        felt!("0x12345"),
+        &Utf8Path::new(&target_dir).to_path_buf(),
+        world,
+    )
+    .unwrap();
+
+    // verifies that key name and actual item name are same
+    for (key, value) in migration.metadata.iter() {
+        match value {
+            MigrationMetadata::Contract(c) => {
+                assert_eq!(key, &c.tag);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn migration_with_correct_calldata_second_time_work_as_expected() {
+    let config = setup::load_config();
+    let ws = setup::setup_ws(&config);
+
+    let base = config.manifest_path().parent().unwrap();
+    let target_dir = format!("{}/target/dev", base);
+
+    let sequencer =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
+
+    let account = sequencer.account(0);
+
+    let profile_name = ws.current_profile().unwrap().to_string();
+
+    let mut manifest = BaseManifest::load_from_path(
+        &base.to_path_buf().join(MANIFESTS_DIR).join(&profile_name).join(BASE_DIR),
+    )
+    .unwrap();
+
+    let world = WorldDiff::compute(manifest.clone(), None, "dojo-test").unwrap();
+
+    let migration = prepare_for_migration(
+        None,
+        felt!("0x12345"),
+        &Utf8Path::new(&target_dir).to_path_buf(),
+        world,
+    )
+    .unwrap();
+
+    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+
+    let migration_output =
+        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+            .await
+            .unwrap();
+
+    let world_address = migration_output.world_address;
+
+    let remote_manifest = DeploymentManifest::load_from_remote(sequencer.provider(), world_address)
+        .await
+        .expect("Failed to load remote manifest");
+
+    let overlay_dir = base.join(OVERLAYS_DIR).join(profile_name);
+    if overlay_dir.exists() {
+        let overlay = OverlayManifest::load_from_path(&overlay_dir, &manifest)
+            .expect("Failed to load overlay");
+
+        // adding correct calldata
+        manifest.merge(overlay);
+    }
+    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+
+    let world = WorldDiff::compute(manifest, Some(remote_manifest), &default_namespace)
+        .expect("failed to update order");
+
+    let migration = prepare_for_migration(
+        Some(world_address),
+        felt!("0x12345"),
+        &Utf8Path::new(&target_dir).to_path_buf(),
+        world,
+    )
+    .unwrap();
+
+    let migration_output =
+        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+            .await
+            .unwrap();
+
+    assert!(migration_output.full);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unchanged_contracts_are_skipped_but_still_authorized() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, diff, .. } =
+        MigrationTest::setup(&config).await;
+
+    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None).await.unwrap();
+
+    // Migrate the contracts again, the world and the models being already up to date.
+    let mut rerun = migration.clone();
+    rerun.world = None;
+    rerun.base = None;
+    rerun.models.clear();
+
+    let output =
+        execute_strategy(&ws, &rerun, &account, TxnConfig::init_wait(), &[], None).await.unwrap();
+    assert!(output.full);
+    assert!(output.contracts.is_empty());
+
+    let mut unchanged = output.unchanged_contracts.clone();
+    unchanged.sort();
+    let mut expected = migration.contracts.iter().map(|c| c.diff.tag.clone()).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(unchanged, expected);
+
+    let provider = sequencer.provider();
+    for hash in &output.transaction_hashes {
+        let receipt = provider.get_transaction_receipt(hash).await.unwrap();
+        assert!(!matches!(receipt.receipt, TransactionReceipt::Declare(_)));
+    }
+
+    // Unchanged contracts are already initialized.
+    assert!(init_contract_calls(&rerun, &output).unwrap().is_empty());
+
+    // The writer permissions of the unchanged contracts are still granted.
+    let world = WorldContract::new(migration.world_address, account);
+    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+    let (grant, _) =
+        find_authorization_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
+            .await
+            .unwrap();
+
+    for c in diff.contracts.iter().filter(|c| !c.local_writes.is_empty()) {
+        assert!(grant.iter().any(|rw| rw.tag_or_address == c.tag), "{} isn't granted", c.tag);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn contracts_upgraded_outside_the_migration_are_reverted() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, .. } =
+        MigrationTest::setup(&config).await;
+
+    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None).await.unwrap();
+
+    let actions = migration
+        .contracts
+        .iter()
+        .find(|c| c.diff.tag == "dojo_examples-actions")
+        .expect("actions contract should be migrated");
+
+    // Upgrade the contract directly through the world, which keeps the deployed class in its
+    // resource registry.
+    let world = WorldContract::new(migration.world_address, &account);
+    let class_hash = declare_class_copy(&account, &actions.artifact_path).await;
+    let res = world
+        .upgrade_contract(&compute_selector_from_tag(&actions.diff.tag), &class_hash.into())
+        .send_with_cfg(&TxnConfig::init_wait())
+        .await
+        .unwrap();
+
+    let provider = sequencer.provider();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let world_reader = WorldContractReader::new(migration.world_address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
+    assert_eq!(
+        world_reader.contract_class_hash(&actions.diff.tag).await.unwrap(),
+        Some(class_hash)
+    );
+
+    // Migrating the contracts again reverts the upgrade instead of skipping the contract.
+    let mut rerun = migration.clone();
+    rerun.world = None;
+    rerun.base = None;
+    rerun.models.clear();
+
+    let output =
+        execute_strategy(&ws, &rerun, &account, TxnConfig::init_wait(), &[], None).await.unwrap();
+    assert!(!output.unchanged_contracts.contains(&actions.diff.tag));
+    assert!(output.contracts.iter().flatten().any(|c| c.tag == actions.diff.tag && c.was_upgraded));
+
+    assert_eq!(
+        world_reader.contract_class_hash(&actions.diff.tag).await.unwrap(),
+        Some(actions.diff.local_class_hash)
+    );
+}
+
+#[tokio::test]
+async fn migration_from_remote() {
+    let config = setup::load_config();
+    let ws = setup::setup_ws(&config);
+
+    let base = config.manifest_path().parent().unwrap();
+    let target_dir = format!("{}/target/dev", base);
+
+    let sequencer =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
+
+    let account = sequencer.account(0);
+
+    let profile_name = ws.current_profile().unwrap().to_string();
+
+    let manifest = BaseManifest::load_from_path(
+        &base.to_path_buf().join(MANIFESTS_DIR).join(&profile_name).join(BASE_DIR),
+    )
+    .unwrap();
+
+    let world = WorldDiff::compute(manifest, None, "dojo-test").unwrap();
+
+    let migration = prepare_for_migration(
+        None,
+        felt!("0x12345"),
+        &Utf8Path::new(&target_dir).to_path_buf(),
+        world,
+    )
+    .unwrap();
+
+    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+
+    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+        .await
+        .unwrap();
+
+    let local_manifest = BaseManifest::load_from_path(
+        &base.to_path_buf().join(MANIFESTS_DIR).join(&profile_name).join(BASE_DIR),
+    )
+    .unwrap();
+
+    let remote_manifest = DeploymentManifest::load_from_remote(
+        JsonRpcClient::new(HttpTransport::new(sequencer.url())),
+        migration.world_address,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(local_manifest.world.inner.class_hash, remote_manifest.world.inner.class_hash);
+    assert_eq!(local_manifest.models.len(), remote_manifest.models.len());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_with_metadata() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
+
+    let output =
+        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+            .await
+            .unwrap();
+
+    let storage = IpfsStorage::default_node().unwrap();
+    let mut pinned = HashMap::new();
+    let res = upload_metadata(
+        &ws,
+        &account,
+        output.clone(),
+        TxnConfig::init_wait(),
+        &storage,
+        &mut pinned,
+        None,
+    )
+    .await;
+    assert!(res.is_ok());
+
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(output.world_address, &provider);
+
+    let client = IpfsClient::from_str(IPFS_CLIENT_URL)
+        .unwrap_or_else(|_| panic!("Unable to initialize the IPFS Client"))
+        .with_credentials(IPFS_USERNAME, IPFS_PASSWORD);
+
+    let dojo_metadata =
+        dojo_metadata_from_workspace(&ws).expect("No current package with dojo metadata found.");
+
+    // check world metadata
+    let resource = world_reader.metadata(&Felt::ZERO).call().await.unwrap();
+    let element_name = WORLD_CONTRACT_TAG.to_string();
+
+    let full_uri = resource.metadata_uri.to_string().unwrap();
+    let resource_bytes = get_ipfs_resource_data(&client, &element_name, &full_uri).await;
+
+    let metadata = resource_bytes_to_world_metadata(&resource_bytes, &element_name);
+
+    assert_eq!(metadata.name, dojo_metadata.world.name, "");
+    assert_eq!(metadata.description, dojo_metadata.world.description, "");
+    assert_eq!(metadata.cover_uri, dojo_metadata.world.cover_uri, "");
+    assert_eq!(metadata.icon_uri, dojo_metadata.world.icon_uri, "");
+    assert_eq!(metadata.website, dojo_metadata.world.website, "");
+    assert_eq!(metadata.socials, dojo_metadata.world.socials, "");
+
+    // TODO: uncomment when https://github.com/dojoengine/dojo/issues/2137 is fixed.
+    //     check_artifact_fields(
+    // &client,
+    // &metadata.artifacts,
+    // &dojo_metadata.world.artifacts,
+    // &element_name,
+    // )
+    // .await;
+    // check model metadata
+    //     for m in migration.models {
+    // let selector = compute_selector_from_tag(&m.diff.tag);
+    // check_artifact_metadata(&client, &world_reader, selector, &m.diff.tag, &dojo_metadata)
+    // .await;
+    // }
+    // check contract metadata
+    //     for c in migration.contracts {
+    // let contract_address =
+    // get_contract_address_from_reader(&world_reader, c.diff.tag.clone()).await.unwrap();
+    //
+    // check_artifact_metadata(
+    // &client,
+    // &world_reader,
+    // contract_address,
+    // &c.diff.tag,
+    // &dojo_metadata,
+    // )
+    // .await;
+    // }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn read_world_metadata_cid() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, .. } =
+        MigrationTest::setup(&config).await;
+
+    let output = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None)
+        .await
+        .unwrap();
+
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(output.world_address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
+
+    assert_eq!(world_reader.world_metadata_cid().await.unwrap(), None);
+
+    let storage = IpfsStorage::default_node().unwrap();
+    let mut pinned = HashMap::new();
+    upload_metadata(
+        &ws,
+        &account,
+        output.clone(),
+        TxnConfig::init_wait(),
+        &storage,
+        &mut pinned,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let world_uri = pinned.get(WORLD_CONTRACT_TAG).expect("World metadata should be pinned");
+    let cid = world_reader.world_metadata_cid().await.unwrap();
+
+    assert_eq!(cid, Some(get_hash_from_uri(&world_uri.to_string())));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn upload_metadata_skips_pinned_elements() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
+
+    let output =
+        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+            .await
+            .unwrap();
+
+    // A previous run pinned the metadata of every resource, but failed to upload the world's.
+    let previous_uri =
+        Uri::Ipfs("ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string());
+    let mut pinned = output
+        .models
+        .iter()
+        .cloned()
+        .chain(output.contracts.iter().flatten().map(|c| c.tag.clone()))
+        .map(|tag| (tag, previous_uri.clone()))
+        .collect::<HashMap<_, _>>();
+    let previously_pinned = pinned.clone();
+
+    let storage = IpfsStorage::default_node().unwrap();
+    upload_metadata(
+        &ws,
+        &account,
+        output.clone(),
+        TxnConfig::init_wait(),
+        &storage,
+        &mut pinned,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Only the world metadata has been uploaded, the other elements are kept as is.
+    let world_uri = pinned.remove(WORLD_CONTRACT_TAG).expect("World metadata should be pinned");
+    assert_ne!(world_uri, previous_uri);
+    assert_eq!(pinned, previously_pinned);
+
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(output.world_address, &provider);
+    let resource = world_reader.metadata(&Felt::ZERO).call().await.unwrap();
+    assert_eq!(resource.metadata_uri.to_string().unwrap(), world_uri.to_string());
+}
+
+/// Storage failing to add the content containing `failing`.
+struct FailingStorage {
+    inner: IpfsStorage,
+    failing: String,
+}
+
+#[async_trait::async_trait(?Send)]
+impl MetadataStorage for FailingStorage {
+    async fn add(&self, data: Vec<u8>, config: &UploadConfig) -> anyhow::Result<String> {
+        if String::from_utf8_lossy(&data).contains(&self.failing) {
+            anyhow::bail!("Upload failed");
+        }
+        self.inner.add(data, config).await
+    }
+
+    async fn pin(&self, cid: &str) -> anyhow::Result<()> {
+        self.inner.pin(cid).await
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_metadata_upload_is_saved_and_resumed() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, .. } =
+        MigrationTest::setup(&config).await;
+
+    let output = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None)
+        .await
+        .unwrap();
+
+    let failing_tag = output.models[0].clone();
+    let storage = FailingStorage {
+        inner: IpfsStorage::default_node().unwrap(),
+        failing: format!("\"name\":\"{failing_tag}\""),
+    };
+
+    let dir = TempDir::new().unwrap();
+    let pinned_path = Utf8PathBuf::from_path_buf(dir.path().join("pinned.json")).unwrap();
+
+    let mut pinned = HashMap::new();
+    let err = upload_metadata(
+        &ws,
+        &account,
+        output.clone(),
+        TxnConfig::init_wait(),
+        &storage,
+        &mut pinned,
+        Some(&pinned_path),
+    )
+    .await
+    .expect_err("A failed upload should be reported");
+    assert!(err.to_string().contains(&failing_tag));
+
+    // Every successful upload is saved, only the failed one is missing.
+    let saved: HashMap<String, Uri> =
+        serde_json::from_str(&fs::read_to_string(&pinned_path).unwrap()).unwrap();
+    assert_eq!(saved, pinned);
+    assert!(saved.contains_key(WORLD_CONTRACT_TAG));
+    assert!(!saved.contains_key(&failing_tag));
+
+    // Resuming only uploads the failed element.
+    let mut resumed = saved.clone();
+    let storage = IpfsStorage::default_node().unwrap();
+    upload_metadata(
+        &ws,
+        &account,
+        output.clone(),
+        TxnConfig::init_wait(),
+        &storage,
+        &mut resumed,
+        Some(&pinned_path),
+    )
+    .await
+    .unwrap();
+
+    let uploaded = resumed.remove(&failing_tag).expect("Failed element should be uploaded");
+    assert_eq!(resumed, saved);
+
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(output.world_address, &provider);
+    let resource =
+        world_reader.metadata(&compute_selector_from_tag(&failing_tag)).call().await.unwrap();
+    assert_eq!(resource.metadata_uri.to_string().unwrap(), uploaded.to_string());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn upload_metadata_with_cid_v1() {
+    let tag = "dojo_examples-moves".to_string();
+    let metadata = ResourceMetadata { name: tag.clone(), artifacts: ArtifactMetadata::default() };
+
+    let config = UploadConfig { pin: true, cid_version: CidVersion::V1 };
+    let hash = metadata.upload(&config).await.unwrap();
+
+    // CIDv1 are base32 encoded, with the `b` multibase prefix.
+    assert!(hash.starts_with('b'), "{hash} is not a CIDv1");
+
+    let uri = Uri::from_string(&format!("ipfs://{hash}")).unwrap();
+    assert_eq!(uri.cid(), Some(hash.as_str()));
+    assert_eq!(get_hash_from_uri(&uri.to_string()), hash);
+
+    let client = IpfsClient::from_str(IPFS_CLIENT_URL)
+        .unwrap_or_else(|_| panic!("Unable to initialize the IPFS Client"))
+        .with_credentials(IPFS_USERNAME, IPFS_PASSWORD);
+
+    let resource_data = get_ipfs_resource_data(&client, &tag, &uri.to_string()).await;
+    let uploaded: ResourceMetadata = serde_json::from_slice(&resource_data).unwrap();
+    assert_eq!(uploaded.name, tag);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_with_auto_authorize() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, diff } =
+        MigrationTest::setup(&config).await;
+
+    let manifest_base = config.manifest_path().parent().unwrap();
+    let mut manifest =
+        BaseManifest::load_from_path(&manifest_base.join(MANIFESTS_DIR).join("dev").join(BASE_DIR))
+            .unwrap();
+
+    let overlay_dir = manifest_base.join(OVERLAYS_DIR).join("dev");
+    if overlay_dir.exists() {
+        let overlay_manifest = OverlayManifest::load_from_path(&overlay_dir, &manifest).unwrap();
+        manifest.merge(overlay_manifest);
+    }
+
+    let txn_config = TxnConfig::init_wait();
+
+    let output = execute_strategy(&ws, &migration, &account, txn_config.clone(), &declarers, None)
+        .await
+        .unwrap();
+
+    let world_address = migration.world_address;
+    let world = WorldContract::new(world_address, account);
+
+    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+    let (grant, revoke) =
+        find_authorization_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
+            .await
+            .unwrap();
+
+    let res =
+        auto_authorize(&ws, &world, &txn_config, &default_namespace, &grant, &revoke, &[]).await;
+    assert!(res.is_ok());
+
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(output.world_address, &provider);
+    let registered = world_reader.registered_contracts().await.unwrap();
+
+    // check contract metadata
+    for c in migration.contracts {
+        let contract_address = registered
+            .iter()
+            .find(|r| r.tag == c.diff.tag)
+            .expect("Contract should be registered")
+            .address;
+
+        let contract = manifest.contracts.iter().find(|a| a.inner.tag == c.diff.tag).unwrap();
+
+        for resource in &contract.inner.writes {
+            let resource_type = ResourceType::from_str(resource).unwrap();
+
+            let selector = match resource_type {
+                ResourceType::Model(tag) => compute_selector_from_tag(&tag),
+                ResourceType::Contract(tag) => compute_selector_from_tag(&tag),
+                ResourceType::Namespace(ns) => compute_bytearray_hash(&ns),
+                ResourceType::Selector(s) => s,
+            };
+
+            let contract_address = ContractAddress(contract_address);
+            let is_writer =
+                world_reader.is_writer(&selector, &contract_address).call().await.unwrap();
+            assert!(is_writer);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_with_auto_authorize_owners() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, mut diff } =
+        MigrationTest::setup(&config).await;
+
+    let actions_tag = "dojo_examples-actions";
+    let actions = diff.contracts.iter_mut().find(|c| c.tag == actions_tag).unwrap();
+    actions.local_owners = vec!["ns:dojo_examples".to_string()];
+
+    let txn_config = TxnConfig::init_wait();
+
+    let output = execute_strategy(&ws, &migration, &account, txn_config.clone(), &declarers, None)
+        .await
+        .unwrap();
+
+    let world = WorldContract::new(migration.world_address, account);
+
+    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+    let (grant, revoke) =
+        find_authorization_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
+            .await
+            .unwrap();
+    let owners =
+        find_ownership_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
+            .await
+            .unwrap();
+    assert_eq!(owners.len(), 1);
+
+    auto_authorize(&ws, &world, &txn_config, &default_namespace, &grant, &revoke, &owners)
+        .await
+        .unwrap();
+
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(output.world_address, &provider);
+
+    let contract_address =
+        get_contract_address_from_reader(&world_reader, actions_tag.to_string()).await.unwrap();
+
+    let is_owner = world_reader
+        .is_owner(&compute_bytearray_hash("dojo_examples"), &ContractAddress(contract_address))
+        .call()
+        .await
+        .unwrap();
+    assert!(is_owner);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_with_overlay_grants() {
+    let config = setup::load_config();
+    // The project is a copy of the example, its overlays can be changed.
+    let tag = "dojo_examples-mock_token";
+    let overlay_dir = config.manifest_path().parent().unwrap().join(OVERLAYS_DIR).join("dev");
+    fs::write(
+        overlay_dir.join("mock_token.toml"),
+        format!(
+            r#"
+tag = "{tag}"
+
+[grants]
+writer = ["m:dojo_examples-Moves"]
+owner = ["ns:dojo_examples"]
+"#
+        ),
+    )
+    .unwrap();
+
+    let MigrationTest { sequencer, account, migration, ws, diff, .. } =
+        MigrationTest::setup(&config).await;
+
+    let txn_config = TxnConfig::init_wait();
+
+    let output =
+        execute_strategy(&ws, &migration, &account, txn_config.clone(), &[], None).await.unwrap();
+
+    let world = WorldContract::new(migration.world_address, account);
+
+    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+    let (grant, revoke) =
+        find_authorization_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
+            .await
+            .unwrap();
+    let owners =
+        find_ownership_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
+            .await
+            .unwrap();
+
+    assert!(grant.iter().any(|w| w.tag_or_address == tag));
+    assert_eq!(owners.len(), 1);
+
+    auto_authorize(&ws, &world, &txn_config, &default_namespace, &grant, &revoke, &owners)
+        .await
+        .unwrap();
+
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(output.world_address, &provider);
+    let contract_address = ContractAddress(
+        get_contract_address_from_reader(&world_reader, tag.to_string()).await.unwrap(),
+    );
+
+    let is_writer = world_reader
+        .is_writer(&compute_selector_from_tag("dojo_examples-Moves"), &contract_address)
+        .call()
+        .await
+        .unwrap();
+    assert!(is_writer);
+
+    let is_owner = world_reader
+        .is_owner(&compute_bytearray_hash("dojo_examples"), &contract_address)
+        .call()
+        .await
+        .unwrap();
+    assert!(is_owner);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn auto_authorize_skips_existing_grants() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, diff, .. } =
+        MigrationTest::setup(&config).await;
+
+    let txn_config = TxnConfig::init_wait();
+
+    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+
+    let output = execute_strategy(&ws, &migration, &account, txn_config.clone(), &declarers, None)
+        .await
+        .unwrap();
+
+    let world = WorldContract::new(migration.world_address, account);
+
+    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+    let (grant, revoke) =
+        find_authorization_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
+            .await
+            .unwrap();
+    assert!(!grant.is_empty());
+
+    let first = auto_authorize(&ws, &world, &txn_config, &default_namespace, &grant, &revoke, &[])
+        .await
+        .unwrap();
+    assert_eq!(first.granted, grant.len());
+    assert_eq!(first.already_granted, 0);
+
+    let provider = sequencer.provider();
+    let nonce =
+        provider.get_nonce(BlockId::Tag(BlockTag::Pending), world.account.address()).await.unwrap();
+
+    // The same grants are now all on-chain, nothing should be sent.
+    let second = auto_authorize(&ws, &world, &txn_config, &default_namespace, &grant, &[], &[])
+        .await
+        .unwrap();
+    assert_eq!(second.granted, 0);
+    assert_eq!(second.already_granted, grant.len());
+
+    let nonce_after =
+        provider.get_nonce(BlockId::Tag(BlockTag::Pending), world.account.address()).await.unwrap();
+    assert_eq!(nonce, nonce_after);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn auto_revoke_removes_undeclared_writers() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, diff, .. } =
+        MigrationTest::setup(&config).await;
+
+    let txn_config = TxnConfig::init_wait();
+
+    let output =
+        execute_strategy(&ws, &migration, &account, txn_config.clone(), &[], None).await.unwrap();
+
+    let world = WorldContract::new(migration.world_address, account);
+
+    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+    let (grant, revoke) =
+        find_authorization_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
+            .await
+            .unwrap();
+    auto_authorize(&ws, &world, &txn_config, &default_namespace, &grant, &revoke, &[])
+        .await
+        .unwrap();
+
+    // Everything granted is still declared, nothing is revoked.
+    let revoked = auto_revoke(&ws, &world, &txn_config, &default_namespace, &diff).await.unwrap();
+    assert!(revoked.is_empty());
+
+    // `actions` is decommissioned and doesn't declare any write anymore.
+    let mut desired = diff.clone();
+    let actions = desired.contracts.iter_mut().find(|c| c.tag == "dojo_examples-actions").unwrap();
+    actions.local_writes.clear();
+
+    let revoked =
+        auto_revoke(&ws, &world, &txn_config, &default_namespace, &desired).await.unwrap();
+    assert_eq!(revoked.len(), 1);
+
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(migration.world_address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
+    let namespace = compute_bytearray_hash("dojo_examples");
+
+    for (tag, is_writer) in [("dojo_examples-actions", false), ("dojo_examples-mock_token", true)] {
+        let address =
+            get_contract_address_from_reader(&world_reader, tag.to_string()).await.unwrap();
+        let writer =
+            world_reader.is_writer(&namespace, &ContractAddress(address)).call().await.unwrap();
+        assert_eq!(writer, is_writer, "unexpected write access of {tag}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn auto_authorize_from_non_owner_fails() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, diff, .. } =
+        MigrationTest::setup(&config).await;
+
+    let txn_config = TxnConfig::init_wait();
+
+    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+
+    let output = execute_strategy(&ws, &migration, &account, txn_config.clone(), &declarers, None)
+        .await
+        .unwrap();
+
+    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+    let world = WorldContract::new(migration.world_address, account);
+    let (grant, revoke) =
+        find_authorization_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
+            .await
+            .unwrap();
+    assert!(!grant.is_empty());
+
+    let mut other = sequencer.account(1);
+    other.set_block_id(BlockId::Tag(BlockTag::Pending));
+    let other_address = other.address();
+    let world = WorldContract::new(migration.world_address, other);
+
+    let provider = sequencer.provider();
+    let nonce = provider.get_nonce(BlockId::Tag(BlockTag::Pending), other_address).await.unwrap();
+
+    let err = auto_authorize(&ws, &world, &txn_config, &default_namespace, &grant, &revoke, &[])
+        .await
+        .expect_err("Authorizing from a non-owner account should fail");
+
+    type SignError =
+        <SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet> as Account>::SignError;
+    let err = err.downcast_ref::<MigrationError<SignError>>().expect("Expected a migration error");
+    assert!(matches!(err, MigrationError::NotWorldOwner { account } if *account == other_address));
+
+    let nonce_after =
+        provider.get_nonce(BlockId::Tag(BlockTag::Pending), other_address).await.unwrap();
+    assert_eq!(nonce, nonce_after);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn declared_classes_are_cached_and_skipped() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
+
+    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+        .await
+        .unwrap();
+
+    let class_hashes = migration
+        .models
+        .iter()
+        .map(|m| m.diff.local_class_hash)
+        .chain(migration.contracts.iter().map(|c| c.diff.local_class_hash))
+        .collect::<Vec<_>>();
+
+    let provider = sequencer.provider();
+    let chain_id = provider.chain_id().await.unwrap();
+    let cache = DeclarationCache::load(&declaration_cache_path(&ws).unwrap()).unwrap();
+
+    for class_hash in &class_hashes {
+        assert!(cache.get(chain_id, *class_hash).is_some(), "{class_hash:#x} isn't cached");
+    }
+
+    let confirmed = cache.confirmed(provider, chain_id, class_hashes.clone()).await.unwrap();
+    assert_eq!(confirmed.len(), class_hashes.iter().collect::<HashSet<_>>().len());
+
+    // A cache hit is only trusted once the class is found on-chain.
+    let other =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
+    let confirmed = cache.confirmed(other.provider(), chain_id, class_hashes).await.unwrap();
+    assert!(confirmed.is_empty());
+
+    // Another world on the same chain skips the declarations confirmed by the cache.
+    let (migration, _) = setup::setup_migration(&config, "dojo_examples_cached").unwrap();
+    let output =
+        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+            .await
+            .unwrap();
+    assert!(output.full);
+
+    for hash in output.transaction_hashes {
+        let receipt = provider.get_transaction_receipt(hash).await.unwrap();
+        assert!(!matches!(receipt.receipt, TransactionReceipt::Declare(_)));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn contracts_sharing_a_class_are_declared_once() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, mut migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
+
+    // A second contract deployed from the class of the dungeon, under another salt.
+    let dungeon = migration
+        .contracts
+        .iter()
+        .find(|c| c.diff.tag == "dojo_examples-dungeon")
+        .cloned()
+        .unwrap();
+    let mut copy = dungeon.clone();
+    copy.diff.tag = "dojo_examples-dungeon_copy".to_string();
+    copy.salt = dungeon.salt + Felt::ONE;
+    copy.contract_address =
+        get_contract_address(copy.salt, copy.diff.base_class_hash, &[], migration.world_address);
+    migration.contracts.push(copy.clone());
+
+    let observer = RecordingObserver::default();
+    let output = execute_strategy(
+        &ws,
+        &migration,
+        &account,
+        TxnConfig::init_wait(),
+        &declarers,
+        Some(&observer),
+    )
+    .await
+    .unwrap();
+    assert!(output.full);
+
+    let declared = observer.events("declare_start");
+    assert!(declared.iter().any(|(tag, _)| *tag == dungeon.diff.tag));
+    assert!(!declared.iter().any(|(tag, _)| *tag == copy.diff.tag));
+
+    let deployed = observer.events("deploy");
+    for contract in [&dungeon, &copy] {
+        assert!(deployed.iter().any(|(tag, _)| *tag == contract.diff.tag));
+
+        let class_hash = sequencer
+            .provider()
+            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), contract.contract_address)
+            .await
+            .unwrap();
+        assert_eq!(class_hash, dungeon.diff.local_class_hash);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn init_calls_carry_resolved_init_calldata() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, .. } =
+        MigrationTest::setup(&config).await;
+
+    let actions = migration.contracts.iter().find(|c| c.diff.tag == "dojo_examples-actions");
+    let actions = actions.unwrap();
+    let actions_address = get_contract_address(
+        generate_salt(&get_name_from_tag(&actions.diff.tag)),
+        actions.diff.base_class_hash,
+        &[],
+        migration.world_address,
+    );
+
+    let output = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None)
+        .await
+        .unwrap();
+
+    let calls = init_contract_calls(&migration, &output).unwrap();
+    assert_eq!(calls.len(), migration.contracts.len());
+
+    // `others` is initialized with the address and class hash of `actions`, and a value.
+    let others = calls
+        .iter()
+        .find(|c| c.calldata[0] == compute_selector_from_tag("dojo_examples-others"))
+        .unwrap();
+    assert_eq!(others.to, migration.world_address);
+    assert_eq!(
+        others.calldata[1..],
+        [Felt::from(3u8), actions_address, actions.diff.local_class_hash, Felt::from(10u8)]
+    );
+
+    // The deployed `others` emits the values it has been initialized with.
+    let transaction_hash = account.execute_v1(calls).send().await.unwrap().transaction_hash;
+    let receipt = TransactionWaiter::new(transaction_hash, sequencer.provider()).await.unwrap();
+
+    let TransactionReceipt::Invoke(receipt) = receipt.receipt else {
+        panic!("Init transaction should be an invoke");
+    };
+    assert!(receipt.events.iter().any(|e| {
+        e.from_address == migration.world_address
+            && e.keys.contains(&actions_address)
+            && e.data.ends_with(&[actions.diff.local_class_hash, Felt::from(10u8)])
+    }));
+
+    // Upgraded contracts have already been initialized.
+    let output = MigrationOutput {
+        world_address: migration.world_address,
+        contracts: vec![Some(ContractMigrationOutput {
+            tag: "dojo_examples-others".to_string(),
+            contract_address: Felt::ONE,
+            class_hash: Felt::ONE,
+            base_class_hash: Felt::ONE,
+            was_upgraded: true,
+        })],
+        ..Default::default()
+    };
+    let calls = init_contract_calls(&migration, &output).unwrap();
+    assert_eq!(calls.len(), migration.contracts.len() - 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migration_with_mismatching_world_address_and_seed() {
+    let config = setup::load_config();
+    let ws = setup::setup_ws(&config);
+
+    let base_dir = config.manifest_path().parent().unwrap().to_path_buf();
+    let target_dir = base_dir.join("target").join("dev");
+
+    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+
+    let (strategy, _) = prepare_migration_with_world_and_seed(
+        base_dir,
+        target_dir,
+        Some(Felt::ONE),
+        "sozo_test",
+        &default_namespace,
+    )
+    .unwrap();
+
+    // The strategy.world has it's address set with the seed directly, and not
+    // from the world address provided by the user.
+    assert_ne!(strategy.world_address, strategy.world.unwrap().contract_address);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn world_address_from_deployment_receipt_matches_strategy() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
+
+    let output =
+        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers, None)
+            .await
+            .unwrap();
+
+    let world_tx_hash = output.world_tx_hash.expect("World should have been deployed");
+    let deployed_address =
+        get_deployed_address_from_receipt(sequencer.provider(), world_tx_hash).await.unwrap();
+
+    assert_eq!(deployed_address, migration.world_address);
+}
+
+/// Records the events of a migration, in the order they are received.
+#[derive(Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<(String, String, Option<Felt>)>>,
+}
+
+impl RecordingObserver {
+    fn record(&self, event: &str, element: &str, transaction_hash: Option<Felt>) {
+        let event = (event.to_string(), element.to_string(), transaction_hash);
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn events(&self, event: &str) -> Vec<(String, Option<Felt>)> {
+        let events = self.events.lock().unwrap();
+        events.iter().filter(|(e, ..)| e == event).map(|(_, el, tx)| (el.clone(), *tx)).collect()
+    }
+}
+
+impl MigrationObserver for RecordingObserver {
+    fn on_declare_start(&self, element: &str) {
+        self.record("declare_start", element, None);
+    }
+
+    fn on_declare_done(&self, element: &str, transaction_hash: Option<Felt>) {
+        self.record("declare_done", element, transaction_hash);
+    }
+
+    fn on_deploy(&self, element: &str, _address: Felt, transaction_hash: Felt) {
+        self.record("deploy", element, Some(transaction_hash));
+    }
+
+    fn on_register_model(&self, element: &str, transaction_hash: Felt) {
+        self.record("register_model", element, Some(transaction_hash));
+    }
+
+    fn on_error(&self, element: &str, _error: &dyn std::fmt::Display) {
+        self.record("error", element, None);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migration_progress_is_streamed_to_observer() {
+    let config = setup::load_config();
+    let manifest_path = config.manifest_path();
+    let target_dir = Utf8PathBuf::from(format!("{}/target/dev", manifest_path.parent().unwrap()));
+
+    let MigrationTest { sequencer: _sequencer, account, migration, ws, diff, .. } =
+        MigrationTest::setup(&config).await;
+
+    let observer = RecordingObserver::default();
+    let output =
+        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], Some(&observer))
+            .await
+            .unwrap();
+    assert!(output.full);
+
+    let started = observer.events("declare_start");
+    let declared = observer.events("declare_done");
+    assert!(started.iter().any(|(element, _)| element == "base"));
+    assert_eq!(started.len(), declared.len());
+    assert!(declared.iter().all(|(_, tx)| tx.is_some()));
+
+    let registered = observer.events("register_model");
+    assert_eq!(
+        registered.iter().map(|(tag, _)| tag.clone()).collect::<HashSet<_>>(),
+        output.models.iter().cloned().collect::<HashSet<_>>()
+    );
+
+    let deployed = observer.events("deploy");
+    assert_eq!(deployed[0], ("world".to_string(), output.world_tx_hash));
+    for contract in output.contracts.iter().flatten() {
+        assert!(deployed.iter().any(|(tag, _)| tag == &contract.tag));
+    }
+
+    assert!(observer.events("error").is_empty());
+
+    // A second world on the same chain finds the base class in the declaration cache.
+    let other_world = prepare_for_migration(
+        None,
+        cairo_short_string_to_felt("dojo_examples_other").unwrap(),
+        &target_dir,
+        diff,
+    )
+    .unwrap();
+
+    let observer = RecordingObserver::default();
+    execute_strategy(&ws, &other_world, &account, TxnConfig::init_wait(), &[], Some(&observer))
+        .await
+        .unwrap();
+
+    let started = observer.events("declare_start");
+    let declared = observer.events("declare_done");
+    assert!(started.iter().any(|(element, _)| element == "base"));
+    assert!(declared.contains(&("base".to_string(), None)));
+    assert_eq!(started.len(), declared.len());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migration_transaction_log_can_be_replayed() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, declarers, .. } =
+        MigrationTest::setup(&config).await;
+
+    let (output, log) =
+        execute_strategy_with_log(&ws, &migration, &account, TxnConfig::init_wait(), &declarers)
+            .await
+            .unwrap();
+
+    let log = log.unwrap();
+    assert!(output.full);
+    assert!(!log.transactions.is_empty());
+
+    let log = TransactionLog::from_json(&log.to_json().unwrap()).unwrap();
+
+    let replay_sequencer =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
+
+    let mut replay_account = replay_sequencer.account(0);
+    replay_account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+    let hashes = replay_transaction_log(&replay_account, &log).await.unwrap();
+    assert_eq!(hashes.len(), log.transactions.len());
+
+    let original = DeploymentManifest::load_from_remote(sequencer.provider(), output.world_address)
+        .await
+        .unwrap();
+    let replayed =
+        DeploymentManifest::load_from_remote(replay_sequencer.provider(), output.world_address)
+            .await
+            .unwrap();
+
+    assert_eq!(original.world.inner.class_hash, replayed.world.inner.class_hash);
+    assert_eq!(original.base.inner.class_hash, replayed.base.inner.class_hash);
+    assert_eq!(
+        original
+            .models
+            .iter()
+            .map(|m| (m.inner.tag.clone(), m.inner.class_hash))
+            .collect::<Vec<_>>(),
+        replayed
+            .models
+            .iter()
+            .map(|m| (m.inner.tag.clone(), m.inner.class_hash))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        original
+            .contracts
+            .iter()
+            .map(|c| (c.inner.tag.clone(), c.inner.address, c.inner.class_hash))
+            .collect::<Vec<_>>(),
+        replayed
+            .contracts
+            .iter()
+            .map(|c| (c.inner.tag.clone(), c.inner.address, c.inner.class_hash))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn read_contract_layout_version() {
+    let sequencer =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
 
-    let migration = prepare_for_migration(
-        Some(world_address),
-        felt!("0x12345"),
-        &Utf8Path::new(&target_dir).to_path_buf(),
-        world,
-    )
-    .unwrap();
+    let world = setup::setup(&sequencer).await.unwrap();
 
-    let migration_output =
-        execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers)
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(world.address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
+
+    let contract_address =
+        get_contract_address_from_reader(&world_reader, "dojo_examples-actions".to_string())
             .await
             .unwrap();
 
-    assert!(migration_output.full);
+    let version = world_reader.contract_layout_version(contract_address).await.unwrap();
+    assert_eq!(version, DEFAULT_LAYOUT_VERSION);
 }
 
-#[tokio::test]
-async fn migration_from_remote() {
-    let config = setup::load_config();
-    let ws = setup::setup_ws(&config);
+/// Declares a copy of the class at `artifact_path` with a different ABI, which has the same code
+/// as the original class but another class hash.
+async fn declare_class_copy(
+    account: &SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
+    artifact_path: &PathBuf,
+) -> Felt {
+    let mut class = read_class(artifact_path).unwrap().flatten().unwrap();
+    class.abi.push(' ');
+    let class_hash = class.class_hash();
+    let compiled_class_hash = get_compiled_class_hash(artifact_path).unwrap();
+
+    let res = account
+        .declare_v2(Arc::new(class), compiled_class_hash)
+        .send_with_cfg(&TxnConfig::init_wait())
+        .await
+        .unwrap();
+    TransactionWaiter::new(res.transaction_hash, account.provider()).await.unwrap();
 
-    let base = config.manifest_path().parent().unwrap();
-    let target_dir = format!("{}/target/dev", base);
+    class_hash
+}
 
+#[tokio::test(flavor = "multi_thread")]
+async fn rollback_world_upgrade_to_declared_class() {
     let sequencer =
         KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
             .expect("Failed to start runner.");
 
-    let account = sequencer.account(0);
+    let world = setup::setup(&sequencer).await.unwrap();
 
-    let profile_name = ws.current_profile().unwrap().to_string();
+    let provider = sequencer.provider();
+    let original_class_hash =
+        provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), world.address).await.unwrap();
 
-    let manifest = BaseManifest::load_from_path(
-        &base.to_path_buf().join(MANIFESTS_DIR).join(&profile_name).join(BASE_DIR),
-    )
-    .unwrap();
+    // A class which has never been declared is rejected before sending any transaction.
+    let err = rollback_world_upgrade(&world, felt!("0x1337"), &TxnConfig::init_wait())
+        .await
+        .expect_err("Rollback to an undeclared class should fail");
+    assert!(err.to_string().contains("is not declared"));
 
-    let world = WorldDiff::compute(manifest, None, "dojo-test").unwrap();
+    let (migration, _) = setup::setup_migration(&setup::load_config(), "dojo_examples").unwrap();
+    let world_artifact = migration.world.unwrap().artifact_path;
+    let upgraded_class_hash = declare_class_copy(&world.account, &world_artifact).await;
+    assert_ne!(upgraded_class_hash, original_class_hash);
 
-    let migration = prepare_for_migration(
-        None,
-        felt!("0x12345"),
-        &Utf8Path::new(&target_dir).to_path_buf(),
-        world,
-    )
-    .unwrap();
+    let res = world
+        .upgrade(&upgraded_class_hash.into())
+        .send_with_cfg(&TxnConfig::init_wait())
+        .await
+        .unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
 
-    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+    let class_hash =
+        provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), world.address).await.unwrap();
+    assert_eq!(class_hash, upgraded_class_hash);
 
-    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers).await.unwrap();
+    rollback_world_upgrade(&world, original_class_hash, &TxnConfig::init_wait()).await.unwrap();
 
-    let local_manifest = BaseManifest::load_from_path(
-        &base.to_path_buf().join(MANIFESTS_DIR).join(&profile_name).join(BASE_DIR),
-    )
-    .unwrap();
+    let class_hash =
+        provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), world.address).await.unwrap();
+    assert_eq!(class_hash, original_class_hash);
+}
 
-    let remote_manifest = DeploymentManifest::load_from_remote(
-        JsonRpcClient::new(HttpTransport::new(sequencer.url())),
-        migration.world_address,
-    )
-    .await
-    .unwrap();
+#[tokio::test(flavor = "multi_thread")]
+async fn read_registered_contracts() {
+    let sequencer =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
 
-    assert_eq!(local_manifest.world.inner.class_hash, remote_manifest.world.inner.class_hash);
-    assert_eq!(local_manifest.models.len(), remote_manifest.models.len());
+    let world = setup::setup(&sequencer).await.unwrap();
+
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(world.address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
+
+    let contracts = world_reader.registered_contracts().await.unwrap();
+
+    for tag in ["dojo_examples-actions", "dojo_examples-mock_token"] {
+        let contract = contracts
+            .iter()
+            .find(|c| c.tag == tag)
+            .unwrap_or_else(|| panic!("{tag} should be registered"));
+
+        let address =
+            get_contract_address_from_reader(&world_reader, tag.to_string()).await.unwrap();
+        let class_hash =
+            provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), address).await.unwrap();
+
+        assert_eq!(contract.selector, compute_selector_from_tag(tag));
+        assert_eq!(contract.address, address);
+        assert_eq!(contract.class_hash, class_hash);
+    }
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn migrate_with_metadata() {
+async fn manifest_resource_selectors_match_registered_resources() {
+    let sequencer =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
+
+    let world = setup::setup(&sequencer).await.unwrap();
+
     let config = setup::load_config();
-    let ws = setup::setup_ws(&config);
+    let manifest_base = config.manifest_path().parent().unwrap();
+    let manifest =
+        BaseManifest::load_from_path(&manifest_base.join(MANIFESTS_DIR).join("dev").join(BASE_DIR))
+            .unwrap();
 
-    let (migration, _) = setup::setup_migration(&config, "dojo_examples").unwrap();
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(world.address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
+
+    let selectors = manifest.resource_selectors();
+    assert_eq!(selectors.len(), manifest.models.len() + manifest.contracts.len());
+
+    for model in &manifest.models {
+        let resource = world_reader.resource(&selectors[&model.inner.tag]).call().await.unwrap();
+        assert!(matches!(resource, Resource::Model(_)), "{} not registered", model.inner.tag);
+    }
+
+    for contract in &manifest.contracts {
+        let resource = world_reader.resource(&selectors[&contract.inner.tag]).call().await.unwrap();
+        assert!(matches!(resource, Resource::Contract(_)), "{} not registered", contract.inner.tag);
+    }
+}
 
+#[tokio::test(flavor = "multi_thread")]
+async fn read_model_count() {
     let sequencer =
         KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
             .expect("Failed to start runner.");
 
-    let mut account = sequencer.account(0);
-    account.set_block_id(BlockId::Tag(BlockTag::Pending));
+    let world = setup::setup(&sequencer).await.unwrap();
 
-    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+    let config = setup::load_config();
+    let (migration, _) = setup::setup_migration(&config, "dojo_examples").unwrap();
 
-    let output = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &declarers)
-        .await
-        .unwrap();
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(world.address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
 
-    let res = upload_metadata(&ws, &account, output.clone(), TxnConfig::init_wait()).await;
-    assert!(res.is_ok());
+    let count = world_reader.model_count().await.unwrap();
 
-    let provider = sequencer.provider();
-    let world_reader = WorldContractReader::new(output.world_address, &provider);
+    let remote_manifest =
+        DeploymentManifest::load_from_remote(&provider, world.address).await.unwrap();
 
-    let client = IpfsClient::from_str(IPFS_CLIENT_URL)
-        .unwrap_or_else(|_| panic!("Unable to initialize the IPFS Client"))
-        .with_credentials(IPFS_USERNAME, IPFS_PASSWORD);
+    assert_eq!(count, migration.models.len());
+    assert_eq!(count, remote_manifest.models.len());
+}
 
-    let dojo_metadata =
-        dojo_metadata_from_workspace(&ws).expect("No current package with dojo metadata found.");
+#[tokio::test(flavor = "multi_thread")]
+async fn world_fingerprint_changes_when_a_model_is_registered() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, migration, ws, .. } =
+        MigrationTest::setup(&config).await;
 
-    // check world metadata
-    let resource = world_reader.metadata(&Felt::ZERO).call().await.unwrap();
-    let element_name = WORLD_CONTRACT_TAG.to_string();
+    // The last model is only registered once the rest of the world is migrated.
+    let mut first = migration.clone();
+    let model = first.models.pop().expect("Expected at least one model");
 
-    let full_uri = resource.metadata_uri.to_string().unwrap();
-    let resource_bytes = get_ipfs_resource_data(&client, &element_name, &full_uri).await;
+    execute_strategy(&ws, &first, &account, TxnConfig::init_wait(), &[], None).await.unwrap();
 
-    let metadata = resource_bytes_to_world_metadata(&resource_bytes, &element_name);
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(migration.world_address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
 
-    assert_eq!(metadata.name, dojo_metadata.world.name, "");
-    assert_eq!(metadata.description, dojo_metadata.world.description, "");
-    assert_eq!(metadata.cover_uri, dojo_metadata.world.cover_uri, "");
-    assert_eq!(metadata.icon_uri, dojo_metadata.world.icon_uri, "");
-    assert_eq!(metadata.website, dojo_metadata.world.website, "");
-    assert_eq!(metadata.socials, dojo_metadata.world.socials, "");
+    let fingerprint = world_reader.fingerprint().await.unwrap();
+    assert_eq!(world_reader.fingerprint().await.unwrap(), fingerprint);
 
-    // TODO: uncomment when https://github.com/dojoengine/dojo/issues/2137 is fixed.
-    //     check_artifact_fields(
-    // &client,
-    // &metadata.artifacts,
-    // &dojo_metadata.world.artifacts,
-    // &element_name,
-    // )
-    // .await;
-    // check model metadata
-    //     for m in migration.models {
-    // let selector = compute_selector_from_tag(&m.diff.tag);
-    // check_artifact_metadata(&client, &world_reader, selector, &m.diff.tag, &dojo_metadata)
-    // .await;
-    // }
-    // check contract metadata
-    //     for c in migration.contracts {
-    // let contract_address =
-    // get_contract_address_from_reader(&world_reader, c.diff.tag.clone()).await.unwrap();
-    //
-    // check_artifact_metadata(
-    // &client,
-    // &world_reader,
-    // contract_address,
-    // &c.diff.tag,
-    // &dojo_metadata,
-    // )
-    // .await;
-    // }
+    let mut second = migration.clone();
+    second.world = None;
+    second.base = None;
+    second.contracts.clear();
+    second.models = vec![model];
+
+    execute_strategy(&ws, &second, &account, TxnConfig::init_wait(), &[], None).await.unwrap();
+
+    assert_ne!(world_reader.fingerprint().await.unwrap(), fingerprint);
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn migrate_with_auto_authorize() {
-    let config = setup::load_config();
-    let ws = setup::setup_ws(&config);
+async fn world_fingerprint_changes_when_a_contract_is_upgraded() {
+    let sequencer =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
 
-    let (migration, diff) = setup::setup_migration(&config, "dojo_examples").unwrap();
+    let world = setup::setup(&sequencer).await.unwrap();
 
-    let manifest_base = config.manifest_path().parent().unwrap();
-    let mut manifest =
-        BaseManifest::load_from_path(&manifest_base.join(MANIFESTS_DIR).join("dev").join(BASE_DIR))
-            .unwrap();
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(world.address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
+
+    let fingerprint = world_reader.fingerprint().await.unwrap();
+
+    let (migration, _) = setup::setup_migration(&setup::load_config(), "dojo_examples").unwrap();
+    let actions = migration
+        .contracts
+        .iter()
+        .find(|c| c.diff.tag == "dojo_examples-actions")
+        .expect("actions contract should be migrated");
+    let class_hash = declare_class_copy(&world.account, &actions.artifact_path).await;
+
+    // The world doesn't update its resource registry when a contract is upgraded.
+    let selector = compute_selector_from_tag("dojo_examples-actions");
+    let res = world
+        .upgrade_contract(&selector, &class_hash.into())
+        .send_with_cfg(&TxnConfig::init_wait())
+        .await
+        .unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
 
-    let overlay_dir = manifest_base.join(OVERLAYS_DIR).join("dev");
-    if overlay_dir.exists() {
-        let overlay_manifest = OverlayManifest::load_from_path(&overlay_dir, &manifest).unwrap();
-        manifest.merge(overlay_manifest);
-    }
+    assert_ne!(world_reader.fingerprint().await.unwrap(), fingerprint);
+}
 
+#[tokio::test(flavor = "multi_thread")]
+async fn read_registered_models() {
     let sequencer =
         KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
             .expect("Failed to start runner.");
 
-    let mut account = sequencer.account(0);
-    account.set_block_id(BlockId::Tag(BlockTag::Pending));
+    let world = setup::setup(&sequencer).await.unwrap();
 
-    let txn_config = TxnConfig::init_wait();
+    let config = setup::load_config();
+    let (migration, _) = setup::setup_migration(&config, "dojo_examples").unwrap();
 
-    let declarers = setup::get_declarers_from_sequencer(&sequencer).await;
+    let provider = sequencer.provider();
+    let world_reader = WorldContractReader::new(world.address, &provider)
+        .with_block(BlockId::Tag(BlockTag::Pending));
 
-    let output = execute_strategy(&ws, &migration, &account, txn_config, &declarers).await.unwrap();
+    let models = world_reader.registered_models().await.unwrap();
+    assert_eq!(models.len(), migration.models.len());
 
-    let world_address = migration.world_address;
-    let world = WorldContract::new(world_address, account);
+    for model in &migration.models {
+        let registered = models
+            .iter()
+            .find(|m| m.tag == model.diff.tag)
+            .unwrap_or_else(|| panic!("{} should be registered", model.diff.tag));
 
-    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
-    let (grant, revoke) =
-        find_authorization_diff(&config.ui(), &world, &diff, Some(&output), &default_namespace)
-            .await
-            .unwrap();
+        assert_eq!(registered.selector, compute_selector_from_tag(&model.diff.tag));
+        assert_eq!(registered.class_hash, model.diff.local_class_hash);
+    }
+}
 
-    let res = auto_authorize(&ws, &world, &txn_config, &default_namespace, &grant, &revoke).await;
-    assert!(res.is_ok());
+#[tokio::test(flavor = "multi_thread")]
+async fn oversized_class_blocks_strict_migration() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer: _sequencer, account, mut migration, ws, .. } =
+        MigrationTest::setup(&config).await;
 
-    let provider = sequencer.provider();
-    let world_reader = WorldContractReader::new(output.world_address, &provider);
+    // Only the first contract is migrated, with a maximum size just below the one of its class.
+    let contract = migration.contracts.first().expect("Expected at least one contract");
+    let sizes = migration.class_sizes().unwrap();
+    let (tag, size) = sizes.iter().find(|(tag, _)| *tag == contract.diff.tag).unwrap().clone();
 
-    // check contract metadata
-    for c in migration.contracts {
-        let contract_address =
-            get_contract_address_from_reader(&world_reader, c.diff.tag.clone()).await.unwrap();
+    migration.max_class_size = size - 1;
+    migration.strict_class_size = true;
+    migration.world = None;
+    migration.base = None;
+    migration.models.clear();
+    migration.contracts.truncate(1);
 
-        let contract = manifest.contracts.iter().find(|a| a.inner.tag == c.diff.tag).unwrap();
+    let res = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None).await;
 
-        for resource in &contract.inner.writes {
-            let resource_type = ResourceType::from_str(resource).unwrap();
+    let err = res.expect_err("Migration should be blocked by the class size check");
+    assert!(err.to_string().contains(&format!("Class of `{tag}`")));
+}
 
-            let selector = match resource_type {
-                ResourceType::Model(tag) => compute_selector_from_tag(&tag),
-                ResourceType::Contract(tag) => compute_selector_from_tag(&tag),
-                ResourceType::Namespace(ns) => compute_bytearray_hash(&ns),
-                ResourceType::Selector(s) => s,
-            };
+#[tokio::test(flavor = "multi_thread")]
+async fn stale_artifact_is_reported() {
+    let config = setup::load_config();
+    let MigrationTest { sequencer, account, mut migration, ws, .. } =
+        MigrationTest::setup(&config).await;
 
-            let contract_address = ContractAddress(contract_address);
-            let is_writer =
-                world_reader.is_writer(&selector, &contract_address).call().await.unwrap();
-            assert!(is_writer);
-        }
-    }
+    execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None).await.unwrap();
+
+    let provider = sequencer.provider();
+    assert!(check_artifact_freshness(&migration, &provider).await.unwrap().is_empty());
+
+    // The artifact of the first model is replaced by the one of another model, as if it had
+    // been rebuilt without updating the manifest.
+    assert!(migration.models.len() > 1, "Expected at least two models");
+    let tag = migration.models[0].diff.tag.clone();
+    migration.models[0].artifact_path = migration.models[1].artifact_path.clone();
+
+    let stale = check_artifact_freshness(&migration, &provider).await.unwrap();
+
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].tag, tag);
+    assert_eq!(stale[0].expected_class_hash, migration.models[0].diff.local_class_hash);
+    assert_eq!(stale[0].artifact_class_hash, migration.models[1].diff.local_class_hash);
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn migration_with_mismatching_world_address_and_seed() {
+async fn mismatching_layout_version_blocks_strict_upgrade() {
+    let sequencer =
+        KatanaRunner::new_with_config(KatanaRunnerConfig { n_accounts: 10, ..Default::default() })
+            .expect("Failed to start runner.");
+
+    setup::setup(&sequencer).await.unwrap();
+
     let config = setup::load_config();
     let ws = setup::setup_ws(&config);
 
+    let (mut migration, _) = setup::setup_migration(&config, "dojo_examples").unwrap();
+    migration.strict_layout = true;
+
+    // Pretend the first contract gets a new class expecting another storage layout.
+    let contract = migration.contracts.first_mut().expect("Expected at least one contract");
+    contract.diff.local_class_hash = Felt::ONE;
+    contract.expected_layout_version = Some(DEFAULT_LAYOUT_VERSION + 1);
+
+    let mut account = sequencer.account(0);
+    account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+    let res = execute_strategy(&ws, &migration, &account, TxnConfig::init_wait(), &[], None).await;
+
+    let err = res.expect_err("Upgrade should be blocked by the layout version check");
+    assert!(err.to_string().contains("storage layout version"));
+}
+
+#[test]
+fn validate_artifacts_reports_missing_artifact() {
+    let config = setup::load_config();
+
     let base_dir = config.manifest_path().parent().unwrap().to_path_buf();
     let target_dir = base_dir.join("target").join("dev");
 
-    let default_namespace = get_default_namespace_from_ws(&ws).unwrap();
+    let (strategy, _) = setup::setup_migration(&config, "dojo_examples").unwrap();
 
-    let (strategy, _) = prepare_migration_with_world_and_seed(
-        base_dir,
-        target_dir,
-        Some(Felt::ONE),
-        "sozo_test",
-        &default_namespace,
-    )
-    .unwrap();
+    assert!(strategy.validate_artifacts(&target_dir).is_ok());
 
-    // The strategy.world has it's address set with the seed directly, and not
-    // from the world address provided by the user.
-    assert_ne!(strategy.world_address, strategy.world.unwrap().contract_address);
+    let removed = strategy.models.first().expect("Expected at least one model").clone();
+    std::fs::remove_file(&removed.artifact_path).unwrap();
+
+    let missing = strategy.validate_artifacts(&target_dir).unwrap_err();
+
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].tag, removed.diff.tag);
+    assert_eq!(missing[0].path, removed.artifact_path);
 }
 
-/// Get the hash from a IPFS URI
+/// Get the hash from a IPFS URI, whatever the version of its CID.
 ///
 /// # Arguments
 ///
@@ -462,42 +2513,49 @@ fn get_hash_from_uri(uri: &str) -> String {
     }
 }
 
-/// Check a metadata field which refers to a file.
+/// Check a metadata field which refers to a file, fetching the uploaded content from the storage
+/// declared by the scheme of its URI.
 ///
 /// # Arguments
 ///
-/// * `client` - a IPFS client.
-/// * `uri` - the IPFS URI of the abi field.
+/// * `client` - a IPFS client, used for `ipfs://` URIs.
+/// * `uri` - the `ipfs://` or `ar://` URI of the field.
 /// * `expected_uri` - the URI of the expected file.
 /// * `field_name` - the field name.
 /// * `tag` - the tag of the element linked to this field.
+/// * `encoding` - the encoding of the uploaded content.
 async fn check_file_field(
     client: &HyperBackend,
     uri: &Uri,
     expected_uri: &Uri,
     field_name: String,
     tag: &String,
+    encoding: Option<ContentEncoding>,
 ) {
-    if let Uri::Ipfs(uri) = uri {
-        let resource_data = get_ipfs_resource_data(client, tag, uri).await;
-        assert!(!resource_data.is_empty(), "{field_name} IPFS artifact for {} is empty", tag);
-
-        if let Uri::File(f) = expected_uri {
-            let file_content = std::fs::read_to_string(f).unwrap();
-            let resource_content = std::str::from_utf8(&resource_data).unwrap_or_else(|_| {
-                panic!("Unable to stringify resource data for field '{}' of {}", field_name, tag)
-            });
-
-            assert!(
-                file_content.eq(&resource_content),
-                "local '{field_name}' content differs from the one uploaded on IPFS for {}",
-                tag
-            );
-        } else {
-            panic!("The field '{field_name}' of {} is not a file (Should never happen !)", tag);
-        }
+    let resource_data = match uri {
+        Uri::Ipfs(ipfs) => get_ipfs_resource_data(client, tag, ipfs).await,
+        Uri::Arweave(_) => get_arweave_resource_data(tag, uri).await,
+        _ => panic!("The '{field_name}' field is not an uploaded artifact for {}", tag),
+    };
+    assert!(!resource_data.is_empty(), "{field_name} artifact at {uri} for {} is empty", tag);
+
+    let resource_data = decode_artifact(&resource_data, encoding).unwrap_or_else(|_| {
+        panic!("Unable to decode resource data for field '{}' of {}", field_name, tag)
+    });
+
+    if let Uri::File(f) = expected_uri {
+        let file_content = std::fs::read_to_string(f).unwrap();
+        let resource_content = std::str::from_utf8(&resource_data).unwrap_or_else(|_| {
+            panic!("Unable to stringify resource data for field '{}' of {}", field_name, tag)
+        });
+
+        assert!(
+            file_content.eq(&resource_content),
+            "local '{field_name}' content differs from the one uploaded at {uri} for {}",
+            tag
+        );
     } else {
-        panic!("The '{field_name}' field is not an IPFS artifact for {}", tag);
+        panic!("The field '{field_name}' of {} is not a file (Should never happen !)", tag);
     }
 }
 
@@ -555,6 +2613,25 @@ async fn get_ipfs_resource_data(client: &HyperBackend, tag: &String, uri: &Strin
     res.unwrap()
 }
 
+/// Read the content of a resource uploaded on Arweave, from the default gateway.
+///
+/// # Arguments
+///
+/// * `tag` - the tag of the element linked to the resource.
+/// * `uri` - the `ar://` URI of the resource.
+///
+/// # Returns
+///
+/// The resource content as bytes.
+async fn get_arweave_resource_data(tag: &String, uri: &Uri) -> Vec<u8> {
+    let id = uri.arweave_id().unwrap_or_else(|| panic!("Malformed Arweave URI {uri} for {tag}"));
+
+    let res = reqwest::get(format!("{ARWEAVE_GATEWAY_URL}/{id}")).await;
+    assert!(res.is_ok(), "Unable to read the Arweave artifact {} for {}", uri, tag);
+
+    res.unwrap().bytes().await.unwrap().to_vec()
+}
+
 /// Check the validity of artifact metadata fields.
 ///
 /// # Arguments
@@ -575,13 +2652,22 @@ async fn check_artifact_fields(
     assert!(metadata.abi.is_some(), "'abi' field not set for {}", tag);
     let abi = metadata.abi.as_ref().unwrap();
     let expected_abi = expected_metadata.abi.as_ref().unwrap();
-    check_file_field(client, abi, expected_abi, "abi".to_string(), tag).await;
+    check_file_field(client, abi, expected_abi, "abi".to_string(), tag, metadata.content_encoding)
+        .await;
 
     // For now source are not expended, uncomment when https://github.com/dojoengine/dojo/issues/2137 is fixed.
     // assert!(metadata.source.is_some(), "'source' field not set for {}", tag);
     // let source = metadata.source.as_ref().unwrap();
     // let expected_source = expected_metadata.source.as_ref().unwrap();
-    // check_file_field(client, source, expected_source, "source".to_string(), tag).await;
+    // check_file_field(
+    //     client,
+    //     source,
+    //     expected_source,
+    //     "source".to_string(),
+    //     tag,
+    //     metadata.content_encoding,
+    // )
+    // .await;
 }
 
 /// Check the validity of a IPFS artifact metadata.
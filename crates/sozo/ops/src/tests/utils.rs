@@ -1,9 +1,13 @@
+use cainome::cairo_serde::ByteArray;
 use dojo_test_utils::migration::copy_spawn_and_move_db;
+use dojo_utils::{TransactionExt, TransactionWaiter, TxnConfig};
+use dojo_world::contracts::abi::world::ResourceMetadata;
+use dojo_world::contracts::naming::compute_selector_from_tag;
 use dojo_world::contracts::world::WorldContract;
 use dojo_world::contracts::WorldContractReader;
 use katana_runner::{KatanaRunner, KatanaRunnerConfig};
 use starknet::accounts::ConnectedAccount;
-use starknet::core::types::{BlockId, BlockTag, Felt};
+use starknet::core::types::{BlockId, BlockTag, Felt, ReceiptBlock};
 
 use crate::test_utils::setup;
 use crate::utils;
@@ -100,3 +104,68 @@ fn parse_block_id_latest() {
 fn parse_block_id_number() {
     assert!(utils::parse_block_id("42".to_string()).unwrap() == BlockId::Number(42));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn read_metadata_at_past_blocks() {
+    let seq_config = KatanaRunnerConfig::default().with_db_dir(copy_spawn_and_move_db().as_str());
+    let sequencer = KatanaRunner::new_with_config(seq_config).expect("Failed to start runner.");
+
+    let world = setup::setup_with_world(&sequencer).await.unwrap();
+    let resource_id = compute_selector_from_tag("dojo_examples-Moves");
+
+    let mut blocks = vec![];
+    for uri in ["ipfs://first", "ipfs://second"] {
+        let metadata_uri = ByteArray::from_string(uri).unwrap();
+        let res = world
+            .set_metadata(&ResourceMetadata { resource_id, metadata_uri })
+            .send_with_cfg(&TxnConfig::default())
+            .await
+            .unwrap();
+
+        let receipt =
+            TransactionWaiter::new(res.transaction_hash, world.account.provider()).await.unwrap();
+        let ReceiptBlock::Block { block_number, .. } = receipt.block else {
+            panic!("set_metadata tx not mined");
+        };
+
+        blocks.push(block_number);
+    }
+
+    let reader = WorldContractReader::new(world.address, world.account.provider());
+
+    let first = reader.metadata_at(resource_id, blocks[0]).await.unwrap();
+    assert_eq!(first.to_string().unwrap(), "ipfs://first");
+
+    let second = reader.metadata_at(resource_id, blocks[1]).await.unwrap();
+    assert_eq!(second.to_string().unwrap(), "ipfs://second");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn world_metadata_cid_is_only_read_from_ipfs_uris() {
+    let seq_config = KatanaRunnerConfig::default().with_db_dir(copy_spawn_and_move_db().as_str());
+    let sequencer = KatanaRunner::new_with_config(seq_config).expect("Failed to start runner.");
+
+    let world = setup::setup_with_world(&sequencer).await.unwrap();
+    let reader = WorldContractReader::new(world.address, world.account.provider())
+        .with_block(BlockId::Tag(BlockTag::Pending));
+
+    for (uri, cid) in [
+        ("ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U", None),
+        ("https://example.com/world.json", None),
+        (
+            "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG/",
+            Some("QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+        ),
+    ] {
+        let metadata_uri = ByteArray::from_string(uri).unwrap();
+        let res = world
+            .set_metadata(&ResourceMetadata { resource_id: Felt::ZERO, metadata_uri })
+            .send_with_cfg(&TxnConfig::default())
+            .await
+            .unwrap();
+        TransactionWaiter::new(res.transaction_hash, world.account.provider()).await.unwrap();
+
+        let read = reader.world_metadata_cid().await.unwrap();
+        assert_eq!(read.as_deref(), cid, "unexpected CID read from {uri}");
+    }
+}
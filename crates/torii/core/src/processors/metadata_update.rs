@@ -6,7 +6,7 @@ use base64::engine::general_purpose;
 use base64::Engine as _;
 use cainome::cairo_serde::{ByteArray, CairoSerde, Zeroable};
 use dojo_world::contracts::world::WorldContractReader;
-use dojo_world::metadata::WorldMetadata;
+use dojo_world::metadata::{WorldMetadata, ARWEAVE_GATEWAY_URL};
 use dojo_world::uri::Uri;
 use reqwest::Client;
 use starknet::core::types::{Event, Felt};
@@ -89,7 +89,7 @@ async fn try_retrieve(mut db: Sql, resource: Felt, uri_str: String) {
             info!(
                 target: LOG_TARGET,
                 resource = %format!("{:#x}", resource),
-                "Updated resource metadata from ipfs."
+                "Updated resource metadata."
             );
         }
         Err(e) => {
@@ -105,10 +105,8 @@ async fn try_retrieve(mut db: Sql, resource: Felt, uri_str: String) {
 }
 
 async fn metadata(uri_str: String) -> Result<(WorldMetadata, Option<String>, Option<String>)> {
-    let uri = Uri::Ipfs(uri_str);
-    let cid = uri.cid().ok_or("Uri is malformed").map_err(Error::msg)?;
-
-    let bytes = fetch_content(cid, MAX_RETRY).await?;
+    let uri = Uri::from_string(&uri_str)?;
+    let bytes = fetch_content(&uri, MAX_RETRY).await?;
     let metadata: WorldMetadata = serde_json::from_str(std::str::from_utf8(&bytes)?)?;
 
     let icon_img = fetch_image(&metadata.icon_uri).await;
@@ -119,7 +117,7 @@ async fn metadata(uri_str: String) -> Result<(WorldMetadata, Option<String>, Opt
 
 async fn fetch_image(image_uri: &Option<Uri>) -> Option<String> {
     if let Some(uri) = image_uri {
-        let data = fetch_content(uri.cid()?, MAX_RETRY).await.ok()?;
+        let data = fetch_content(uri, MAX_RETRY).await.ok()?;
         let encoded = general_purpose::STANDARD.encode(data);
         return Some(encoded);
     }
@@ -127,9 +125,21 @@ async fn fetch_image(image_uri: &Option<Uri>) -> Option<String> {
     None
 }
 
-async fn fetch_content(cid: &str, mut retries: u8) -> Result<Bytes> {
+/// Returns the URL the content referenced by `uri` is served at, depending on its scheme.
+fn content_url(uri: &Uri) -> Option<String> {
+    match uri {
+        Uri::Ipfs(_) => uri.cid().map(|cid| format!("{IPFS_URL}{cid}")),
+        Uri::Arweave(_) => uri.arweave_id().map(|id| format!("{ARWEAVE_GATEWAY_URL}/{id}")),
+        Uri::Http(url) => Some(url.to_string()),
+        Uri::File(_) => None,
+    }
+}
+
+async fn fetch_content(uri: &Uri, mut retries: u8) -> Result<Bytes> {
+    let url = content_url(uri).ok_or_else(|| Error::msg(format!("Uri {uri} can't be fetched")))?;
+
     while retries > 0 {
-        let response = Client::new().get(format!("{IPFS_URL}{}", cid)).send().await;
+        let response = Client::new().get(&url).send().await;
 
         match response {
             Ok(response) => return response.bytes().await.map_err(|e| e.into()),
@@ -147,8 +157,33 @@ async fn fetch_content(cid: &str, mut retries: u8) -> Result<Bytes> {
         }
     }
 
-    Err(Error::msg(format!(
-        "Failed to pull data from IPFS after {} attempts, cid: {}",
-        MAX_RETRY, cid
-    )))
+    Err(Error::msg(format!("Failed to pull data after {} attempts, uri: {}", MAX_RETRY, uri)))
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_world::uri::Uri;
+
+    use super::content_url;
+
+    #[test]
+    fn content_url_depends_on_the_uri_scheme() {
+        let ipfs =
+            Uri::from_string("ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG").unwrap();
+        assert_eq!(
+            content_url(&ipfs).unwrap(),
+            "https://cartridge.infura-ipfs.io/ipfs/QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+        );
+
+        let arweave = Uri::from_string("ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U").unwrap();
+        assert_eq!(
+            content_url(&arweave).unwrap(),
+            "https://arweave.net/bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U"
+        );
+
+        let http = Uri::from_string("https://example.com/metadata.json").unwrap();
+        assert_eq!(content_url(&http).unwrap(), "https://example.com/metadata.json");
+
+        assert!(content_url(&Uri::from_string("file:///tmp/metadata.json").unwrap()).is_none());
+    }
 }